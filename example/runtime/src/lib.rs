@@ -26,7 +26,7 @@ use constants::{currency::*, time::*};
 
 use dev_parachain_primitives::*;
 use sp_api::impl_runtime_apis;
-use sp_core::OpaqueMetadata;
+use sp_core::{OpaqueMetadata, U256};
 use sp_runtime::{
     create_runtime_str, generic, impl_opaque_keys,
     traits::{AccountIdLookup, BlakeTwo256, Block as BlockT},
@@ -608,6 +608,79 @@ impl_runtime_apis! {
                 amount_1_min
             )
         }
+
+        fn get_estimate_lptoken_single_asset(
+            asset_0: AssetId,
+            asset_1: AssetId,
+            amount_0: AssetBalance,
+        ) -> AssetBalance {
+            ZenlinkProtocol::get_estimate_lptoken_single_asset(asset_0, asset_1, amount_0)
+        }
+
+        fn get_estimate_remove_liquidity_single_asset(
+            asset_0: AssetId,
+            asset_1: AssetId,
+            liquidity: AssetBalance,
+        ) -> AssetBalance {
+            ZenlinkProtocol::get_estimate_remove_liquidity_single_asset(asset_0, asset_1, liquidity)
+        }
+
+        fn get_price_cumulative(
+            asset_in: AssetId,
+            asset_out: AssetId
+        ) -> Option<U256> {
+            ZenlinkProtocol::get_price_cumulative(asset_in, asset_out)
+        }
+
+        fn get_best_split(
+            amount_in: AssetBalance,
+            paths: Vec<Vec<AssetId>>
+        ) -> (Vec<u32>, AssetBalance) {
+            ZenlinkProtocol::get_best_split(amount_in, &paths)
+        }
+
+        fn get_lbp_weights(asset_0: AssetId, asset_1: AssetId) -> Option<(u128, u128)> {
+            ZenlinkProtocol::get_lbp_weights(asset_0, asset_1)
+        }
+
+        fn get_lbp_spot_price(asset_in: AssetId, asset_out: AssetId) -> AssetBalance {
+            ZenlinkProtocol::get_lbp_spot_price(asset_in, asset_out)
+        }
+
+        fn get_price_impact_by_path(
+            amount_in: AssetBalance,
+            path: Vec<AssetId>
+        ) -> Vec<AssetBalance> {
+            ZenlinkProtocol::get_price_impact_by_path(amount_in, &path).unwrap_or_default()
+        }
+
+        fn get_pending_farm_rewards(farm_id: u32, owner: AccountId) -> AssetBalance {
+            ZenlinkProtocol::get_pending_farm_rewards(farm_id, &owner)
+        }
+    }
+
+    impl zenlink_protocol_runtime_api::ZenlinkConcentratedApi<Block, AccountId> for Runtime {
+        fn get_concentrated_positions(
+            owner: AccountId
+        ) -> Vec<ConcentratedPositionInfo<AssetBalance>> {
+            ZenlinkProtocol::get_concentrated_positions(&owner)
+        }
+
+        fn get_concentrated_amount_out(
+            asset_in: AssetId,
+            asset_out: AssetId,
+            amount_in: AssetBalance
+        ) -> AssetBalance {
+            ZenlinkProtocol::concentrated_amount_out(asset_in, asset_out, amount_in)
+        }
+
+        fn get_concentrated_amount_in(
+            asset_in: AssetId,
+            asset_out: AssetId,
+            amount_out: AssetBalance
+        ) -> AssetBalance {
+            ZenlinkProtocol::concentrated_amount_in(asset_in, asset_out, amount_out)
+        }
     }
 
     impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<
@@ -2,20 +2,25 @@
 // Licensed under GPL-3.0.
 
 pub use zenlink_protocol::{
-    make_x2_location, AssetBalance, AssetId, MultiAssetsHandler, PairInfo, TransactorAdaptor,
-    TrustedParas, ZenlinkMultiAssets,
+    make_x2_location, AssetBalance, AssetId, ConcentratedPositionInfo, MultiAssetsHandler,
+    PairInfo, TransactorAdaptor, TrustedParas, VerifySignature, ZenlinkMultiAssets,
 };
 
 use super::{
-    parameter_types, vec, AccountId, AccountId32, AccountId32Aliases, Balances, Event, Get,
-    MultiLocation, NetworkId, PalletId, Parachain, ParachainInfo, Parent, Runtime, ShouldExecute,
-    Sibling, SiblingParachainConvertsVia, Vec, Weight, Xcm, XcmConfig, XcmExecutor,
-    ZenlinkProtocol, X1, X2,
+    parameter_types, vec, AccountId, AccountId32, AccountId32Aliases, Balance, Balances, Event,
+    Get, MultiLocation, NetworkId, PalletId, Parachain, ParachainInfo, Parent, Runtime,
+    ShouldExecute, Sibling, SiblingParachainConvertsVia, Signature, Timestamp, Vec, Weight, Xcm,
+    XcmConfig, XcmExecutor, ZenlinkProtocol, DOLLARS, X1, X2,
 };
 
 parameter_types! {
     pub const ZenlinkPalletId: PalletId = PalletId(*b"/zenlink");
     pub const GetExchangeFee: (u32, u32) = (3, 1000);   // 0.3%
+    pub const MaxSwapHops: u32 = 3;
+    pub const MaxDcaExecutionsPerBlock: u32 = 16;
+    pub const MaxPriceOrderChecksPerBlock: u32 = 32;
+    pub const PairCreationDeposit: Balance = 10 * DOLLARS;
+    pub const PriceOrderDeposit: Balance = DOLLARS;
     pub SelfParaId: u32 = ParachainInfo::get().into();
 
     // xcm
@@ -77,6 +82,7 @@ pub type ZenlinkLocationToAccountId = (
 
 use super::Assets;
 use frame_support::dispatch::{DispatchError, DispatchResult};
+use frame_system::{EnsureRoot, EnsureSigned};
 use pallet_assets::ExternalAssetHandler;
 use sp_std::marker::PhantomData;
 use zenlink_protocol::LocalAssetHandler;
@@ -136,6 +142,18 @@ impl zenlink_protocol::Config for Runtime {
     type GetExchangeFee = GetExchangeFee;
     type MultiAssetsHandler = MultiAssets;
     type PalletId = ZenlinkPalletId;
+    type ControlOrigin = EnsureRoot<AccountId>;
+    type CreatePairOrigin = EnsureSigned<AccountId>;
+    type Currency = Balances;
+    type PairCreationDeposit = PairCreationDeposit;
+    type EmergencyOrigin = EnsureRoot<AccountId>;
+    type Time = Timestamp;
+    type OrderSignatureVerifier = VerifySignature<Signature>;
+    type FlashSwapHandler = ();
+    type MaxSwapHops = MaxSwapHops;
+    type MaxDcaExecutionsPerBlock = MaxDcaExecutionsPerBlock;
+    type MaxPriceOrderChecksPerBlock = MaxPriceOrderChecksPerBlock;
+    type PriceOrderDeposit = PriceOrderDeposit;
     type SelfParaId = SelfParaId;
 
     type TargetChains = ZenlinkRegistedParaChains;
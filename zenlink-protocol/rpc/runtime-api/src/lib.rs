@@ -6,8 +6,9 @@
 #![allow(clippy::unnecessary_mut_passed)]
 
 use codec::Codec;
+use sp_core::U256;
 use sp_std::vec::Vec;
-use zenlink_protocol::{AssetBalance, AssetId, PairInfo};
+use zenlink_protocol::{AssetBalance, AssetId, ConcentratedPositionInfo, PairInfo};
 
 sp_api::decl_runtime_apis! {
      pub trait ZenlinkProtocolApi<AccountId>
@@ -44,5 +45,65 @@ sp_api::decl_runtime_apis! {
             amount_0_min: AssetBalance,
             amount_1_min: AssetBalance,
         ) -> AssetBalance;
+
+        fn get_estimate_lptoken_single_asset(
+            asset_0: AssetId,
+            asset_1: AssetId,
+            amount_0: AssetBalance,
+        ) -> AssetBalance;
+
+        fn get_estimate_remove_liquidity_single_asset(
+            asset_0: AssetId,
+            asset_1: AssetId,
+            liquidity: AssetBalance,
+        ) -> AssetBalance;
+
+        //cumulative price of asset_in quoted in asset_out
+        fn get_price_cumulative(asset_in: AssetId, asset_out: AssetId) -> Option<U256>;
+
+        //best split of the sell amount across the paths, as the proportions of the paths
+        fn get_best_split(
+            amount_in: AssetBalance,
+            paths: Vec<Vec<AssetId>>
+        ) -> (Vec<u32>, AssetBalance);
+
+        //current weights of the assets in the liquidity bootstrapping pool
+        fn get_lbp_weights(asset_0: AssetId, asset_1: AssetId) -> Option<(u128, u128)>;
+
+        //spot price of asset_in quoted in asset_out in the liquidity bootstrapping pool
+        fn get_lbp_spot_price(asset_in: AssetId, asset_out: AssetId) -> AssetBalance;
+
+        //price impacts of every hop of selling the amount through the path
+        fn get_price_impact_by_path(
+            amount_in: AssetBalance,
+            path: Vec<AssetId>
+        ) -> Vec<AssetBalance>;
+
+        //rewards of the farm accrued to the owner and not claimed yet
+        fn get_pending_farm_rewards(farm_id: u32, owner: AccountId) -> AssetBalance;
+     }
+
+     pub trait ZenlinkConcentratedApi<AccountId>
+     where
+        AccountId: Codec,
+        AssetBalance: Codec
+     {
+        fn get_concentrated_positions(
+            owner: AccountId
+        ) -> Vec<ConcentratedPositionInfo<AssetBalance>>;
+
+        //sell amount asset price in the concentrated liquidity pool
+        fn get_concentrated_amount_out(
+            asset_in: AssetId,
+            asset_out: AssetId,
+            amount_in: AssetBalance
+        ) -> AssetBalance;
+
+        //buy amount asset price in the concentrated liquidity pool
+        fn get_concentrated_amount_in(
+            asset_in: AssetId,
+            asset_out: AssetId,
+            amount_out: AssetBalance
+        ) -> AssetBalance;
      }
 }
@@ -11,11 +11,20 @@ use jsonrpc_derive::rpc;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_rpc::number::NumberOrHex;
-use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use sp_runtime::{
+    generic::BlockId,
+    traits::{Block as BlockT, NumberFor, Saturating},
+    SaturatedConversion,
+};
 use std::sync::Arc;
 
-use zenlink_protocol::{AssetBalance, AssetId, PairInfo};
-use zenlink_protocol_runtime_api::ZenlinkProtocolApi as ZenlinkProtocolRuntimeApi;
+use zenlink_protocol::{
+    calculate_twap_amount, AssetBalance, AssetId, ConcentratedPositionInfo, PairInfo,
+};
+use zenlink_protocol_runtime_api::{
+    ZenlinkConcentratedApi as ZenlinkConcentratedRuntimeApi,
+    ZenlinkProtocolApi as ZenlinkProtocolRuntimeApi,
+};
 
 #[rpc]
 pub trait ZenlinkProtocolApi<BlockHash, AccountId> {
@@ -83,6 +92,74 @@ pub trait ZenlinkProtocolApi<BlockHash, AccountId> {
         amount_1_min: AssetBalance,
         at: Option<BlockHash>,
     ) -> Result<NumberOrHex>;
+
+    #[rpc(name = "zenlinkProtocol_getEstimateLptokenSingleAsset")]
+    fn get_estimate_lptoken_single_asset(
+        &self,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        amount_0: AssetBalance,
+        at: Option<BlockHash>,
+    ) -> Result<NumberOrHex>;
+
+    #[rpc(name = "zenlinkProtocol_getEstimateRemoveLiquiditySingleAsset")]
+    fn get_estimate_remove_liquidity_single_asset(
+        &self,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        liquidity: AssetBalance,
+        at: Option<BlockHash>,
+    ) -> Result<NumberOrHex>;
+
+    #[rpc(name = "zenlinkProtocol_getTwapPrice")]
+    fn get_twap_price(
+        &self,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: AssetBalance,
+        window: u32,
+        at: Option<BlockHash>,
+    ) -> Result<NumberOrHex>;
+
+    #[rpc(name = "zenlinkProtocol_getBestSplit")]
+    fn get_best_split(
+        &self,
+        amount_in: AssetBalance,
+        paths: Vec<Vec<AssetId>>,
+        at: Option<BlockHash>,
+    ) -> Result<(Vec<u32>, NumberOrHex)>;
+
+    #[rpc(name = "zenlinkProtocol_getLbpWeights")]
+    fn get_lbp_weights(
+        &self,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        at: Option<BlockHash>,
+    ) -> Result<Option<(NumberOrHex, NumberOrHex)>>;
+
+    #[rpc(name = "zenlinkProtocol_getLbpSpotPrice")]
+    fn get_lbp_spot_price(
+        &self,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        at: Option<BlockHash>,
+    ) -> Result<NumberOrHex>;
+
+    #[rpc(name = "zenlinkProtocol_getPriceImpactByPath")]
+    fn get_price_impact_by_path(
+        &self,
+        amount_in: AssetBalance,
+        path: Vec<AssetId>,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<NumberOrHex>>;
+
+    #[rpc(name = "zenlinkProtocol_getPendingFarmRewards")]
+    fn get_pending_farm_rewards(
+        &self,
+        farm_id: u32,
+        account: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<NumberOrHex>;
 }
 
 const RUNTIME_ERROR: i64 = 1;
@@ -288,6 +365,255 @@ where
         .map(|price| price.into())
         .map_err(runtime_error_into_rpc_err)
     }
+
+    fn get_estimate_lptoken_single_asset(
+        &self,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        amount_0: AssetBalance,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<NumberOrHex> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.get_estimate_lptoken_single_asset(&at, asset_0, asset_1, amount_0)
+            .map(|liquidity| liquidity.into())
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn get_estimate_remove_liquidity_single_asset(
+        &self,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        liquidity: AssetBalance,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<NumberOrHex> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.get_estimate_remove_liquidity_single_asset(&at, asset_0, asset_1, liquidity)
+            .map(|amount| amount.into())
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    //time-weighted average sell amount asset price over the last `window` blocks
+    fn get_twap_price(
+        &self,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: AssetBalance,
+        window: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<NumberOrHex> {
+        let api = self.client.runtime_api();
+        let end_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+        let end_number = self
+            .client
+            .number(end_hash)
+            .map_err(runtime_error_into_rpc_err)?
+            .ok_or_else(|| runtime_error_into_rpc_err(end_hash))?;
+
+        let start_number = end_number.saturating_sub(NumberFor::<Block>::from(window));
+        let start_hash = self
+            .client
+            .hash(start_number)
+            .map_err(runtime_error_into_rpc_err)?
+            .ok_or_else(|| runtime_error_into_rpc_err(start_number))?;
+
+        let price_start = api
+            .get_price_cumulative(&BlockId::hash(start_hash), asset_in, asset_out)
+            .map_err(runtime_error_into_rpc_err)?;
+        let price_end = api
+            .get_price_cumulative(&BlockId::hash(end_hash), asset_in, asset_out)
+            .map_err(runtime_error_into_rpc_err)?;
+
+        let elapsed = end_number.saturating_sub(start_number).saturated_into::<u32>();
+
+        Ok(price_start
+            .zip(price_end)
+            .map_or(AssetBalance::default(), |(start, end)| {
+                calculate_twap_amount(start, end, elapsed, amount_in)
+            })
+            .into())
+    }
+
+    fn get_best_split(
+        &self,
+        amount_in: AssetBalance,
+        paths: Vec<Vec<AssetId>>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<(Vec<u32>, NumberOrHex)> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.get_best_split(&at, amount_in, paths)
+            .map(|(proportions, amount_out)| (proportions, amount_out.into()))
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn get_lbp_weights(
+        &self,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<(NumberOrHex, NumberOrHex)>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.get_lbp_weights(&at, asset_0, asset_1)
+            .map(|weights| weights.map(|(weight_0, weight_1)| (weight_0.into(), weight_1.into())))
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn get_lbp_spot_price(
+        &self,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<NumberOrHex> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.get_lbp_spot_price(&at, asset_in, asset_out)
+            .map(|price| price.into())
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn get_price_impact_by_path(
+        &self,
+        amount_in: AssetBalance,
+        path: Vec<AssetId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<NumberOrHex>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.get_price_impact_by_path(&at, amount_in, path)
+            .map(|impacts| impacts.into_iter().map(|impact| impact.into()).collect())
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn get_pending_farm_rewards(
+        &self,
+        farm_id: u32,
+        account: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<NumberOrHex> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.get_pending_farm_rewards(&at, farm_id, account)
+            .map(|rewards| rewards.into())
+            .map_err(runtime_error_into_rpc_err)
+    }
+}
+
+#[rpc]
+pub trait ZenlinkConcentratedApi<BlockHash, AccountId> {
+    #[rpc(name = "zenlinkProtocol_getConcentratedPositions")]
+    fn get_concentrated_positions(
+        &self,
+        owner: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<ConcentratedPositionInfo<NumberOrHex>>>;
+
+    #[rpc(name = "zenlinkProtocol_getConcentratedAmountOut")]
+    fn get_concentrated_amount_out(
+        &self,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: AssetBalance,
+        at: Option<BlockHash>,
+    ) -> Result<NumberOrHex>;
+
+    #[rpc(name = "zenlinkProtocol_getConcentratedAmountIn")]
+    fn get_concentrated_amount_in(
+        &self,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_out: AssetBalance,
+        at: Option<BlockHash>,
+    ) -> Result<NumberOrHex>;
+}
+
+pub struct ZenlinkConcentrated<C, M> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<M>,
+}
+
+impl<C, M> ZenlinkConcentrated<C, M> {
+    pub fn new(client: Arc<C>) -> Self {
+        Self { client, _marker: Default::default() }
+    }
+}
+
+impl<C, Block, AccountId> ZenlinkConcentratedApi<<Block as BlockT>::Hash, AccountId>
+    for ZenlinkConcentrated<C, Block>
+where
+    Block: BlockT,
+    AccountId: Codec,
+    C: Send + Sync + 'static,
+    C: ProvideRuntimeApi<Block>,
+    C: HeaderBackend<Block>,
+    C::Api: ZenlinkConcentratedRuntimeApi<Block, AccountId>,
+{
+    fn get_concentrated_positions(
+        &self,
+        owner: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<ConcentratedPositionInfo<NumberOrHex>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.get_concentrated_positions(&at, owner)
+            .map(|positions| {
+                positions
+                    .into_iter()
+                    .map(|position| ConcentratedPositionInfo {
+                        asset_0: position.asset_0,
+                        asset_1: position.asset_1,
+                        tick_lower: position.tick_lower,
+                        tick_upper: position.tick_upper,
+                        liquidity: position.liquidity.into(),
+                        amount_0: position.amount_0.into(),
+                        amount_1: position.amount_1.into(),
+                        fees_0: position.fees_0.into(),
+                        fees_1: position.fees_1.into(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn get_concentrated_amount_out(
+        &self,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: AssetBalance,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<NumberOrHex> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.get_concentrated_amount_out(&at, asset_in, asset_out, amount_in)
+            .map(|amount| amount.into())
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn get_concentrated_amount_in(
+        &self,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_out: AssetBalance,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<NumberOrHex> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.get_concentrated_amount_in(&at, asset_in, asset_out, amount_out)
+            .map(|amount| amount.into())
+            .map_err(runtime_error_into_rpc_err)
+    }
 }
 
 /// Converts a runtime trap into an RPC error.
@@ -0,0 +1,340 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+//! # Bootstrap Module
+//!
+//! ## Overview
+//!
+//! The fair launch of a trading pair. The contributors deposit either asset of the pair
+//! before the end block, the pair opens at the ratio of all the contributions once both
+//! targets are reached, and the contributors claim the liquidity pro rata. The contributions
+//! are refunded if the targets are not reached before the end block, and the pair is enabled
+//! once all of them are refunded.
+
+use super::*;
+
+#[cfg(test)]
+mod tests;
+
+impl<T: Config> Pallet<T> {
+    /// Put the pair into bootstrap, the pair is created if it doesn't exist.
+    pub fn inner_bootstrap_create(
+        asset_0: AssetId,
+        asset_1: AssetId,
+        target_supply_0: AssetBalance,
+        target_supply_1: AssetBalance,
+        end_block_number: T::BlockNumber,
+    ) -> DispatchResult {
+        let pair = Self::sort_asset_id(asset_0, asset_1);
+        let target_supply =
+            Self::sort_bootstrap_amounts(asset_0, asset_1, (target_supply_0, target_supply_1));
+        ensure!(
+            target_supply.0 > Zero::zero()
+                && target_supply.1 > Zero::zero()
+                && end_block_number > frame_system::Pallet::<T>::block_number(),
+            Error::<T>::InvalidBootstrapParameter
+        );
+        ensure!(!BootstrapParameters::<T>::contains_key(pair), Error::<T>::BootstrapAlreadyExists);
+
+        match Self::lp_metadata(pair) {
+            Some(meta) => {
+                ensure!(
+                    meta.total_supply.is_zero() && meta.status != PairStatus::Delisted,
+                    Error::<T>::PairAlreadyExists
+                );
+                LiquidityMeta::<T>::mutate(pair, |meta| {
+                    if let Some(meta) = meta {
+                        meta.status = PairStatus::Bootstrap;
+                    }
+                });
+            }
+            None => Self::inner_create_pair(pair.0, pair.1, PairStatus::Bootstrap)?,
+        }
+
+        BootstrapParameters::<T>::insert(
+            pair,
+            BootstrapParameter {
+                target_supply,
+                accumulated_supply: (Zero::zero(), Zero::zero()),
+                end_block_number,
+            },
+        );
+
+        Self::deposit_event(Event::BootstrapCreated(
+            pair.0,
+            pair.1,
+            target_supply.0,
+            target_supply.1,
+            end_block_number,
+        ));
+
+        Ok(())
+    }
+
+    /// Contribute `amount_0` of `asset_0` and `amount_1` of `asset_1` to the bootstrap.
+    pub fn inner_bootstrap_contribute(
+        who: &T::AccountId,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        amount_0: AssetBalance,
+        amount_1: AssetBalance,
+    ) -> DispatchResult {
+        let pair = Self::sort_asset_id(asset_0, asset_1);
+        let (amount_0, amount_1) =
+            Self::sort_bootstrap_amounts(asset_0, asset_1, (amount_0, amount_1));
+        ensure!(
+            amount_0 > Zero::zero() || amount_1 > Zero::zero(),
+            Error::<T>::InsufficientInputAmount
+        );
+
+        let meta = Self::lp_metadata(pair).ok_or(Error::<T>::BootstrapNotExists)?;
+        BootstrapParameters::<T>::try_mutate(pair, |parameter| {
+            let parameter = parameter.as_mut().ok_or(Error::<T>::BootstrapNotExists)?;
+            ensure!(
+                meta.status == PairStatus::Bootstrap
+                    && frame_system::Pallet::<T>::block_number() < parameter.end_block_number
+                    && !Self::bootstrap_target_reached(parameter),
+                Error::<T>::BootstrapClosed
+            );
+
+            let (accumulated_0, accumulated_1) = parameter.accumulated_supply;
+            parameter.accumulated_supply = (
+                accumulated_0.checked_add(amount_0).ok_or(Error::<T>::Overflow)?,
+                accumulated_1.checked_add(amount_1).ok_or(Error::<T>::Overflow)?,
+            );
+            let (contribution_0, contribution_1) = Self::bootstrap_contribution((pair, who));
+            let contribution = (
+                contribution_0.checked_add(amount_0).ok_or(Error::<T>::Overflow)?,
+                contribution_1.checked_add(amount_1).ok_or(Error::<T>::Overflow)?,
+            );
+
+            if amount_0 > Zero::zero() {
+                T::MultiAssetsHandler::transfer(pair.0, who, &meta.pair_account, amount_0)?;
+            }
+            if amount_1 > Zero::zero() {
+                T::MultiAssetsHandler::transfer(pair.1, who, &meta.pair_account, amount_1)?;
+            }
+            BootstrapContributions::<T>::insert((pair, who), contribution);
+
+            Self::deposit_event(Event::BootstrapContributed(
+                who.clone(),
+                pair.0,
+                pair.1,
+                amount_0,
+                amount_1,
+            ));
+
+            Ok(())
+        })
+    }
+
+    /// Open the pair at the ratio of the contributions once both targets are reached.
+    pub fn inner_bootstrap_end(asset_0: AssetId, asset_1: AssetId) -> DispatchResult {
+        let pair = Self::sort_asset_id(asset_0, asset_1);
+        let parameter = Self::bootstrap_parameter(pair).ok_or(Error::<T>::BootstrapNotExists)?;
+        ensure!(Self::bootstrap_target_reached(&parameter), Error::<T>::BootstrapTargetNotReached);
+
+        let (accumulated_0, accumulated_1) = parameter.accumulated_supply;
+        let total_liquidity = Self::bootstrap_total_liquidity(accumulated_0, accumulated_1);
+        ensure!(total_liquidity > MINIMUM_LIQUIDITY, Error::<T>::InsufficientLiquidity);
+
+        LiquidityMeta::<T>::try_mutate(pair, |meta| {
+            let meta = meta.as_mut().ok_or(Error::<T>::PairNotExists)?;
+            ensure!(meta.status == PairStatus::Bootstrap, Error::<T>::BootstrapClosed);
+
+            let fee_on = Self::mint_protocol_fee(
+                pair.0,
+                pair.1,
+                Zero::zero(),
+                Zero::zero(),
+                &mut meta.total_supply,
+            )?;
+
+            // The liquidity of the contributors is minted as they claim it.
+            meta.total_supply = total_liquidity;
+            meta.status = PairStatus::Enabled;
+            Self::mutate_liquidity(
+                pair.0,
+                pair.1,
+                &Self::lock_account_id(),
+                MINIMUM_LIQUIDITY,
+                true,
+            )?;
+            Self::update_reserves(meta, pair.0, pair.1, accumulated_0, accumulated_1)?;
+
+            if fee_on {
+                Self::update_k_last(pair.0, pair.1, accumulated_0, accumulated_1);
+            }
+
+            Self::deposit_event(Event::BootstrapEnded(
+                pair.0,
+                pair.1,
+                accumulated_0,
+                accumulated_1,
+                total_liquidity,
+            ));
+
+            Ok(())
+        })
+    }
+
+    /// Claim the liquidity of the contributions of `who` to `recipient` after the pair opens.
+    pub fn inner_bootstrap_claim(
+        who: &T::AccountId,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        let pair = Self::sort_asset_id(asset_0, asset_1);
+        let parameter = Self::bootstrap_parameter(pair).ok_or(Error::<T>::BootstrapNotExists)?;
+        let meta = Self::lp_metadata(pair).ok_or(Error::<T>::PairNotExists)?;
+        ensure!(meta.status != PairStatus::Bootstrap, Error::<T>::BootstrapNotEnded);
+
+        let (contribution_0, contribution_1) = Self::bootstrap_contribution((pair, who));
+        ensure!(
+            contribution_0 > Zero::zero() || contribution_1 > Zero::zero(),
+            Error::<T>::NoBootstrapContribution
+        );
+
+        let liquidity = Self::calculate_bootstrap_liquidity(
+            (contribution_0, contribution_1),
+            parameter.accumulated_supply,
+        );
+        Self::mutate_liquidity(pair.0, pair.1, recipient, liquidity, true)?;
+        BootstrapContributions::<T>::remove((pair, who));
+
+        Self::deposit_event(Event::BootstrapClaimed(
+            who.clone(),
+            recipient.clone(),
+            pair.0,
+            pair.1,
+            contribution_0,
+            contribution_1,
+            liquidity,
+        ));
+
+        Ok(())
+    }
+
+    /// Refund the contributions of `who` after the bootstrap ends without reaching the
+    /// targets. The bootstrap is removed and the pair is enabled after the last refund.
+    pub fn inner_bootstrap_refund(
+        who: &T::AccountId,
+        asset_0: AssetId,
+        asset_1: AssetId,
+    ) -> DispatchResult {
+        let pair = Self::sort_asset_id(asset_0, asset_1);
+        let meta = Self::lp_metadata(pair).ok_or(Error::<T>::BootstrapNotExists)?;
+
+        BootstrapParameters::<T>::try_mutate_exists(pair, |maybe_parameter| {
+            let parameter = maybe_parameter.as_mut().ok_or(Error::<T>::BootstrapNotExists)?;
+            ensure!(
+                meta.status == PairStatus::Bootstrap
+                    && frame_system::Pallet::<T>::block_number() >= parameter.end_block_number
+                    && !Self::bootstrap_target_reached(parameter),
+                Error::<T>::BootstrapNotRefundable
+            );
+
+            let (contribution_0, contribution_1) = Self::bootstrap_contribution((pair, who));
+            ensure!(
+                contribution_0 > Zero::zero() || contribution_1 > Zero::zero(),
+                Error::<T>::NoBootstrapContribution
+            );
+
+            let (accumulated_0, accumulated_1) = parameter.accumulated_supply;
+            parameter.accumulated_supply = (
+                accumulated_0.saturating_sub(contribution_0),
+                accumulated_1.saturating_sub(contribution_1),
+            );
+
+            if contribution_0 > Zero::zero() {
+                T::MultiAssetsHandler::transfer(pair.0, &meta.pair_account, who, contribution_0)?;
+            }
+            if contribution_1 > Zero::zero() {
+                T::MultiAssetsHandler::transfer(pair.1, &meta.pair_account, who, contribution_1)?;
+            }
+            BootstrapContributions::<T>::remove((pair, who));
+
+            Self::deposit_event(Event::BootstrapRefunded(
+                who.clone(),
+                pair.0,
+                pair.1,
+                contribution_0,
+                contribution_1,
+            ));
+
+            if Self::is_bootstrap_refunded(parameter) {
+                *maybe_parameter = None;
+                LiquidityMeta::<T>::mutate(pair, |meta| {
+                    if let Some(meta) = meta {
+                        meta.status = PairStatus::Enabled;
+                    }
+                });
+                Self::deposit_event(Event::PairStatusChanged(pair.0, pair.1, PairStatus::Enabled));
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Whether the bootstrap ends without reaching the targets and all the contributions are
+    /// refunded.
+    pub(crate) fn is_bootstrap_refunded(parameter: &BootstrapParameter<T::BlockNumber>) -> bool {
+        frame_system::Pallet::<T>::block_number() >= parameter.end_block_number
+            && parameter.accumulated_supply.0.is_zero()
+            && parameter.accumulated_supply.1.is_zero()
+    }
+
+    /// The liquidity of the contributions, which are valued at the ratio of all the
+    /// contributions. The liquidity locked on the opening is taken from all of them.
+    pub fn calculate_bootstrap_liquidity(
+        contribution: (AssetBalance, AssetBalance),
+        accumulated_supply: (AssetBalance, AssetBalance),
+    ) -> AssetBalance {
+        let (accumulated_0, accumulated_1) = accumulated_supply;
+        let claimable = Self::bootstrap_total_liquidity(accumulated_0, accumulated_1)
+            .saturating_sub(MINIMUM_LIQUIDITY);
+
+        // share = (contribution_0 / accumulated_0 + contribution_1 / accumulated_1) / 2
+        let numerator = U256::from(contribution.0)
+            .full_mul(U256::from(accumulated_1))
+            .saturating_add(U256::from(contribution.1).full_mul(U256::from(accumulated_0)))
+            .saturating_mul(U512::from(claimable));
+        let denominator = U256::from(accumulated_0)
+            .full_mul(U256::from(accumulated_1))
+            .saturating_mul(U512::from(2));
+
+        numerator
+            .checked_div(denominator)
+            .and_then(|n| TryInto::<AssetBalance>::try_into(n).ok())
+            .unwrap_or_else(Zero::zero)
+    }
+
+    fn bootstrap_total_liquidity(
+        accumulated_0: AssetBalance,
+        accumulated_1: AssetBalance,
+    ) -> AssetBalance {
+        TryInto::<AssetBalance>::try_into(Self::u256_sqrt(
+            U256::from(accumulated_0) * U256::from(accumulated_1),
+        ))
+        .unwrap_or_else(|_| Zero::zero())
+    }
+
+    fn bootstrap_target_reached(parameter: &BootstrapParameter<T::BlockNumber>) -> bool {
+        parameter.accumulated_supply.0 >= parameter.target_supply.0
+            && parameter.accumulated_supply.1 >= parameter.target_supply.1
+    }
+
+    /// The amounts of `asset_0` and `asset_1` in the order of the sorted pair.
+    fn sort_bootstrap_amounts(
+        asset_0: AssetId,
+        asset_1: AssetId,
+        amounts: (AssetBalance, AssetBalance),
+    ) -> (AssetBalance, AssetBalance) {
+        if asset_0 < asset_1 {
+            amounts
+        } else {
+            (amounts.1, amounts.0)
+        }
+    }
+}
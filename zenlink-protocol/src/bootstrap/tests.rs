@@ -0,0 +1,412 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::DispatchError;
+
+use super::{AssetId, Error, MultiAssetsHandler, PairStatus, MINIMUM_LIQUIDITY};
+use crate::mock::*;
+
+const DOT_ASSET_ID: AssetId = AssetId { chain_id: 200, asset_type: LOCAL, asset_index: 2 };
+
+const BTC_ASSET_ID: AssetId = AssetId { chain_id: 300, asset_type: RESERVED, asset_index: 3 };
+
+const ETH_ASSET_ID: AssetId = AssetId { chain_id: 400, asset_type: NATIVE, asset_index: 0 };
+
+const ALICE: u128 = 1;
+const BOB: u128 = 2;
+const CHARLIE: u128 = 3;
+
+const DOT_UNIT: u128 = 1000_000_000_000_000;
+const BTC_UNIT: u128 = 1000_000_00;
+const ETH_UNIT: u128 = 1000_000_000_000;
+
+const PAIR_DOT_BTC: (AssetId, AssetId) = (DOT_ASSET_ID, BTC_ASSET_ID);
+
+/// The bootstrap of DOT-BTC which targets 1000 DOT and 2000 BTC before block 10, created
+/// with the assets in the reversed order.
+fn create_bootstrap() {
+    for who in [ALICE, BOB, CHARLIE].iter() {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, who, 10000 * DOT_UNIT));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, who, 10000 * BTC_UNIT));
+    }
+
+    assert_ok!(DexPallet::bootstrap_create(
+        Origin::root(),
+        BTC_ASSET_ID,
+        DOT_ASSET_ID,
+        2000 * BTC_UNIT,
+        1000 * DOT_UNIT,
+        10
+    ));
+}
+
+#[test]
+fn bootstrap_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_bootstrap();
+
+        let meta = DexPallet::lp_metadata(PAIR_DOT_BTC).unwrap();
+        assert_eq!(meta.status, PairStatus::Bootstrap);
+        let parameter = DexPallet::bootstrap_parameter(PAIR_DOT_BTC).unwrap();
+        assert_eq!(parameter.target_supply, (1000 * DOT_UNIT, 2000 * BTC_UNIT));
+        assert_eq!(DexPallet::lp_pairs(), vec![PAIR_DOT_BTC]);
+
+        assert_noop!(
+            DexPallet::inner_add_liquidity(
+                &ALICE,
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                DOT_UNIT,
+                BTC_UNIT,
+                0,
+                0
+            ),
+            Error::<Test>::PairNotEnabled
+        );
+
+        assert_ok!(DexPallet::bootstrap_contribute(
+            Origin::signed(BOB),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            600 * DOT_UNIT,
+            0,
+            Deadline::BlockNumber(100)
+        ));
+        assert_noop!(
+            DexPallet::bootstrap_end(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID),
+            Error::<Test>::BootstrapTargetNotReached
+        );
+        assert_ok!(DexPallet::bootstrap_contribute(
+            Origin::signed(CHARLIE),
+            BTC_ASSET_ID,
+            DOT_ASSET_ID,
+            2000 * BTC_UNIT,
+            400 * DOT_UNIT,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(
+            DexPallet::bootstrap_contribution((PAIR_DOT_BTC, CHARLIE)),
+            (400 * DOT_UNIT, 2000 * BTC_UNIT)
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &meta.pair_account),
+            1000 * DOT_UNIT
+        );
+
+        // The targets are reached before the end block.
+        assert_noop!(
+            DexPallet::bootstrap_contribute(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                DOT_UNIT,
+                0,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::BootstrapClosed
+        );
+        assert_noop!(
+            DexPallet::bootstrap_claim(
+                Origin::signed(BOB),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::BootstrapNotEnded
+        );
+        assert_noop!(
+            DexPallet::bootstrap_refund(Origin::signed(BOB), DOT_ASSET_ID, BTC_ASSET_ID),
+            Error::<Test>::BootstrapNotRefundable
+        );
+
+        assert_ok!(DexPallet::bootstrap_end(Origin::signed(ALICE), BTC_ASSET_ID, DOT_ASSET_ID));
+        let total_liquidity = 447213595499957;
+        let meta = DexPallet::lp_metadata(PAIR_DOT_BTC).unwrap();
+        assert_eq!(meta.status, PairStatus::Enabled);
+        assert_eq!(meta.total_supply, total_liquidity);
+        assert_eq!((meta.reserve_0, meta.reserve_1), (1000 * DOT_UNIT, 2000 * BTC_UNIT));
+        assert_eq!(
+            DexPallet::lp_ledger((PAIR_DOT_BTC, DexPallet::lock_account_id())),
+            MINIMUM_LIQUIDITY
+        );
+        assert_noop!(
+            DexPallet::bootstrap_end(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID),
+            Error::<Test>::BootstrapClosed
+        );
+
+        // BOB contributes 0.6 of DOT, CHARLIE 0.4 of DOT and all of BTC.
+        assert_ok!(DexPallet::bootstrap_claim(
+            Origin::signed(BOB),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            BOB,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(DexPallet::lp_ledger((PAIR_DOT_BTC, BOB)), 134164078649687);
+        assert_noop!(
+            DexPallet::bootstrap_claim(
+                Origin::signed(BOB),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::NoBootstrapContribution
+        );
+        assert_ok!(DexPallet::bootstrap_claim(
+            Origin::signed(CHARLIE),
+            BTC_ASSET_ID,
+            DOT_ASSET_ID,
+            ALICE,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(DexPallet::lp_ledger((PAIR_DOT_BTC, ALICE)), 313049516849269);
+        assert_eq!(DexPallet::lp_ledger((PAIR_DOT_BTC, CHARLIE)), 0);
+        assert!(134164078649687 + 313049516849269 + MINIMUM_LIQUIDITY <= total_liquidity);
+
+        assert_ok!(DexPallet::inner_swap_exact_tokens_for_tokens(
+            &BOB,
+            DOT_UNIT,
+            0,
+            &[DOT_ASSET_ID, BTC_ASSET_ID],
+            &BOB
+        ));
+    });
+}
+
+#[test]
+fn bootstrap_refund_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_bootstrap();
+
+        assert_ok!(DexPallet::bootstrap_contribute(
+            Origin::signed(BOB),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            600 * DOT_UNIT,
+            1000 * BTC_UNIT,
+            Deadline::BlockNumber(100)
+        ));
+        assert_noop!(
+            DexPallet::bootstrap_refund(Origin::signed(BOB), DOT_ASSET_ID, BTC_ASSET_ID),
+            Error::<Test>::BootstrapNotRefundable
+        );
+
+        System::set_block_number(10);
+        assert_noop!(
+            DexPallet::bootstrap_contribute(
+                Origin::signed(CHARLIE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                400 * DOT_UNIT,
+                1000 * BTC_UNIT,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::BootstrapClosed
+        );
+        assert_noop!(
+            DexPallet::bootstrap_end(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID),
+            Error::<Test>::BootstrapTargetNotReached
+        );
+        assert_noop!(
+            DexPallet::bootstrap_claim(
+                Origin::signed(BOB),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::BootstrapNotEnded
+        );
+        assert_noop!(
+            DexPallet::set_pair_status(
+                Origin::root(),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                PairStatus::Enabled
+            ),
+            Error::<Test>::InvalidPairStatus
+        );
+        assert_noop!(
+            DexPallet::bootstrap_refund(Origin::signed(CHARLIE), DOT_ASSET_ID, BTC_ASSET_ID),
+            Error::<Test>::NoBootstrapContribution
+        );
+
+        assert_ok!(DexPallet::bootstrap_refund(Origin::signed(BOB), BTC_ASSET_ID, DOT_ASSET_ID));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &BOB),
+            10000 * DOT_UNIT
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &BOB),
+            10000 * BTC_UNIT
+        );
+
+        // The bootstrap is removed and the pair is enabled after the last refund.
+        assert!(System::events().iter().any(|record| record.event
+            == Event::Zenlink(crate::Event::PairStatusChanged(
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                PairStatus::Enabled
+            ))));
+        assert_eq!(DexPallet::bootstrap_parameter(PAIR_DOT_BTC), None);
+        assert_eq!(DexPallet::lp_metadata(PAIR_DOT_BTC).unwrap().status, PairStatus::Enabled);
+        assert_noop!(
+            DexPallet::bootstrap_refund(Origin::signed(BOB), DOT_ASSET_ID, BTC_ASSET_ID),
+            Error::<Test>::BootstrapNotExists
+        );
+
+        // The pair can be bootstrapped again.
+        assert_ok!(DexPallet::bootstrap_create(
+            Origin::root(),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            DOT_UNIT,
+            BTC_UNIT,
+            20
+        ));
+        assert_eq!(DexPallet::lp_metadata(PAIR_DOT_BTC).unwrap().status, PairStatus::Bootstrap);
+        assert_ok!(DexPallet::bootstrap_contribute(
+            Origin::signed(BOB),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            DOT_UNIT,
+            BTC_UNIT,
+            Deadline::BlockNumber(100)
+        ));
+        assert_ok!(DexPallet::bootstrap_end(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+    });
+}
+
+#[test]
+fn bootstrap_without_contributions_should_be_removed() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_bootstrap();
+
+        assert_noop!(
+            DexPallet::set_pair_status(
+                Origin::root(),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                PairStatus::Enabled
+            ),
+            Error::<Test>::InvalidPairStatus
+        );
+
+        System::set_block_number(10);
+        assert_ok!(DexPallet::set_pair_status(
+            Origin::root(),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            PairStatus::Enabled
+        ));
+        assert_eq!(DexPallet::bootstrap_parameter(PAIR_DOT_BTC), None);
+        assert!(DexPallet::is_pair_enabled(DOT_ASSET_ID, BTC_ASSET_ID));
+        assert_ok!(DexPallet::inner_add_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            DOT_UNIT,
+            BTC_UNIT,
+            0,
+            0
+        ));
+    });
+}
+
+#[test]
+fn bootstrap_create_should_not_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, 10000 * DOT_UNIT));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, 10000 * BTC_UNIT));
+
+        assert_noop!(
+            DexPallet::bootstrap_create(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                DOT_UNIT,
+                BTC_UNIT,
+                10
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            DexPallet::bootstrap_create(
+                Origin::root(),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                0,
+                BTC_UNIT,
+                10
+            ),
+            Error::<Test>::InvalidBootstrapParameter
+        );
+        assert_noop!(
+            DexPallet::bootstrap_create(
+                Origin::root(),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                DOT_UNIT,
+                BTC_UNIT,
+                1
+            ),
+            Error::<Test>::InvalidBootstrapParameter
+        );
+
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+        assert_ok!(DexPallet::inner_add_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            DOT_UNIT,
+            BTC_UNIT,
+            0,
+            0
+        ));
+        assert_noop!(
+            DexPallet::bootstrap_create(
+                Origin::root(),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                DOT_UNIT,
+                BTC_UNIT,
+                10
+            ),
+            Error::<Test>::PairAlreadyExists
+        );
+
+        // The pair without liquidity is put into bootstrap.
+        assert_ok!(DexPallet::foreign_mint(ETH_ASSET_ID, &ALICE, 10000 * ETH_UNIT));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, ETH_ASSET_ID));
+        assert_ok!(DexPallet::bootstrap_create(
+            Origin::root(),
+            DOT_ASSET_ID,
+            ETH_ASSET_ID,
+            DOT_UNIT,
+            ETH_UNIT,
+            10
+        ));
+        assert_eq!(
+            DexPallet::lp_metadata((DOT_ASSET_ID, ETH_ASSET_ID)).unwrap().status,
+            PairStatus::Bootstrap
+        );
+        assert_noop!(
+            DexPallet::bootstrap_create(
+                Origin::root(),
+                DOT_ASSET_ID,
+                ETH_ASSET_ID,
+                DOT_UNIT,
+                ETH_UNIT,
+                10
+            ),
+            Error::<Test>::BootstrapAlreadyExists
+        );
+    });
+}
@@ -0,0 +1,392 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+//! Tick and square root price math of the concentrated liquidity pools, the prices are the
+//! square roots of `asset_1` quoted in `asset_0` in Q64.96.
+
+use sp_core::{U256, U512};
+
+/// The min tick of a position, whose price is about `2^-128`.
+pub const MIN_TICK: i32 = -887272;
+/// The max tick of a position, whose price is about `2^128`.
+pub const MAX_TICK: i32 = -MIN_TICK;
+/// The square root price of `MIN_TICK`.
+pub const MIN_SQRT_RATIO: U256 = U256([4295128739, 0, 0, 0]);
+/// The square root price of `MAX_TICK`.
+pub const MAX_SQRT_RATIO: U256 =
+    U256([0x5d95_1d52_6398_8d26, 0xefd1_fc6a_5064_8849, 0xfffd_8963, 0]);
+
+const RESOLUTION: usize = 96;
+
+fn q96() -> U256 {
+    U256::one() << RESOLUTION
+}
+
+pub fn q128() -> U256 {
+    U256::one() << 128
+}
+
+fn to_u256(n: U512) -> Option<U256> {
+    if n.bits() > 256 {
+        return None;
+    }
+    let mut bytes = [0u8; 64];
+    n.to_little_endian(&mut bytes);
+
+    Some(U256::from_little_endian(&bytes[..32]))
+}
+
+/// `a * b / denominator` in full precision.
+pub fn mul_div(a: U256, b: U256, denominator: U256) -> Option<U256> {
+    if denominator.is_zero() {
+        return None;
+    }
+
+    to_u256(a.full_mul(b) / U512::from(denominator))
+}
+
+/// `a * b / denominator` in full precision, rounded up.
+pub fn mul_div_rounding_up(a: U256, b: U256, denominator: U256) -> Option<U256> {
+    if denominator.is_zero() {
+        return None;
+    }
+
+    let product = a.full_mul(b);
+    let denominator = U512::from(denominator);
+    let mut result = product / denominator;
+    if !(product % denominator).is_zero() {
+        result += U512::one();
+    }
+
+    to_u256(result)
+}
+
+fn div_rounding_up(a: U256, b: U256) -> Option<U256> {
+    if b.is_zero() {
+        return None;
+    }
+
+    let result = a / b;
+    if (a % b).is_zero() {
+        Some(result)
+    } else {
+        result.checked_add(U256::one())
+    }
+}
+
+/// The square root price of `tick`, which is `sqrt(1.0001^tick) * 2^96`.
+pub fn get_sqrt_ratio_at_tick(tick: i32) -> Option<U256> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return None;
+    }
+
+    // `2^128 / sqrt(1.0001)^(2^i)` of the bit `i` of the absolute tick.
+    const FACTORS: [u128; 19] = [
+        0xfff9_7272_373d_4132_59a4_6990_580e_213a,
+        0xfff2_e50f_5f65_6932_ef12_357c_f3c7_fdcc,
+        0xffe5_caca_7e10_e4e6_1c36_24ea_a094_1cd0,
+        0xffcb_9843_d60f_6159_c9db_5883_5c92_6644,
+        0xff97_3b41_fa98_c081_472e_6896_dfb2_54c0,
+        0xff2e_a164_66c9_6a38_43ec_78b3_26b5_2861,
+        0xfe5d_ee04_6a99_a2a8_11c4_61f1_969c_3053,
+        0xfcbe_86c7_900a_88ae_dcff_c83b_479a_a3a4,
+        0xf987_a725_3ac4_1317_6f2b_074c_f781_5e54,
+        0xf339_2b08_22b7_0005_940c_7a39_8e4b_70f3,
+        0xe715_9475_a2c2_9b74_43b2_9c7f_a6e8_89d9,
+        0xd097_f3bd_fd20_22b8_845a_d8f7_92aa_5825,
+        0xa9f7_4646_2d87_0fdf_8a65_dc1f_90e0_61e5,
+        0x70d8_69a1_56d2_a1b8_90bb_3df6_2baf_32f7,
+        0x31be_135f_97d0_8fd9_8123_1505_542f_cfa6,
+        0x09aa_508b_5b7a_84e1_c677_de54_f3e9_9bc9,
+        0x005d_6af8_dedb_8119_6699_c329_225e_e604,
+        0x0000_2216_e584_f5fa_1ea9_2604_1bed_fe98,
+        0x0000_0000_048a_1703_91f7_dc42_444e_8fa2,
+    ];
+
+    let abs_tick = tick.abs() as u32;
+    let mut ratio = if abs_tick & 0x1 != 0 {
+        U256::from(0xfffc_b933_bd6f_ad37_aa2d_162d_1a59_4001u128)
+    } else {
+        q128()
+    };
+    for (i, factor) in FACTORS.iter().enumerate() {
+        if abs_tick & (0x2 << i) != 0 {
+            ratio = (ratio * U256::from(*factor)) >> 128;
+        }
+    }
+
+    if tick > 0 {
+        ratio = U256::max_value() / ratio;
+    }
+
+    // Round up to Q64.96 so that the price of a tick is never less than the exact one.
+    let sqrt_price = ratio >> 32;
+    if (ratio & U256::from(u32::MAX)).is_zero() {
+        Some(sqrt_price)
+    } else {
+        Some(sqrt_price + 1)
+    }
+}
+
+/// The greatest tick whose square root price is no more than `sqrt_price`.
+pub fn get_tick_at_sqrt_ratio(sqrt_price: U256) -> Option<i32> {
+    if sqrt_price < MIN_SQRT_RATIO || sqrt_price >= MAX_SQRT_RATIO {
+        return None;
+    }
+
+    let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+        if get_sqrt_ratio_at_tick(mid)? <= sqrt_price {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    Some(low)
+}
+
+/// The amount of `asset_0` between two prices of `liquidity`.
+pub fn get_amount_0_delta(
+    sqrt_price_a: U256,
+    sqrt_price_b: U256,
+    liquidity: u128,
+    round_up: bool,
+) -> Option<U256> {
+    let (sqrt_price_a, sqrt_price_b) = if sqrt_price_a > sqrt_price_b {
+        (sqrt_price_b, sqrt_price_a)
+    } else {
+        (sqrt_price_a, sqrt_price_b)
+    };
+    if sqrt_price_a.is_zero() {
+        return None;
+    }
+
+    let numerator_1 = U256::from(liquidity) << RESOLUTION;
+    let numerator_2 = sqrt_price_b - sqrt_price_a;
+
+    if round_up {
+        div_rounding_up(mul_div_rounding_up(numerator_1, numerator_2, sqrt_price_b)?, sqrt_price_a)
+    } else {
+        Some(mul_div(numerator_1, numerator_2, sqrt_price_b)? / sqrt_price_a)
+    }
+}
+
+/// The amount of `asset_1` between two prices of `liquidity`.
+pub fn get_amount_1_delta(
+    sqrt_price_a: U256,
+    sqrt_price_b: U256,
+    liquidity: u128,
+    round_up: bool,
+) -> Option<U256> {
+    let difference = if sqrt_price_a > sqrt_price_b {
+        sqrt_price_a - sqrt_price_b
+    } else {
+        sqrt_price_b - sqrt_price_a
+    };
+
+    if round_up {
+        mul_div_rounding_up(U256::from(liquidity), difference, q96())
+    } else {
+        mul_div(U256::from(liquidity), difference, q96())
+    }
+}
+
+fn get_next_sqrt_price_from_amount_0_rounding_up(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Option<U256> {
+    if amount.is_zero() {
+        return Some(sqrt_price);
+    }
+
+    let numerator_1 = U256::from(liquidity) << RESOLUTION;
+    let product = amount.full_mul(sqrt_price);
+    let denominator = if add {
+        U512::from(numerator_1).checked_add(product)?
+    } else {
+        U512::from(numerator_1).checked_sub(product).filter(|n| !n.is_zero())?
+    };
+
+    let numerator = numerator_1.full_mul(sqrt_price);
+    let mut result = numerator / denominator;
+    if !(numerator % denominator).is_zero() {
+        result += U512::one();
+    }
+
+    to_u256(result)
+}
+
+fn get_next_sqrt_price_from_amount_1_rounding_down(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Option<U256> {
+    if add {
+        let quotient = mul_div(amount, q96(), U256::from(liquidity))?;
+        sqrt_price.checked_add(quotient)
+    } else {
+        let quotient = mul_div_rounding_up(amount, q96(), U256::from(liquidity))?;
+        sqrt_price.checked_sub(quotient).filter(|n| !n.is_zero())
+    }
+}
+
+fn get_next_sqrt_price_from_input(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount_in: U256,
+    zero_for_one: bool,
+) -> Option<U256> {
+    if zero_for_one {
+        get_next_sqrt_price_from_amount_0_rounding_up(sqrt_price, liquidity, amount_in, true)
+    } else {
+        get_next_sqrt_price_from_amount_1_rounding_down(sqrt_price, liquidity, amount_in, true)
+    }
+}
+
+fn get_next_sqrt_price_from_output(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount_out: U256,
+    zero_for_one: bool,
+) -> Option<U256> {
+    if zero_for_one {
+        get_next_sqrt_price_from_amount_1_rounding_down(sqrt_price, liquidity, amount_out, false)
+    } else {
+        get_next_sqrt_price_from_amount_0_rounding_up(sqrt_price, liquidity, amount_out, false)
+    }
+}
+
+/// The result of swapping within a single tick range.
+#[derive(Debug, Eq, PartialEq)]
+pub struct SwapStep {
+    pub sqrt_price_next: U256,
+    pub amount_in: U256,
+    pub amount_out: U256,
+    pub fee_amount: U256,
+}
+
+/// Swap `amount_remaining`, which is the amount in if `exact_input` or else the amount out,
+/// from `sqrt_price_current` towards `sqrt_price_target` with the fee rate of
+/// `fee_numerator / fee_denominator`.
+pub fn compute_swap_step(
+    sqrt_price_current: U256,
+    sqrt_price_target: U256,
+    liquidity: u128,
+    amount_remaining: U256,
+    exact_input: bool,
+    (fee_numerator, fee_denominator): (u32, u32),
+) -> Option<SwapStep> {
+    let zero_for_one = sqrt_price_current >= sqrt_price_target;
+    let fee_numerator = U256::from(fee_numerator);
+    let fee_denominator = U256::from(fee_denominator);
+    let fee_complement = fee_denominator.checked_sub(fee_numerator)?;
+
+    let mut amount_in = U256::zero();
+    let mut amount_out = U256::zero();
+    let sqrt_price_next = if exact_input {
+        let amount_remaining_less_fee = mul_div(amount_remaining, fee_complement, fee_denominator)?;
+        amount_in = if zero_for_one {
+            get_amount_0_delta(sqrt_price_target, sqrt_price_current, liquidity, true)?
+        } else {
+            get_amount_1_delta(sqrt_price_current, sqrt_price_target, liquidity, true)?
+        };
+        if amount_remaining_less_fee >= amount_in {
+            sqrt_price_target
+        } else {
+            get_next_sqrt_price_from_input(
+                sqrt_price_current,
+                liquidity,
+                amount_remaining_less_fee,
+                zero_for_one,
+            )?
+        }
+    } else {
+        amount_out = if zero_for_one {
+            get_amount_1_delta(sqrt_price_target, sqrt_price_current, liquidity, false)?
+        } else {
+            get_amount_0_delta(sqrt_price_current, sqrt_price_target, liquidity, false)?
+        };
+        if amount_remaining >= amount_out {
+            sqrt_price_target
+        } else {
+            get_next_sqrt_price_from_output(
+                sqrt_price_current,
+                liquidity,
+                amount_remaining,
+                zero_for_one,
+            )?
+        }
+    };
+
+    let max = sqrt_price_target == sqrt_price_next;
+    if zero_for_one {
+        if !(max && exact_input) {
+            amount_in = get_amount_0_delta(sqrt_price_next, sqrt_price_current, liquidity, true)?;
+        }
+        if !(max && !exact_input) {
+            amount_out = get_amount_1_delta(sqrt_price_next, sqrt_price_current, liquidity, false)?;
+        }
+    } else {
+        if !(max && exact_input) {
+            amount_in = get_amount_1_delta(sqrt_price_current, sqrt_price_next, liquidity, true)?;
+        }
+        if !(max && !exact_input) {
+            amount_out = get_amount_0_delta(sqrt_price_current, sqrt_price_next, liquidity, false)?;
+        }
+    }
+
+    // The price moved by the rounded up amount may give out a little more than remaining.
+    if !exact_input && amount_out > amount_remaining {
+        amount_out = amount_remaining;
+    }
+
+    let fee_amount = if exact_input && sqrt_price_next != sqrt_price_target {
+        // The remaining of the exact input is all taken as the fee.
+        amount_remaining.checked_sub(amount_in)?
+    } else {
+        mul_div_rounding_up(amount_in, fee_numerator, fee_complement)?
+    };
+
+    Some(SwapStep { sqrt_price_next, amount_in, amount_out, fee_amount })
+}
+
+/// The max liquidity which `amount_0` and `amount_1` provide between `sqrt_price_a` and
+/// `sqrt_price_b` at `sqrt_price`.
+pub fn get_liquidity_for_amounts(
+    sqrt_price: U256,
+    sqrt_price_a: U256,
+    sqrt_price_b: U256,
+    amount_0: U256,
+    amount_1: U256,
+) -> Option<u128> {
+    let (sqrt_price_a, sqrt_price_b) = if sqrt_price_a > sqrt_price_b {
+        (sqrt_price_b, sqrt_price_a)
+    } else {
+        (sqrt_price_a, sqrt_price_b)
+    };
+
+    let liquidity_0 = |sqrt_price_a: U256| {
+        let intermediate = mul_div(sqrt_price_a, sqrt_price_b, q96())?;
+        mul_div(amount_0, intermediate, sqrt_price_b - sqrt_price_a)
+    };
+    let liquidity_1 = |sqrt_price_b: U256| mul_div(amount_1, q96(), sqrt_price_b - sqrt_price_a);
+
+    let liquidity = if sqrt_price <= sqrt_price_a {
+        liquidity_0(sqrt_price_a)?
+    } else if sqrt_price < sqrt_price_b {
+        liquidity_0(sqrt_price)?.min(liquidity_1(sqrt_price)?)
+    } else {
+        liquidity_1(sqrt_price_b)?
+    };
+
+    if liquidity > U256::from(u128::MAX) {
+        None
+    } else {
+        Some(liquidity.low_u128())
+    }
+}
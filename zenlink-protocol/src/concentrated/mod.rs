@@ -0,0 +1,835 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+//! # Concentrated Liquidity Module
+//!
+//! ## Overview
+//!
+//! Positions of liquidity provided between a lower and an upper tick of the price, the swap
+//! mechanism refers to the design of Uniswap V3.
+//!
+//! The price of a pool is the price of `asset_1` quoted in `asset_0` of the sorted pair, the
+//! ticks given in the reversed order of the assets are of the reciprocal price, which are the
+//! negatives of the ticks of the sorted pair.
+
+use super::*;
+
+pub(crate) mod math;
+
+pub use math::{MAX_SQRT_RATIO, MAX_TICK, MIN_SQRT_RATIO, MIN_TICK};
+
+use math::{
+    compute_swap_step, get_amount_0_delta, get_amount_1_delta, get_liquidity_for_amounts,
+    get_sqrt_ratio_at_tick, get_tick_at_sqrt_ratio, mul_div, q128,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// The state of a swap in a concentrated liquidity pool after it crosses the ticks.
+struct ConcentratedSwap {
+    amount_remaining: U256,
+    amount_calculated: U256,
+    sqrt_price: U256,
+    tick: i32,
+    liquidity: u128,
+    /// The fee growth of the input asset.
+    fee_growth_global: U256,
+    /// The ticks crossed with the fee growth of the input asset at the crossing.
+    crossed_ticks: Vec<(i32, U256)>,
+}
+
+impl<T: Config> Pallet<T> {
+    /// The account ID of a concentrated liquidity pool account
+    pub fn concentrated_pool_account_id(asset_0: AssetId, asset_1: AssetId) -> T::AccountId {
+        let (asset_0, asset_1) = Self::sort_asset_id(asset_0, asset_1);
+        let pool_hash: T::Hash = T::Hashing::hash_of(&(b"concentrated", asset_0, asset_1));
+
+        T::PalletId::get().into_sub_account(pool_hash.as_ref())
+    }
+
+    /// The sorted pair of the assets with the ticks and amounts of the position in the order
+    /// of it.
+    #[allow(clippy::type_complexity)]
+    fn sort_concentrated_position(
+        asset_0: AssetId,
+        asset_1: AssetId,
+        tick_lower: i32,
+        tick_upper: i32,
+        amounts: [(AssetBalance, AssetBalance); 2],
+    ) -> ((AssetId, AssetId), i32, i32, [(AssetBalance, AssetBalance); 2]) {
+        let pair = Self::sort_asset_id(asset_0, asset_1);
+        if asset_0 == pair.0 {
+            (pair, tick_lower, tick_upper, amounts)
+        } else {
+            (
+                pair,
+                tick_upper.saturating_neg(),
+                tick_lower.saturating_neg(),
+                [(amounts[0].1, amounts[0].0), (amounts[1].1, amounts[1].0)],
+            )
+        }
+    }
+
+    pub fn inner_create_concentrated_pool(
+        asset_0: AssetId,
+        asset_1: AssetId,
+        fee_numerator: u32,
+        fee_denominator: u32,
+        tick_spacing: u32,
+        initial_tick: i32,
+    ) -> DispatchResult {
+        ensure!(asset_0.is_support() && asset_1.is_support(), Error::<T>::UnsupportedAssetType);
+        ensure!(asset_0 != asset_1, Error::<T>::DeniedCreatePair);
+        ensure!(T::MultiAssetsHandler::is_exists(asset_0), Error::<T>::AssetNotExists);
+        ensure!(T::MultiAssetsHandler::is_exists(asset_1), Error::<T>::AssetNotExists);
+        ensure!(fee_numerator < fee_denominator, Error::<T>::InvalidExchangeFee);
+        ensure!(tick_spacing > 0 && tick_spacing <= MAX_TICK as u32, Error::<T>::InvalidTick);
+        ensure!(initial_tick > MIN_TICK && initial_tick < MAX_TICK, Error::<T>::InvalidTick);
+
+        let pair = Self::sort_asset_id(asset_0, asset_1);
+        let tick = if asset_0 == pair.0 { initial_tick } else { -initial_tick };
+        ensure!(!ConcentratedPools::<T>::contains_key(pair), Error::<T>::PoolAlreadyExists);
+
+        let sqrt_price = get_sqrt_ratio_at_tick(tick).ok_or(Error::<T>::InvalidTick)?;
+        ConcentratedPools::<T>::insert(
+            pair,
+            ConcentratedPool {
+                pool_account: Self::concentrated_pool_account_id(pair.0, pair.1),
+                fee: (fee_numerator, fee_denominator),
+                tick_spacing,
+                sqrt_price,
+                tick,
+                ..Default::default()
+            },
+        );
+
+        Self::deposit_event(Event::ConcentratedPoolCreated(pair.0, pair.1, tick_spacing, tick));
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn inner_add_concentrated_liquidity(
+        who: &T::AccountId,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        tick_lower: i32,
+        tick_upper: i32,
+        amount_0_desired: AssetBalance,
+        amount_1_desired: AssetBalance,
+        amount_0_min: AssetBalance,
+        amount_1_min: AssetBalance,
+    ) -> DispatchResult {
+        let (pair, tick_lower, tick_upper, [desired, min]) = Self::sort_concentrated_position(
+            asset_0,
+            asset_1,
+            tick_lower,
+            tick_upper,
+            [(amount_0_desired, amount_1_desired), (amount_0_min, amount_1_min)],
+        );
+        let pool = Self::concentrated_pool(pair).ok_or(Error::<T>::PoolNotExists)?;
+        Self::ensure_concentrated_ticks(&pool, tick_lower, tick_upper)?;
+
+        let liquidity = get_liquidity_for_amounts(
+            pool.sqrt_price,
+            get_sqrt_ratio_at_tick(tick_lower).ok_or(Error::<T>::InvalidTick)?,
+            get_sqrt_ratio_at_tick(tick_upper).ok_or(Error::<T>::InvalidTick)?,
+            U256::from(desired.0),
+            U256::from(desired.1),
+        )
+        .filter(|liquidity| *liquidity <= i128::MAX as u128)
+        .ok_or(Error::<T>::Overflow)?;
+        ensure!(liquidity > Zero::zero(), Error::<T>::InsufficientLiquidity);
+
+        let (amount_0, amount_1) = Self::modify_concentrated_position(
+            who,
+            pair,
+            tick_lower,
+            tick_upper,
+            liquidity as i128,
+        )?;
+        ensure!(amount_0 >= min.0 && amount_1 >= min.1, Error::<T>::IncorrectAssetAmountRange);
+
+        T::MultiAssetsHandler::transfer(pair.0, who, &pool.pool_account, amount_0)?;
+        T::MultiAssetsHandler::transfer(pair.1, who, &pool.pool_account, amount_1)?;
+
+        Self::deposit_event(Event::ConcentratedLiquidityAdded(
+            who.clone(),
+            pair.0,
+            pair.1,
+            tick_lower,
+            tick_upper,
+            liquidity,
+            amount_0,
+            amount_1,
+        ));
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn inner_remove_concentrated_liquidity(
+        who: &T::AccountId,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity: u128,
+        amount_0_min: AssetBalance,
+        amount_1_min: AssetBalance,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        let (pair, tick_lower, tick_upper, [min, _]) = Self::sort_concentrated_position(
+            asset_0,
+            asset_1,
+            tick_lower,
+            tick_upper,
+            [(amount_0_min, amount_1_min), (Zero::zero(), Zero::zero())],
+        );
+        let pool = Self::concentrated_pool(pair).ok_or(Error::<T>::PoolNotExists)?;
+        ensure!(
+            liquidity > Zero::zero() && liquidity <= i128::MAX as u128,
+            Error::<T>::InsufficientLiquidity
+        );
+
+        let (amount_0, amount_1) = Self::modify_concentrated_position(
+            who,
+            pair,
+            tick_lower,
+            tick_upper,
+            -(liquidity as i128),
+        )?;
+        ensure!(amount_0 >= min.0 && amount_1 >= min.1, Error::<T>::InsufficientTargetAmount);
+
+        T::MultiAssetsHandler::transfer(pair.0, &pool.pool_account, recipient, amount_0)?;
+        T::MultiAssetsHandler::transfer(pair.1, &pool.pool_account, recipient, amount_1)?;
+
+        Self::deposit_event(Event::ConcentratedLiquidityRemoved(
+            who.clone(),
+            recipient.clone(),
+            pair.0,
+            pair.1,
+            tick_lower,
+            tick_upper,
+            liquidity,
+            amount_0,
+            amount_1,
+        ));
+
+        Ok(())
+    }
+
+    pub fn inner_collect_concentrated_fees(
+        who: &T::AccountId,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        tick_lower: i32,
+        tick_upper: i32,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        let (pair, tick_lower, tick_upper, _) = Self::sort_concentrated_position(
+            asset_0,
+            asset_1,
+            tick_lower,
+            tick_upper,
+            Default::default(),
+        );
+        let pool = Self::concentrated_pool(pair).ok_or(Error::<T>::PoolNotExists)?;
+
+        // Accrue the fees earned by the position so far.
+        Self::modify_concentrated_position(who, pair, tick_lower, tick_upper, 0)?;
+
+        let (fees_0, fees_1) = ConcentratedPositions::<T>::try_mutate_exists(
+            who,
+            (pair, tick_lower, tick_upper),
+            |position| -> Result<(AssetBalance, AssetBalance), DispatchError> {
+                let mut current = position.take().ok_or(Error::<T>::PositionNotExists)?;
+                let fees = (current.fees_owed_0, current.fees_owed_1);
+                current.fees_owed_0 = Zero::zero();
+                current.fees_owed_1 = Zero::zero();
+                if current.liquidity > Zero::zero() {
+                    *position = Some(current);
+                }
+
+                Ok(fees)
+            },
+        )?;
+
+        T::MultiAssetsHandler::transfer(pair.0, &pool.pool_account, recipient, fees_0)?;
+        T::MultiAssetsHandler::transfer(pair.1, &pool.pool_account, recipient, fees_1)?;
+
+        Self::deposit_event(Event::ConcentratedFeesCollected(
+            who.clone(),
+            recipient.clone(),
+            pair.0,
+            pair.1,
+            tick_lower,
+            tick_upper,
+            fees_0,
+            fees_1,
+        ));
+
+        Ok(())
+    }
+
+    /// Swap the exact `amount_in` of `asset_in` for `asset_out` in the concentrated liquidity
+    /// pool of the two assets.
+    pub fn inner_concentrated_swap_exact_assets_for_assets(
+        who: &T::AccountId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: AssetBalance,
+        amount_out_min: AssetBalance,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        let (amount_in, amount_out) =
+            Self::concentrated_swap(who, asset_in, asset_out, amount_in, true, recipient)?;
+        ensure!(amount_out >= amount_out_min, Error::<T>::InsufficientTargetAmount);
+
+        Self::deposit_event(Event::AssetSwap(
+            who.clone(),
+            recipient.clone(),
+            vec![asset_in, asset_out],
+            amount_in,
+            amount_out,
+        ));
+
+        Ok(())
+    }
+
+    /// Swap `asset_in` for the exact `amount_out` of `asset_out` in the concentrated liquidity
+    /// pool of the two assets.
+    pub fn inner_concentrated_swap_assets_for_exact_assets(
+        who: &T::AccountId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_out: AssetBalance,
+        amount_in_max: AssetBalance,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        let (amount_in, amount_out) =
+            Self::concentrated_swap(who, asset_in, asset_out, amount_out, false, recipient)?;
+        ensure!(amount_in <= amount_in_max, Error::<T>::ExcessiveSoldAmount);
+
+        Self::deposit_event(Event::AssetSwap(
+            who.clone(),
+            recipient.clone(),
+            vec![asset_in, asset_out],
+            amount_in,
+            amount_out,
+        ));
+
+        Ok(())
+    }
+
+    /// The amount of `asset_out` got by swapping `amount_in` of `asset_in` in the concentrated
+    /// liquidity pool, zero if the pool can not take all of it.
+    pub fn concentrated_amount_out(
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: AssetBalance,
+    ) -> AssetBalance {
+        Self::quote_concentrated_swap(asset_in, asset_out, amount_in, true)
+            .map_or(Zero::zero(), |(_, amount_out)| amount_out)
+    }
+
+    /// The amount of `asset_in` needed to get `amount_out` of `asset_out` from the concentrated
+    /// liquidity pool, zero if the pool can not give all of it.
+    pub fn concentrated_amount_in(
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_out: AssetBalance,
+    ) -> AssetBalance {
+        Self::quote_concentrated_swap(asset_in, asset_out, amount_out, false)
+            .map_or(Zero::zero(), |(amount_in, _)| amount_in)
+    }
+
+    fn quote_concentrated_swap(
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount: AssetBalance,
+        exact_input: bool,
+    ) -> Option<(AssetBalance, AssetBalance)> {
+        let pair = Self::sort_asset_id(asset_in, asset_out);
+        let pool = Self::concentrated_pool(pair)?;
+        let swap =
+            Self::compute_concentrated_swap(pair, &pool, asset_in == pair.0, exact_input, amount)?;
+
+        Self::concentrated_swap_amounts(&swap, amount, exact_input)
+    }
+
+    /// The amounts in and out of the swap, if all of `amount` is swapped.
+    fn concentrated_swap_amounts(
+        swap: &ConcentratedSwap,
+        amount: AssetBalance,
+        exact_input: bool,
+    ) -> Option<(AssetBalance, AssetBalance)> {
+        if !swap.amount_remaining.is_zero() {
+            return None;
+        }
+        let calculated = TryInto::<AssetBalance>::try_into(swap.amount_calculated).ok()?;
+
+        if exact_input {
+            Some((amount, calculated))
+        } else {
+            Some((calculated, amount))
+        }
+    }
+
+    fn concentrated_swap(
+        who: &T::AccountId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount: AssetBalance,
+        exact_input: bool,
+        recipient: &T::AccountId,
+    ) -> Result<(AssetBalance, AssetBalance), DispatchError> {
+        ensure!(asset_in != asset_out, Error::<T>::InvalidPath);
+        ensure!(amount > Zero::zero(), Error::<T>::InvalidPath);
+        let pair = Self::sort_asset_id(asset_in, asset_out);
+        let zero_for_one = asset_in == pair.0;
+
+        ConcentratedPools::<T>::try_mutate(
+            pair,
+            |pool| -> Result<(AssetBalance, AssetBalance), DispatchError> {
+                let pool = pool.as_mut().ok_or(Error::<T>::PoolNotExists)?;
+                let swap =
+                    Self::compute_concentrated_swap(pair, pool, zero_for_one, exact_input, amount)
+                        .ok_or(Error::<T>::Overflow)?;
+                let (amount_in, amount_out) =
+                    Self::concentrated_swap_amounts(&swap, amount, exact_input)
+                        .ok_or(Error::<T>::InsufficientPairReserve)?;
+                ensure!(amount_out > Zero::zero(), Error::<T>::InsufficientTargetAmount);
+
+                let (fee_growth_global_0, fee_growth_global_1) = if zero_for_one {
+                    (swap.fee_growth_global, pool.fee_growth_global_1)
+                } else {
+                    (pool.fee_growth_global_0, swap.fee_growth_global)
+                };
+                for (tick, fee_growth_global) in swap.crossed_ticks {
+                    let (fee_growth_0, fee_growth_1) = if zero_for_one {
+                        (fee_growth_global, fee_growth_global_1)
+                    } else {
+                        (fee_growth_global_0, fee_growth_global)
+                    };
+                    ConcentratedTicks::<T>::mutate((pair, tick), |info| {
+                        if let Some(info) = info {
+                            info.fee_growth_outside_0 =
+                                fee_growth_0.overflowing_sub(info.fee_growth_outside_0).0;
+                            info.fee_growth_outside_1 =
+                                fee_growth_1.overflowing_sub(info.fee_growth_outside_1).0;
+                        }
+                    });
+                }
+
+                pool.sqrt_price = swap.sqrt_price;
+                pool.tick = swap.tick;
+                pool.liquidity = swap.liquidity;
+                pool.fee_growth_global_0 = fee_growth_global_0;
+                pool.fee_growth_global_1 = fee_growth_global_1;
+
+                T::MultiAssetsHandler::transfer(asset_in, who, &pool.pool_account, amount_in)?;
+                T::MultiAssetsHandler::transfer(
+                    asset_out,
+                    &pool.pool_account,
+                    recipient,
+                    amount_out,
+                )?;
+
+                Ok((amount_in, amount_out))
+            },
+        )
+    }
+
+    /// Swap in the pool until `amount` is swapped or the price hits the bound, crossing the
+    /// initialized ticks on the way.
+    fn compute_concentrated_swap(
+        pair: (AssetId, AssetId),
+        pool: &ConcentratedPool<T::AccountId>,
+        zero_for_one: bool,
+        exact_input: bool,
+        amount: AssetBalance,
+    ) -> Option<ConcentratedSwap> {
+        let ticks = Self::concentrated_initialized_ticks(pair);
+        let sqrt_price_limit =
+            if zero_for_one { MIN_SQRT_RATIO + U256::one() } else { MAX_SQRT_RATIO - U256::one() };
+
+        let mut swap = ConcentratedSwap {
+            amount_remaining: U256::from(amount),
+            amount_calculated: U256::zero(),
+            sqrt_price: pool.sqrt_price,
+            tick: pool.tick,
+            liquidity: pool.liquidity,
+            fee_growth_global: if zero_for_one {
+                pool.fee_growth_global_0
+            } else {
+                pool.fee_growth_global_1
+            },
+            crossed_ticks: Vec::new(),
+        };
+
+        while !swap.amount_remaining.is_zero() && swap.sqrt_price != sqrt_price_limit {
+            // The next initialized tick in the direction of the swap.
+            let tick_next = match (zero_for_one, ticks.binary_search(&swap.tick)) {
+                (true, Ok(i)) => ticks.get(i),
+                (true, Err(i)) => i.checked_sub(1).and_then(|i| ticks.get(i)),
+                (false, Ok(i)) => ticks.get(i + 1),
+                (false, Err(i)) => ticks.get(i),
+            };
+            let (tick_next, initialized) = match tick_next {
+                Some(tick) => (*tick, true),
+                None if zero_for_one => (MIN_TICK, false),
+                None => (MAX_TICK, false),
+            };
+
+            let sqrt_price_start = swap.sqrt_price;
+            let sqrt_price_next = get_sqrt_ratio_at_tick(tick_next)?;
+            let sqrt_price_target = if zero_for_one {
+                sqrt_price_next.max(sqrt_price_limit)
+            } else {
+                sqrt_price_next.min(sqrt_price_limit)
+            };
+
+            let step = compute_swap_step(
+                swap.sqrt_price,
+                sqrt_price_target,
+                swap.liquidity,
+                swap.amount_remaining,
+                exact_input,
+                pool.fee,
+            )?;
+            swap.sqrt_price = step.sqrt_price_next;
+
+            let amount_in = step.amount_in.checked_add(step.fee_amount)?;
+            if exact_input {
+                swap.amount_remaining = swap.amount_remaining.checked_sub(amount_in)?;
+                swap.amount_calculated = swap.amount_calculated.checked_add(step.amount_out)?;
+            } else {
+                swap.amount_remaining = swap.amount_remaining.checked_sub(step.amount_out)?;
+                swap.amount_calculated = swap.amount_calculated.checked_add(amount_in)?;
+            }
+
+            if swap.liquidity > Zero::zero() {
+                let fee_growth = mul_div(step.fee_amount, q128(), U256::from(swap.liquidity))?;
+                swap.fee_growth_global = swap.fee_growth_global.overflowing_add(fee_growth).0;
+            }
+
+            if swap.sqrt_price == sqrt_price_next {
+                if initialized {
+                    let info = Self::concentrated_tick((pair, tick_next))?;
+                    let liquidity_net = if zero_for_one {
+                        info.liquidity_net.checked_neg()?
+                    } else {
+                        info.liquidity_net
+                    };
+                    swap.liquidity = Self::add_liquidity_delta(swap.liquidity, liquidity_net)?;
+                    swap.crossed_ticks.push((tick_next, swap.fee_growth_global));
+                }
+                swap.tick = if zero_for_one { tick_next - 1 } else { tick_next };
+            } else if swap.sqrt_price != sqrt_price_start {
+                swap.tick = get_tick_at_sqrt_ratio(swap.sqrt_price)?;
+            }
+        }
+
+        Some(swap)
+    }
+
+    fn ensure_concentrated_ticks(
+        pool: &ConcentratedPool<T::AccountId>,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> DispatchResult {
+        let tick_spacing = pool.tick_spacing as i32;
+        ensure!(
+            tick_lower < tick_upper
+                && tick_lower >= MIN_TICK
+                && tick_upper <= MAX_TICK
+                && tick_lower % tick_spacing == 0
+                && tick_upper % tick_spacing == 0,
+            Error::<T>::InvalidTick
+        );
+
+        Ok(())
+    }
+
+    fn add_liquidity_delta(liquidity: u128, delta: i128) -> Option<u128> {
+        if delta < 0 {
+            liquidity.checked_sub(delta.checked_neg()? as u128)
+        } else {
+            liquidity.checked_add(delta as u128)
+        }
+    }
+
+    /// Add `liquidity_delta` to the position, accruing the fees earned by it so far, and
+    /// return the amounts of the assets paid to the pool for it, or taken from the pool by it.
+    fn modify_concentrated_position(
+        who: &T::AccountId,
+        pair: (AssetId, AssetId),
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity_delta: i128,
+    ) -> Result<(AssetBalance, AssetBalance), DispatchError> {
+        ConcentratedPools::<T>::try_mutate(
+            pair,
+            |pool| -> Result<(AssetBalance, AssetBalance), DispatchError> {
+                let pool = pool.as_mut().ok_or(Error::<T>::PoolNotExists)?;
+                Self::ensure_concentrated_ticks(pool, tick_lower, tick_upper)?;
+
+                let (mut flipped_lower, mut flipped_upper) = (false, false);
+                if liquidity_delta != 0 {
+                    flipped_lower = Self::update_concentrated_tick(
+                        pair,
+                        pool,
+                        tick_lower,
+                        liquidity_delta,
+                        false,
+                    )?;
+                    flipped_upper = Self::update_concentrated_tick(
+                        pair,
+                        pool,
+                        tick_upper,
+                        liquidity_delta,
+                        true,
+                    )?;
+                }
+
+                let (fee_growth_inside_0, fee_growth_inside_1) =
+                    Self::concentrated_fee_growth_inside(pair, pool, tick_lower, tick_upper);
+
+                ConcentratedPositions::<T>::try_mutate_exists(
+                    who,
+                    (pair, tick_lower, tick_upper),
+                    |position| -> DispatchResult {
+                        ensure!(
+                            position.is_some() || liquidity_delta > 0,
+                            Error::<T>::PositionNotExists
+                        );
+                        let mut current = position.take().unwrap_or_default();
+
+                        let (fees_0, fees_1) = Self::concentrated_fees_earned(
+                            &current,
+                            fee_growth_inside_0,
+                            fee_growth_inside_1,
+                        );
+                        current.fees_owed_0 = current.fees_owed_0.saturating_add(fees_0);
+                        current.fees_owed_1 = current.fees_owed_1.saturating_add(fees_1);
+                        current.fee_growth_inside_0_last = fee_growth_inside_0;
+                        current.fee_growth_inside_1_last = fee_growth_inside_1;
+                        current.liquidity =
+                            Self::add_liquidity_delta(current.liquidity, liquidity_delta)
+                                .ok_or(Error::<T>::InsufficientLiquidity)?;
+
+                        if current.liquidity > Zero::zero()
+                            || current.fees_owed_0 > Zero::zero()
+                            || current.fees_owed_1 > Zero::zero()
+                        {
+                            *position = Some(current);
+                        }
+
+                        Ok(())
+                    },
+                )?;
+
+                // The ticks are cleared after the fee growth inside is taken from them.
+                if liquidity_delta < 0 {
+                    if flipped_lower {
+                        Self::clear_concentrated_tick(pair, tick_lower);
+                    }
+                    if flipped_upper {
+                        Self::clear_concentrated_tick(pair, tick_upper);
+                    }
+                }
+
+                let liquidity = liquidity_delta.checked_abs().ok_or(Error::<T>::Overflow)? as u128;
+                let round_up = liquidity_delta > 0;
+                let sqrt_price_lower =
+                    get_sqrt_ratio_at_tick(tick_lower).ok_or(Error::<T>::InvalidTick)?;
+                let sqrt_price_upper =
+                    get_sqrt_ratio_at_tick(tick_upper).ok_or(Error::<T>::InvalidTick)?;
+
+                let (amount_0, amount_1) = if pool.tick < tick_lower {
+                    (
+                        get_amount_0_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up),
+                        Some(U256::zero()),
+                    )
+                } else if pool.tick < tick_upper {
+                    pool.liquidity = Self::add_liquidity_delta(pool.liquidity, liquidity_delta)
+                        .ok_or(Error::<T>::Overflow)?;
+
+                    (
+                        get_amount_0_delta(pool.sqrt_price, sqrt_price_upper, liquidity, round_up),
+                        get_amount_1_delta(sqrt_price_lower, pool.sqrt_price, liquidity, round_up),
+                    )
+                } else {
+                    (
+                        Some(U256::zero()),
+                        get_amount_1_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up),
+                    )
+                };
+
+                let to_balance = |amount: Option<U256>| {
+                    amount
+                        .and_then(|n| TryInto::<AssetBalance>::try_into(n).ok())
+                        .ok_or(Error::<T>::Overflow)
+                };
+
+                Ok((to_balance(amount_0)?, to_balance(amount_1)?))
+            },
+        )
+    }
+
+    /// Add `liquidity_delta` to the liquidity referencing the tick and return whether the tick
+    /// is flipped from initialized to uninitialized or vice versa.
+    fn update_concentrated_tick(
+        pair: (AssetId, AssetId),
+        pool: &ConcentratedPool<T::AccountId>,
+        tick: i32,
+        liquidity_delta: i128,
+        upper: bool,
+    ) -> Result<bool, DispatchError> {
+        let mut info = Self::concentrated_tick((pair, tick)).unwrap_or_default();
+
+        let liquidity_gross_before = info.liquidity_gross;
+        let liquidity_gross_after =
+            Self::add_liquidity_delta(liquidity_gross_before, liquidity_delta)
+                .ok_or(Error::<T>::InsufficientLiquidity)?;
+        let flipped = (liquidity_gross_after == 0) != (liquidity_gross_before == 0);
+
+        if liquidity_gross_before == 0 {
+            // All the fee growth before the tick is initialized is assumed to happen below it.
+            if tick <= pool.tick {
+                info.fee_growth_outside_0 = pool.fee_growth_global_0;
+                info.fee_growth_outside_1 = pool.fee_growth_global_1;
+            }
+
+            ConcentratedInitializedTicks::<T>::mutate(pair, |ticks| {
+                if let Err(i) = ticks.binary_search(&tick) {
+                    ticks.insert(i, tick);
+                }
+            });
+        }
+
+        info.liquidity_gross = liquidity_gross_after;
+        info.liquidity_net = if upper {
+            info.liquidity_net.checked_sub(liquidity_delta)
+        } else {
+            info.liquidity_net.checked_add(liquidity_delta)
+        }
+        .ok_or(Error::<T>::Overflow)?;
+
+        ConcentratedTicks::<T>::insert((pair, tick), info);
+
+        Ok(flipped)
+    }
+
+    fn clear_concentrated_tick(pair: (AssetId, AssetId), tick: i32) {
+        ConcentratedTicks::<T>::remove((pair, tick));
+        ConcentratedInitializedTicks::<T>::mutate(pair, |ticks| {
+            if let Ok(i) = ticks.binary_search(&tick) {
+                ticks.remove(i);
+            }
+        });
+    }
+
+    /// The fee growth per unit of liquidity between the two ticks.
+    fn concentrated_fee_growth_inside(
+        pair: (AssetId, AssetId),
+        pool: &ConcentratedPool<T::AccountId>,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> (U256, U256) {
+        let lower = Self::concentrated_tick((pair, tick_lower)).unwrap_or_default();
+        let upper = Self::concentrated_tick((pair, tick_upper)).unwrap_or_default();
+
+        let inside = |global: U256, lower_outside: U256, upper_outside: U256| {
+            let below = if pool.tick >= tick_lower {
+                lower_outside
+            } else {
+                global.overflowing_sub(lower_outside).0
+            };
+            let above = if pool.tick < tick_upper {
+                upper_outside
+            } else {
+                global.overflowing_sub(upper_outside).0
+            };
+
+            global.overflowing_sub(below).0.overflowing_sub(above).0
+        };
+
+        (
+            inside(
+                pool.fee_growth_global_0,
+                lower.fee_growth_outside_0,
+                upper.fee_growth_outside_0,
+            ),
+            inside(
+                pool.fee_growth_global_1,
+                lower.fee_growth_outside_1,
+                upper.fee_growth_outside_1,
+            ),
+        )
+    }
+
+    /// The fees earned by the position since the fee growth inside was last taken.
+    fn concentrated_fees_earned(
+        position: &ConcentratedPosition,
+        fee_growth_inside_0: U256,
+        fee_growth_inside_1: U256,
+    ) -> (AssetBalance, AssetBalance) {
+        let earned = |fee_growth_inside: U256, fee_growth_inside_last: U256| {
+            mul_div(
+                fee_growth_inside.overflowing_sub(fee_growth_inside_last).0,
+                U256::from(position.liquidity),
+                q128(),
+            )
+            .and_then(|n| TryInto::<AssetBalance>::try_into(n).ok())
+            .unwrap_or_else(Zero::zero)
+        };
+
+        (
+            earned(fee_growth_inside_0, position.fee_growth_inside_0_last),
+            earned(fee_growth_inside_1, position.fee_growth_inside_1_last),
+        )
+    }
+
+    /// The positions of `owner` with the amounts of the assets at the current price and the
+    /// fees earned by them.
+    pub fn get_concentrated_positions(
+        owner: &T::AccountId,
+    ) -> Vec<ConcentratedPositionInfo<AssetBalance>> {
+        ConcentratedPositions::<T>::iter_prefix(owner)
+            .filter_map(|((pair, tick_lower, tick_upper), position)| {
+                let pool = Self::concentrated_pool(pair)?;
+                let sqrt_price_lower = get_sqrt_ratio_at_tick(tick_lower)?;
+                let sqrt_price_upper = get_sqrt_ratio_at_tick(tick_upper)?;
+                let sqrt_price = pool.sqrt_price.max(sqrt_price_lower).min(sqrt_price_upper);
+
+                let amount_0 =
+                    get_amount_0_delta(sqrt_price, sqrt_price_upper, position.liquidity, false)?;
+                let amount_1 =
+                    get_amount_1_delta(sqrt_price_lower, sqrt_price, position.liquidity, false)?;
+
+                let (fee_growth_inside_0, fee_growth_inside_1) =
+                    Self::concentrated_fee_growth_inside(pair, &pool, tick_lower, tick_upper);
+                let (fees_0, fees_1) = Self::concentrated_fees_earned(
+                    &position,
+                    fee_growth_inside_0,
+                    fee_growth_inside_1,
+                );
+
+                Some(ConcentratedPositionInfo {
+                    asset_0: pair.0,
+                    asset_1: pair.1,
+                    tick_lower,
+                    tick_upper,
+                    liquidity: position.liquidity,
+                    amount_0: TryInto::<AssetBalance>::try_into(amount_0).ok()?,
+                    amount_1: TryInto::<AssetBalance>::try_into(amount_1).ok()?,
+                    fees_0: position.fees_owed_0.saturating_add(fees_0),
+                    fees_1: position.fees_owed_1.saturating_add(fees_1),
+                })
+            })
+            .collect::<Vec<_>>()
+    }
+}
@@ -0,0 +1,534 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+use frame_support::{assert_noop, assert_ok};
+use sp_core::U256;
+use sp_runtime::DispatchError;
+
+use super::{AssetId, Error, MultiAssetsHandler, MAX_TICK};
+use crate::mock::*;
+
+const DOT_ASSET_ID: AssetId = AssetId { chain_id: 200, asset_type: LOCAL, asset_index: 2 };
+
+const VDOT_ASSET_ID: AssetId = AssetId { chain_id: 200, asset_type: LOCAL, asset_index: 3 };
+
+const BTC_ASSET_ID: AssetId = AssetId { chain_id: 300, asset_type: RESERVED, asset_index: 3 };
+
+const ALICE: u128 = 1;
+const BOB: u128 = 2;
+const CHARLIE: u128 = 3;
+
+const DOT_UNIT: u128 = 1000_000_000_000_000;
+
+const POSITION_LIQUIDITY: u128 = 200510416479002803287;
+const UPPER_POSITION_LIQUIDITY: u128 = 201515428748625854491;
+
+fn create_dot_vdot_pool() {
+    assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX / 2));
+    assert_ok!(DexPallet::foreign_mint(VDOT_ASSET_ID, &ALICE, u128::MAX / 2));
+    assert_ok!(DexPallet::create_concentrated_pool(
+        Origin::root(),
+        DOT_ASSET_ID,
+        VDOT_ASSET_ID,
+        3,
+        1000,
+        10,
+        0
+    ));
+}
+
+fn add_position(tick_lower: i32, tick_upper: i32) {
+    assert_ok!(DexPallet::add_concentrated_liquidity(
+        Origin::signed(ALICE),
+        DOT_ASSET_ID,
+        VDOT_ASSET_ID,
+        tick_lower,
+        tick_upper,
+        1000 * DOT_UNIT,
+        1000 * DOT_UNIT,
+        0,
+        0,
+        Deadline::BlockNumber(100)
+    ));
+}
+
+/// Positions around the price and next to it on both sides, swapped through.
+fn swap_through_positions() {
+    create_dot_vdot_pool();
+    add_position(-100, 100);
+    add_position(100, 200);
+    add_position(-200, -100);
+
+    assert_ok!(DexPallet::concentrated_swap_exact_assets_for_assets(
+        Origin::signed(ALICE),
+        DOT_ASSET_ID,
+        VDOT_ASSET_ID,
+        10 * DOT_UNIT,
+        0,
+        BOB,
+        Deadline::BlockNumber(100)
+    ));
+    assert_ok!(DexPallet::concentrated_swap_exact_assets_for_assets(
+        Origin::signed(ALICE),
+        VDOT_ASSET_ID,
+        DOT_ASSET_ID,
+        1500 * DOT_UNIT,
+        0,
+        BOB,
+        Deadline::BlockNumber(100)
+    ));
+    assert_ok!(DexPallet::concentrated_swap_assets_for_exact_assets(
+        Origin::signed(ALICE),
+        DOT_ASSET_ID,
+        VDOT_ASSET_ID,
+        100 * DOT_UNIT,
+        u128::MAX,
+        BOB,
+        Deadline::BlockNumber(100)
+    ));
+}
+
+#[test]
+fn create_concentrated_pool_should_work() {
+    new_test_ext().execute_with(|| {
+        create_dot_vdot_pool();
+
+        let pool = DexPallet::concentrated_pool((DOT_ASSET_ID, VDOT_ASSET_ID)).unwrap();
+        assert_eq!(pool.sqrt_price, U256::one() << 96);
+        assert_eq!(pool.tick, 0);
+        assert_eq!(pool.tick_spacing, 10);
+        assert_eq!(pool.liquidity, 0);
+        assert_eq!(
+            pool.pool_account,
+            DexPallet::concentrated_pool_account_id(VDOT_ASSET_ID, DOT_ASSET_ID)
+        );
+
+        // The tick of the reversed pair is of the reciprocal price.
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, DOT_UNIT));
+        assert_ok!(DexPallet::create_concentrated_pool(
+            Origin::root(),
+            BTC_ASSET_ID,
+            DOT_ASSET_ID,
+            3,
+            1000,
+            10,
+            100
+        ));
+        assert_eq!(DexPallet::concentrated_pool((DOT_ASSET_ID, BTC_ASSET_ID)).unwrap().tick, -100);
+    });
+}
+
+#[test]
+fn create_concentrated_pool_should_not_work() {
+    new_test_ext().execute_with(|| {
+        create_dot_vdot_pool();
+
+        assert_noop!(
+            DexPallet::create_concentrated_pool(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                3,
+                1000,
+                10,
+                0
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            DexPallet::create_concentrated_pool(
+                Origin::root(),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                3,
+                1000,
+                10,
+                0
+            ),
+            Error::<Test>::AssetNotExists
+        );
+        assert_noop!(
+            DexPallet::create_concentrated_pool(
+                Origin::root(),
+                VDOT_ASSET_ID,
+                DOT_ASSET_ID,
+                3,
+                1000,
+                10,
+                0
+            ),
+            Error::<Test>::PoolAlreadyExists
+        );
+
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, DOT_UNIT));
+        assert_noop!(
+            DexPallet::create_concentrated_pool(
+                Origin::root(),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                1000,
+                1000,
+                10,
+                0
+            ),
+            Error::<Test>::InvalidExchangeFee
+        );
+        assert_noop!(
+            DexPallet::create_concentrated_pool(
+                Origin::root(),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                3,
+                1000,
+                0,
+                0
+            ),
+            Error::<Test>::InvalidTick
+        );
+        assert_noop!(
+            DexPallet::create_concentrated_pool(
+                Origin::root(),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                3,
+                1000,
+                10,
+                MAX_TICK
+            ),
+            Error::<Test>::InvalidTick
+        );
+    });
+}
+
+#[test]
+fn concentrated_liquidity_should_work() {
+    new_test_ext().execute_with(|| {
+        create_dot_vdot_pool();
+        let pair = (DOT_ASSET_ID, VDOT_ASSET_ID);
+
+        assert_noop!(
+            DexPallet::add_concentrated_liquidity(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                VDOT_ASSET_ID,
+                -105,
+                100,
+                1000 * DOT_UNIT,
+                1000 * DOT_UNIT,
+                0,
+                0,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InvalidTick
+        );
+        assert_noop!(
+            DexPallet::add_concentrated_liquidity(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                VDOT_ASSET_ID,
+                -100,
+                100,
+                1000 * DOT_UNIT,
+                1000 * DOT_UNIT,
+                1000 * DOT_UNIT + 1,
+                0,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::IncorrectAssetAmountRange
+        );
+
+        let dot_balance = <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &ALICE);
+        let vdot_balance = <Test as Config>::MultiAssetsHandler::balance_of(VDOT_ASSET_ID, &ALICE);
+        add_position(-100, 100);
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &ALICE),
+            dot_balance - 1000 * DOT_UNIT
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(VDOT_ASSET_ID, &ALICE),
+            vdot_balance - 1000 * DOT_UNIT
+        );
+        assert_eq!(
+            DexPallet::concentrated_position(ALICE, (pair, -100, 100)).unwrap().liquidity,
+            POSITION_LIQUIDITY
+        );
+        assert_eq!(DexPallet::concentrated_pool(pair).unwrap().liquidity, POSITION_LIQUIDITY);
+
+        // Above the price, the position is made up of asset_0 only.
+        add_position(100, 200);
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &ALICE),
+            dot_balance - 2000 * DOT_UNIT
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(VDOT_ASSET_ID, &ALICE),
+            vdot_balance - 1000 * DOT_UNIT
+        );
+        assert_eq!(DexPallet::concentrated_pool(pair).unwrap().liquidity, POSITION_LIQUIDITY);
+        assert_eq!(DexPallet::concentrated_initialized_ticks(pair), vec![-100, 100, 200]);
+
+        // The ticks given in the reversed order of the assets are of the reciprocal price.
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &BOB, 1000 * DOT_UNIT));
+        assert_ok!(DexPallet::add_concentrated_liquidity(
+            Origin::signed(BOB),
+            VDOT_ASSET_ID,
+            DOT_ASSET_ID,
+            -200,
+            -100,
+            0,
+            1000 * DOT_UNIT,
+            0,
+            0,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(
+            DexPallet::concentrated_position(BOB, (pair, 100, 200)).unwrap().liquidity,
+            UPPER_POSITION_LIQUIDITY
+        );
+
+        assert_noop!(
+            DexPallet::remove_concentrated_liquidity(
+                Origin::signed(BOB),
+                DOT_ASSET_ID,
+                VDOT_ASSET_ID,
+                -100,
+                100,
+                1,
+                0,
+                0,
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::PositionNotExists
+        );
+        assert_noop!(
+            DexPallet::remove_concentrated_liquidity(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                VDOT_ASSET_ID,
+                -100,
+                100,
+                POSITION_LIQUIDITY + 1,
+                0,
+                0,
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientLiquidity
+        );
+
+        assert_ok!(DexPallet::remove_concentrated_liquidity(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            VDOT_ASSET_ID,
+            -100,
+            100,
+            POSITION_LIQUIDITY / 2,
+            0,
+            0,
+            CHARLIE,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &CHARLIE),
+            499999999999999999
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(VDOT_ASSET_ID, &CHARLIE),
+            499999999999999999
+        );
+
+        assert_ok!(DexPallet::remove_concentrated_liquidity(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            VDOT_ASSET_ID,
+            -100,
+            100,
+            POSITION_LIQUIDITY - POSITION_LIQUIDITY / 2,
+            0,
+            0,
+            CHARLIE,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &CHARLIE),
+            999999999999999999
+        );
+        assert_eq!(DexPallet::concentrated_position(ALICE, (pair, -100, 100)), None);
+        assert_eq!(DexPallet::concentrated_tick((pair, -100)), None);
+        assert_eq!(DexPallet::concentrated_initialized_ticks(pair), vec![100, 200]);
+        assert_eq!(DexPallet::concentrated_pool(pair).unwrap().liquidity, 0);
+    });
+}
+
+#[test]
+fn concentrated_swap_should_work() {
+    new_test_ext().execute_with(|| {
+        create_dot_vdot_pool();
+        add_position(-100, 100);
+        add_position(100, 200);
+        add_position(-200, -100);
+        let pair = (DOT_ASSET_ID, VDOT_ASSET_ID);
+
+        assert_eq!(
+            DexPallet::concentrated_amount_out(DOT_ASSET_ID, VDOT_ASSET_ID, 10 * DOT_UNIT),
+            9969504285316094
+        );
+        assert_ok!(DexPallet::concentrated_swap_exact_assets_for_assets(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            VDOT_ASSET_ID,
+            10 * DOT_UNIT,
+            0,
+            BOB,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(VDOT_ASSET_ID, &BOB),
+            9969504285316094
+        );
+        assert_eq!(DexPallet::concentrated_pool(pair).unwrap().tick, -1);
+
+        // Crossing tick 100 into the liquidity of the upper position.
+        assert_noop!(
+            DexPallet::concentrated_swap_exact_assets_for_assets(
+                Origin::signed(ALICE),
+                VDOT_ASSET_ID,
+                DOT_ASSET_ID,
+                1500 * DOT_UNIT,
+                1484581150584673393,
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientTargetAmount
+        );
+        assert_ok!(DexPallet::concentrated_swap_exact_assets_for_assets(
+            Origin::signed(ALICE),
+            VDOT_ASSET_ID,
+            DOT_ASSET_ID,
+            1500 * DOT_UNIT,
+            1484581150584673392,
+            BOB,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &BOB),
+            1484581150584673392
+        );
+        let pool = DexPallet::concentrated_pool(pair).unwrap();
+        assert_eq!(pool.tick, 147);
+        assert_eq!(pool.liquidity, UPPER_POSITION_LIQUIDITY);
+
+        assert_eq!(
+            DexPallet::concentrated_amount_in(DOT_ASSET_ID, VDOT_ASSET_ID, 100 * DOT_UNIT),
+            98882103934562021
+        );
+        assert_noop!(
+            DexPallet::concentrated_swap_assets_for_exact_assets(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                VDOT_ASSET_ID,
+                100 * DOT_UNIT,
+                98882103934562020,
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::ExcessiveSoldAmount
+        );
+        assert_ok!(DexPallet::concentrated_swap_assets_for_exact_assets(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            VDOT_ASSET_ID,
+            100 * DOT_UNIT,
+            98882103934562021,
+            BOB,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(VDOT_ASSET_ID, &BOB),
+            9969504285316094 + 100 * DOT_UNIT
+        );
+        assert_eq!(DexPallet::concentrated_pool(pair).unwrap().tick, 137);
+
+        // More than the liquidity of all the positions.
+        assert_eq!(
+            DexPallet::concentrated_amount_out(VDOT_ASSET_ID, DOT_ASSET_ID, 10000 * DOT_UNIT),
+            0
+        );
+        assert_noop!(
+            DexPallet::concentrated_swap_exact_assets_for_assets(
+                Origin::signed(ALICE),
+                VDOT_ASSET_ID,
+                DOT_ASSET_ID,
+                10000 * DOT_UNIT,
+                0,
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientPairReserve
+        );
+    });
+}
+
+#[test]
+fn concentrated_fees_should_work() {
+    new_test_ext().execute_with(|| {
+        swap_through_positions();
+
+        let positions = DexPallet::get_concentrated_positions(&ALICE);
+        assert_eq!(positions.len(), 3);
+
+        let position = positions.iter().find(|info| info.tick_lower == -100).unwrap();
+        assert_eq!(position.liquidity, POSITION_LIQUIDITY);
+        assert_eq!(position.amount_0, 0);
+        assert_eq!(position.amount_1, 2005012269623051203);
+        // 0.3% of the 10 DOT in, all in the range of the position.
+        assert_eq!(position.fees_0, 29999999999999);
+        assert_eq!(position.fees_1, 3054107644659079);
+
+        let position = positions.iter().find(|info| info.tick_lower == 100).unwrap();
+        assert_eq!(position.amount_0, 623974307038084939);
+        assert_eq!(position.amount_1, 380518226091632699);
+        assert_eq!(position.fees_0, 296646311803686);
+        assert_eq!(position.fees_1, 1445892355340920);
+
+        // Out of range of all the swaps.
+        let position = positions.iter().find(|info| info.tick_lower == -200).unwrap();
+        assert_eq!((position.fees_0, position.fees_1), (0, 0));
+
+        assert_ok!(DexPallet::collect_concentrated_fees(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            VDOT_ASSET_ID,
+            -100,
+            100,
+            CHARLIE
+        ));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &CHARLIE),
+            29999999999999
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(VDOT_ASSET_ID, &CHARLIE),
+            3054107644659079
+        );
+
+        let position = DexPallet::get_concentrated_positions(&ALICE)
+            .into_iter()
+            .find(|info| info.tick_lower == -100)
+            .unwrap();
+        assert_eq!((position.fees_0, position.fees_1), (0, 0));
+
+        assert_noop!(
+            DexPallet::collect_concentrated_fees(
+                Origin::signed(BOB),
+                DOT_ASSET_ID,
+                VDOT_ASSET_ID,
+                -100,
+                100,
+                BOB
+            ),
+            Error::<Test>::PositionNotExists
+        );
+    });
+}
@@ -0,0 +1,225 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+//! # DCA Module
+//!
+//! ## Overview
+//!
+//! Dollar-cost-averaging orders, the owner locks an amount of the first asset of a path and
+//! swaps a part of it along the path every few blocks, in `on_initialize` of the due blocks.
+//! At most `MaxDcaExecutionsPerBlock` orders are scheduled at a block, an execution due at a
+//! full block is postponed to the next block with room within `MAX_DCA_SCHEDULE_DELAY`
+//! blocks. An order which can't be scheduled any more is completed early. A failed
+//! execution, such as one below the minimum amount out or paused by the `execute_dca_order`
+//! call name, is skipped and its amount is refunded at the end.
+
+use super::*;
+
+use frame_support::storage::{with_transaction, TransactionOutcome};
+
+#[cfg(test)]
+mod tests;
+
+/// The weight of an execution of a DCA order, for every hop of the path.
+pub const DCA_EXECUTION_WEIGHT: Weight = 1_000_000;
+/// The max number of blocks which a DCA execution is postponed by when the due blocks are full.
+pub const MAX_DCA_SCHEDULE_DELAY: u32 = 10;
+
+impl<T: Config> Pallet<T> {
+    /// The account ID which holds the locked assets of the DCA orders.
+    pub fn dca_account_id() -> T::AccountId {
+        T::PalletId::get().into_sub_account(b"dca")
+    }
+
+    pub fn inner_create_dca_order(
+        who: &T::AccountId,
+        path: Vec<AssetId>,
+        amount_per_execution: AssetBalance,
+        amount_out_min: AssetBalance,
+        executions: u32,
+        period: T::BlockNumber,
+    ) -> Result<u64, DispatchError> {
+        ensure!(
+            !amount_per_execution.is_zero() && executions > 0 && !period.is_zero(),
+            Error::<T>::InvalidDcaOrder
+        );
+        ensure!(
+            path.len() >= 2 && path.len() <= T::MaxSwapHops::get() as usize + 1,
+            Error::<T>::InvalidPath
+        );
+        Self::get_amount_out_by_path(amount_per_execution, &path)?;
+
+        let locked = amount_per_execution
+            .checked_mul(executions as AssetBalance)
+            .ok_or(Error::<T>::Overflow)?;
+        T::MultiAssetsHandler::transfer(path[0], who, &Self::dca_account_id(), locked)?;
+
+        let order_id = Self::next_dca_order_id();
+        NextDcaOrderId::<T>::put(order_id.checked_add(1).ok_or(Error::<T>::Overflow)?);
+
+        let next_execution = Self::schedule_dca_order(
+            order_id,
+            frame_system::Pallet::<T>::block_number().saturating_add(period),
+        )
+        .ok_or(Error::<T>::DcaScheduleFull)?;
+        DcaOrders::<T>::insert(
+            order_id,
+            DcaOrder {
+                owner: who.clone(),
+                path,
+                amount_per_execution,
+                amount_out_min,
+                period,
+                next_execution,
+                executions_left: executions,
+                locked,
+            },
+        );
+
+        Self::deposit_event(Event::DcaOrderCreated(
+            who.clone(),
+            order_id,
+            amount_per_execution,
+            executions,
+        ));
+
+        Ok(order_id)
+    }
+
+    pub fn inner_cancel_dca_order(who: &T::AccountId, order_id: u64) -> DispatchResult {
+        let order = Self::dca_order(order_id).ok_or(Error::<T>::DcaOrderNotExists)?;
+        ensure!(order.owner == *who, Error::<T>::NotDcaOrderOwner);
+
+        Self::refund_dca_order(order_id, &order)?;
+
+        Self::deposit_event(Event::DcaOrderCancelled(who.clone(), order_id, order.locked));
+
+        Ok(())
+    }
+
+    /// Execute the DCA orders due at the block, which are at most `MaxDcaExecutionsPerBlock`.
+    pub(crate) fn execute_due_dca_orders(now: T::BlockNumber) -> Weight {
+        DcaSchedule::<T>::take(now)
+            .into_iter()
+            .fold(T::DbWeight::get().reads_writes(1, 1), |weight, order_id| {
+                weight.saturating_add(Self::execute_dca_order(order_id, now))
+            })
+    }
+
+    /// Schedule the order at the first block from `at` with room for it, within
+    /// `MAX_DCA_SCHEDULE_DELAY` blocks.
+    fn schedule_dca_order(order_id: u64, at: T::BlockNumber) -> Option<T::BlockNumber> {
+        let max_executions = T::MaxDcaExecutionsPerBlock::get() as usize;
+        let mut block_number = at;
+        for _ in 0..=MAX_DCA_SCHEDULE_DELAY {
+            if DcaSchedule::<T>::decode_len(block_number).unwrap_or(0) < max_executions {
+                DcaSchedule::<T>::append(block_number, order_id);
+                return Some(block_number);
+            }
+            block_number = block_number.saturating_add(One::one());
+        }
+
+        None
+    }
+
+    /// Execute the order and schedule the next execution, returns the weight of it.
+    fn execute_dca_order(order_id: u64, now: T::BlockNumber) -> Weight {
+        // The order is gone if it's cancelled.
+        let mut order = match Self::dca_order(order_id) {
+            Some(order) => order,
+            None => return T::DbWeight::get().reads(1),
+        };
+        let weight = DCA_EXECUTION_WEIGHT
+            .saturating_mul(order.path.len().saturating_sub(1) as Weight)
+            .saturating_add(
+                T::DbWeight::get().reads_writes(MAX_DCA_SCHEDULE_DELAY as Weight + 2, 2),
+            );
+
+        let amount_in = order.amount_per_execution.min(order.locked);
+        let result = with_transaction(|| match Self::dca_swap(&order, amount_in) {
+            Ok(amount_out) => TransactionOutcome::Commit(Ok(amount_out)),
+            Err(error) => TransactionOutcome::Rollback(Err(error)),
+        });
+        match result {
+            Ok(amount_out) => {
+                order.locked = order.locked.saturating_sub(amount_in);
+                Self::deposit_event(Event::DcaOrderExecuted(
+                    order.owner.clone(),
+                    order_id,
+                    amount_in,
+                    amount_out,
+                ));
+            }
+            Err(error) => {
+                Self::deposit_event(Event::DcaOrderExecutionFailed(
+                    order.owner.clone(),
+                    order_id,
+                    error,
+                ));
+            }
+        }
+
+        order.executions_left = order.executions_left.saturating_sub(1);
+        let next_execution = if order.executions_left == 0 {
+            None
+        } else {
+            Self::schedule_dca_order(order_id, now.saturating_add(order.period))
+        };
+        match next_execution {
+            Some(next_execution) => order.next_execution = next_execution,
+            // The order is kept for the owner to cancel if the refund fails.
+            None => {
+                if Self::refund_dca_order(order_id, &order).is_ok() {
+                    Self::deposit_event(Event::DcaOrderCompleted(
+                        order.owner.clone(),
+                        order_id,
+                        order.locked,
+                    ));
+                    return weight;
+                }
+            }
+        }
+        DcaOrders::<T>::insert(order_id, order);
+
+        weight
+    }
+
+    /// Swap `amount_in` of the locked assets for the owner of the order, returns the amount
+    /// received by the owner.
+    fn dca_swap(
+        order: &DcaOrder<T::AccountId, T::BlockNumber>,
+        amount_in: AssetBalance,
+    ) -> Result<AssetBalance, DispatchError> {
+        Self::ensure_not_paused(b"execute_dca_order")?;
+
+        let asset_out = *order.path.last().ok_or(Error::<T>::InvalidPath)?;
+        let balance_before = T::MultiAssetsHandler::balance_of(asset_out, &order.owner);
+        Self::inner_swap_exact_tokens_for_tokens(
+            &Self::dca_account_id(),
+            amount_in,
+            order.amount_out_min,
+            &order.path,
+            &order.owner,
+        )?;
+
+        Ok(T::MultiAssetsHandler::balance_of(asset_out, &order.owner)
+            .saturating_sub(balance_before))
+    }
+
+    fn refund_dca_order(
+        order_id: u64,
+        order: &DcaOrder<T::AccountId, T::BlockNumber>,
+    ) -> DispatchResult {
+        if !order.locked.is_zero() {
+            T::MultiAssetsHandler::transfer(
+                order.path[0],
+                &Self::dca_account_id(),
+                &order.owner,
+                order.locked,
+            )?;
+        }
+        DcaOrders::<T>::remove(order_id);
+
+        Ok(())
+    }
+}
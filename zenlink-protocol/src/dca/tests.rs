@@ -0,0 +1,244 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
+
+use super::{AssetId, Error, DCA_EXECUTION_WEIGHT, MAX_DCA_SCHEDULE_DELAY};
+use crate::mock::*;
+
+const DOT_ASSET_ID: AssetId = AssetId { chain_id: 200, asset_type: LOCAL, asset_index: 2 };
+
+const BTC_ASSET_ID: AssetId = AssetId { chain_id: 300, asset_type: RESERVED, asset_index: 3 };
+
+const ALICE: u128 = 1;
+const BOB: u128 = 2;
+
+const DOT_UNIT: u128 = 1000_000_000_000_000;
+const BTC_UNIT: u128 = 1000_000_00;
+
+fn create_pair() {
+    assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, 10000 * DOT_UNIT));
+    assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, 10000 * BTC_UNIT));
+    assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+    assert_ok!(DexPallet::inner_add_liquidity(
+        &ALICE,
+        DOT_ASSET_ID,
+        BTC_ASSET_ID,
+        1000 * DOT_UNIT,
+        1000 * BTC_UNIT,
+        0,
+        0
+    ));
+}
+
+#[test]
+fn dca_order_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pair();
+
+        let dot_balance = balance_of(DOT_ASSET_ID, &ALICE);
+        let btc_balance = balance_of(BTC_ASSET_ID, &ALICE);
+        assert_noop!(
+            DexPallet::create_dca_order(
+                Origin::signed(ALICE),
+                vec![DOT_ASSET_ID, BTC_ASSET_ID],
+                DOT_UNIT,
+                0,
+                0,
+                2
+            ),
+            Error::<Test>::InvalidDcaOrder
+        );
+        assert_ok!(DexPallet::create_dca_order(
+            Origin::signed(ALICE),
+            vec![DOT_ASSET_ID, BTC_ASSET_ID],
+            DOT_UNIT,
+            0,
+            3,
+            2
+        ));
+        assert!(has_event(crate::Event::DcaOrderCreated(ALICE, 0, DOT_UNIT, 3)));
+        assert_eq!(balance_of(DOT_ASSET_ID, &ALICE), dot_balance - 3 * DOT_UNIT);
+        assert_eq!(balance_of(DOT_ASSET_ID, &DexPallet::dca_account_id()), 3 * DOT_UNIT);
+        assert_eq!(DexPallet::dca_schedule(3), vec![0]);
+
+        // Nothing is due before the period.
+        DexPallet::on_initialize(2);
+        assert_eq!(balance_of(BTC_ASSET_ID, &ALICE), btc_balance);
+
+        System::set_block_number(3);
+        DexPallet::on_initialize(3);
+        let btc_received = balance_of(BTC_ASSET_ID, &ALICE) - btc_balance;
+        assert!(btc_received > 0);
+        assert!(has_event(crate::Event::DcaOrderExecuted(ALICE, 0, DOT_UNIT, btc_received)));
+        let order = DexPallet::dca_order(0).unwrap();
+        assert_eq!(order.executions_left, 2);
+        assert_eq!(order.locked, 2 * DOT_UNIT);
+        assert_eq!(order.next_execution, 5);
+        assert_eq!(DexPallet::dca_schedule(5), vec![0]);
+
+        for block_number in [5, 7].iter() {
+            System::set_block_number(*block_number);
+            DexPallet::on_initialize(*block_number);
+        }
+        assert!(has_event(crate::Event::DcaOrderCompleted(ALICE, 0, 0)));
+        assert_eq!(DexPallet::dca_order(0), None);
+        assert_eq!(balance_of(DOT_ASSET_ID, &ALICE), dot_balance - 3 * DOT_UNIT);
+        assert_eq!(balance_of(DOT_ASSET_ID, &DexPallet::dca_account_id()), 0);
+    });
+}
+
+#[test]
+fn failed_dca_execution_should_be_refunded() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pair();
+
+        let dot_balance = balance_of(DOT_ASSET_ID, &ALICE);
+        // One DOT is never worth two BTC in the pair.
+        assert_ok!(DexPallet::create_dca_order(
+            Origin::signed(ALICE),
+            vec![DOT_ASSET_ID, BTC_ASSET_ID],
+            DOT_UNIT,
+            2 * BTC_UNIT,
+            1,
+            1
+        ));
+
+        System::set_block_number(2);
+        DexPallet::on_initialize(2);
+        assert!(has_event(crate::Event::DcaOrderExecutionFailed(
+            ALICE,
+            0,
+            Error::<Test>::InsufficientTargetAmount.into()
+        )));
+        assert!(has_event(crate::Event::DcaOrderCompleted(ALICE, 0, DOT_UNIT)));
+        assert_eq!(DexPallet::dca_order(0), None);
+        assert_eq!(balance_of(DOT_ASSET_ID, &ALICE), dot_balance);
+    });
+}
+
+#[test]
+fn cancel_dca_order_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pair();
+
+        let dot_balance = balance_of(DOT_ASSET_ID, &ALICE);
+        assert_ok!(DexPallet::create_dca_order(
+            Origin::signed(ALICE),
+            vec![DOT_ASSET_ID, BTC_ASSET_ID],
+            DOT_UNIT,
+            0,
+            2,
+            1
+        ));
+
+        assert_noop!(
+            DexPallet::cancel_dca_order(Origin::signed(BOB), 0),
+            Error::<Test>::NotDcaOrderOwner
+        );
+        assert_noop!(
+            DexPallet::cancel_dca_order(Origin::signed(ALICE), 1),
+            Error::<Test>::DcaOrderNotExists
+        );
+        assert_ok!(DexPallet::cancel_dca_order(Origin::signed(ALICE), 0));
+        assert!(has_event(crate::Event::DcaOrderCancelled(ALICE, 0, 2 * DOT_UNIT)));
+        assert_eq!(balance_of(DOT_ASSET_ID, &ALICE), dot_balance);
+
+        // The cancelled order is skipped when it's due.
+        let btc_balance = balance_of(BTC_ASSET_ID, &ALICE);
+        System::set_block_number(2);
+        DexPallet::on_initialize(2);
+        assert_eq!(balance_of(BTC_ASSET_ID, &ALICE), btc_balance);
+    });
+}
+
+#[test]
+fn dca_executions_should_be_bounded_per_block() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pair();
+
+        for _ in 0..3 {
+            assert_ok!(DexPallet::create_dca_order(
+                Origin::signed(ALICE),
+                vec![DOT_ASSET_ID, BTC_ASSET_ID],
+                DOT_UNIT,
+                0,
+                1,
+                1
+            ));
+        }
+        // The third order is postponed to the next block.
+        assert_eq!(DexPallet::dca_schedule(2), vec![0, 1]);
+        assert_eq!(DexPallet::dca_schedule(3), vec![2]);
+        assert_eq!(DexPallet::dca_order(2).unwrap().next_execution, 3);
+
+        System::set_block_number(2);
+        assert_eq!(DexPallet::on_initialize(2), 2 * DCA_EXECUTION_WEIGHT);
+        assert_eq!(DexPallet::dca_order(0), None);
+        assert_eq!(DexPallet::dca_order(1), None);
+        assert!(DexPallet::dca_order(2).is_some());
+
+        System::set_block_number(3);
+        assert_eq!(DexPallet::on_initialize(3), DCA_EXECUTION_WEIGHT);
+        assert_eq!(DexPallet::dca_order(2), None);
+    });
+}
+
+#[test]
+fn dca_schedule_should_be_bounded() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pair();
+
+        // The path is at most `MaxSwapHops` hops.
+        assert_noop!(
+            DexPallet::create_dca_order(
+                Origin::signed(ALICE),
+                vec![DOT_ASSET_ID, BTC_ASSET_ID, DOT_ASSET_ID, BTC_ASSET_ID],
+                DOT_UNIT,
+                0,
+                1,
+                1
+            ),
+            Error::<Test>::InvalidPath
+        );
+
+        // Two orders fit in each of the blocks 2 to 12.
+        for _ in 0..2 * (MAX_DCA_SCHEDULE_DELAY + 1) {
+            assert_ok!(DexPallet::create_dca_order(
+                Origin::signed(ALICE),
+                vec![DOT_ASSET_ID, BTC_ASSET_ID],
+                DOT_UNIT,
+                0,
+                2,
+                1
+            ));
+        }
+        assert_eq!(DexPallet::dca_schedule(12).len(), 2);
+        assert_noop!(
+            DexPallet::create_dca_order(
+                Origin::signed(ALICE),
+                vec![DOT_ASSET_ID, BTC_ASSET_ID],
+                DOT_UNIT,
+                0,
+                2,
+                1
+            ),
+            Error::<Test>::DcaScheduleFull
+        );
+
+        // The orders executed at block 2 can't be scheduled within the delay any more, they
+        // are completed early.
+        crate::DcaSchedule::<Test>::insert(13, vec![100, 101]);
+        System::set_block_number(2);
+        assert_eq!(DexPallet::on_initialize(2), 2 * DCA_EXECUTION_WEIGHT);
+        assert!(has_event(crate::Event::DcaOrderCompleted(ALICE, 0, DOT_UNIT)));
+        assert_eq!(DexPallet::dca_order(0), None);
+        assert_eq!(DexPallet::dca_order(1), None);
+        assert_eq!(DexPallet::dca_schedule(13), vec![100, 101]);
+    });
+}
@@ -0,0 +1,283 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+//! # Farm Module
+//!
+//! ## Overview
+//!
+//! Liquidity mining farms created by the governance, the stakers of the liquidity of a trading
+//! pair share the rewards emitted every block between the start and end blocks, in proportion
+//! to their stakes. The staked liquidity and the rewards are held by the account of the farm,
+//! which is funded with the reward asset apart from the farm creation. The rewards are paid up
+//! to the reward balance of the farm account and the rest is paid once it's funded, the
+//! liquidity can always be unstaked. The accounting refers to the MasterChef of SushiSwap.
+
+use super::*;
+
+use frame_support::storage::{with_transaction, TransactionOutcome};
+
+#[cfg(test)]
+mod tests;
+
+/// The precision of the rewards accrued per staked liquidity.
+pub const FARM_REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+impl<T: Config> Pallet<T> {
+    /// The account ID which holds the staked liquidity and the rewards of the farm.
+    pub fn farm_account_id(farm_id: u32) -> T::AccountId {
+        T::PalletId::get().into_sub_account((b"farm", farm_id))
+    }
+
+    pub fn inner_create_farm(
+        asset_0: AssetId,
+        asset_1: AssetId,
+        reward_asset: AssetId,
+        reward_per_block: AssetBalance,
+        start_block: T::BlockNumber,
+        end_block: T::BlockNumber,
+    ) -> Result<u32, DispatchError> {
+        let pair = Self::sort_asset_id(asset_0, asset_1);
+        ensure!(Self::lp_metadata(pair).is_some(), Error::<T>::PairNotExists);
+        ensure!(
+            !reward_per_block.is_zero()
+                && start_block < end_block
+                && frame_system::Pallet::<T>::block_number() < end_block,
+            Error::<T>::InvalidFarm
+        );
+
+        let farm_id = Self::next_farm_id();
+        NextFarmId::<T>::put(farm_id.checked_add(1).ok_or(Error::<T>::Overflow)?);
+        Farms::<T>::insert(
+            farm_id,
+            Farm {
+                pair,
+                reward_asset,
+                reward_per_block,
+                start_block,
+                end_block,
+                total_staked: Zero::zero(),
+                acc_reward_per_share: Zero::zero(),
+                last_reward_block: start_block,
+            },
+        );
+
+        Self::deposit_event(Event::FarmCreated(
+            farm_id,
+            pair.0,
+            pair.1,
+            reward_asset,
+            reward_per_block,
+            start_block,
+            end_block,
+        ));
+
+        Ok(farm_id)
+    }
+
+    pub fn inner_stake_farm(
+        who: &T::AccountId,
+        farm_id: u32,
+        amount: AssetBalance,
+    ) -> DispatchResult {
+        let mut farm = Self::farm(farm_id).ok_or(Error::<T>::FarmNotExists)?;
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(now < farm.end_block, Error::<T>::FarmEnded);
+
+        Self::update_farm(&mut farm, now)?;
+        let mut stake = Self::farm_stake((farm_id, who));
+        Self::pay_farm_rewards(who, farm_id, &farm, &mut stake)?;
+
+        Self::move_staked_liquidity(farm.pair, who, &Self::farm_account_id(farm_id), amount)?;
+        stake.amount = stake.amount.checked_add(amount).ok_or(Error::<T>::Overflow)?;
+        farm.total_staked = farm.total_staked.checked_add(amount).ok_or(Error::<T>::Overflow)?;
+        stake.reward_debt = Self::accrued_farm_rewards(&farm, stake.amount)?;
+
+        FarmStakes::<T>::insert((farm_id, who), stake);
+        Farms::<T>::insert(farm_id, farm);
+
+        Self::deposit_event(Event::FarmStaked(who.clone(), farm_id, amount));
+
+        Ok(())
+    }
+
+    pub fn inner_unstake_farm(
+        who: &T::AccountId,
+        farm_id: u32,
+        amount: AssetBalance,
+    ) -> DispatchResult {
+        let mut farm = Self::farm(farm_id).ok_or(Error::<T>::FarmNotExists)?;
+        let mut stake = Self::farm_stake((farm_id, who));
+        ensure!(stake.amount >= amount, Error::<T>::InsufficientFarmStake);
+
+        Self::update_farm(&mut farm, frame_system::Pallet::<T>::block_number())?;
+        Self::move_staked_liquidity(farm.pair, &Self::farm_account_id(farm_id), who, amount)?;
+        Self::pay_farm_rewards(who, farm_id, &farm, &mut stake)?;
+
+        stake.amount -= amount;
+        farm.total_staked = farm.total_staked.saturating_sub(amount);
+        stake.reward_debt = Self::accrued_farm_rewards(&farm, stake.amount)?;
+
+        if stake.amount.is_zero() && stake.unpaid_rewards.is_zero() {
+            FarmStakes::<T>::remove((farm_id, who));
+        } else {
+            FarmStakes::<T>::insert((farm_id, who), stake);
+        }
+        Farms::<T>::insert(farm_id, farm);
+
+        Self::deposit_event(Event::FarmUnstaked(who.clone(), farm_id, amount));
+
+        Ok(())
+    }
+
+    pub fn inner_claim_farm_rewards(who: &T::AccountId, farm_id: u32) -> DispatchResult {
+        let mut farm = Self::farm(farm_id).ok_or(Error::<T>::FarmNotExists)?;
+        let mut stake = Self::farm_stake((farm_id, who));
+        ensure!(
+            !stake.amount.is_zero() || !stake.unpaid_rewards.is_zero(),
+            Error::<T>::InsufficientFarmStake
+        );
+
+        Self::update_farm(&mut farm, frame_system::Pallet::<T>::block_number())?;
+        Self::pay_farm_rewards(who, farm_id, &farm, &mut stake)?;
+        stake.reward_debt = Self::accrued_farm_rewards(&farm, stake.amount)?;
+
+        if stake.amount.is_zero() && stake.unpaid_rewards.is_zero() {
+            FarmStakes::<T>::remove((farm_id, who));
+        } else {
+            FarmStakes::<T>::insert((farm_id, who), stake);
+        }
+        Farms::<T>::insert(farm_id, farm);
+
+        Ok(())
+    }
+
+    /// Unstake all the liquidity of `who` from the farm and give up the rewards, which doesn't
+    /// depend on the reward balance of the farm account.
+    pub fn inner_emergency_unstake_farm(who: &T::AccountId, farm_id: u32) -> DispatchResult {
+        let mut farm = Self::farm(farm_id).ok_or(Error::<T>::FarmNotExists)?;
+        let stake = Self::farm_stake((farm_id, who));
+        ensure!(!stake.amount.is_zero(), Error::<T>::InsufficientFarmStake);
+
+        Self::update_farm(&mut farm, frame_system::Pallet::<T>::block_number())?;
+        Self::move_staked_liquidity(farm.pair, &Self::farm_account_id(farm_id), who, stake.amount)?;
+        farm.total_staked = farm.total_staked.saturating_sub(stake.amount);
+
+        FarmStakes::<T>::remove((farm_id, who));
+        Farms::<T>::insert(farm_id, farm);
+
+        Self::deposit_event(Event::FarmEmergencyUnstaked(who.clone(), farm_id, stake.amount));
+
+        Ok(())
+    }
+
+    /// The rewards of the farm accrued to `owner` and not claimed yet.
+    pub fn get_pending_farm_rewards(farm_id: u32, owner: &T::AccountId) -> AssetBalance {
+        let mut farm = match Self::farm(farm_id) {
+            Some(farm) => farm,
+            None => return Zero::zero(),
+        };
+        let stake = Self::farm_stake((farm_id, owner));
+
+        Self::update_farm(&mut farm, frame_system::Pallet::<T>::block_number())
+            .and_then(|_| Self::pending_farm_rewards(&farm, &stake))
+            .unwrap_or_else(|_| Zero::zero())
+    }
+
+    /// Accrue the rewards emitted from the last reward block until `now`, or the end block.
+    fn update_farm(farm: &mut Farm<T::BlockNumber>, now: T::BlockNumber) -> DispatchResult {
+        let until = now.min(farm.end_block);
+        if until <= farm.last_reward_block {
+            return Ok(());
+        }
+
+        if !farm.total_staked.is_zero() {
+            let blocks: AssetBalance = (until - farm.last_reward_block).unique_saturated_into();
+            let rewards = U256::from(blocks) * U256::from(farm.reward_per_block);
+            let reward_per_share =
+                rewards * U256::from(FARM_REWARD_PRECISION) / U256::from(farm.total_staked);
+            farm.acc_reward_per_share = farm
+                .acc_reward_per_share
+                .checked_add(reward_per_share)
+                .ok_or(Error::<T>::Overflow)?;
+        }
+        farm.last_reward_block = until;
+
+        Ok(())
+    }
+
+    /// The rewards accrued to `amount` since the farm started, in full precision as a small
+    /// early stake makes the rewards accrued per share large.
+    fn accrued_farm_rewards(
+        farm: &Farm<T::BlockNumber>,
+        amount: AssetBalance,
+    ) -> Result<U256, DispatchError> {
+        concentrated::math::mul_div(
+            U256::from(amount),
+            farm.acc_reward_per_share,
+            U256::from(FARM_REWARD_PRECISION),
+        )
+        .ok_or_else(|| Error::<T>::Overflow.into())
+    }
+
+    fn pending_farm_rewards(
+        farm: &Farm<T::BlockNumber>,
+        stake: &FarmStake,
+    ) -> Result<AssetBalance, DispatchError> {
+        let rewards: AssetBalance = Self::accrued_farm_rewards(farm, stake.amount)?
+            .saturating_sub(stake.reward_debt)
+            .try_into()
+            .map_err(|_| Error::<T>::Overflow)?;
+
+        Ok(rewards.saturating_add(stake.unpaid_rewards))
+    }
+
+    /// Pay the rewards accrued to the stake up to the reward balance of the farm account, the
+    /// rewards which can't be paid are kept as the unpaid rewards of the stake.
+    fn pay_farm_rewards(
+        who: &T::AccountId,
+        farm_id: u32,
+        farm: &Farm<T::BlockNumber>,
+        stake: &mut FarmStake,
+    ) -> DispatchResult {
+        let rewards = Self::pending_farm_rewards(farm, stake)?;
+        let farm_account = Self::farm_account_id(farm_id);
+        let payable =
+            rewards.min(T::MultiAssetsHandler::balance_of(farm.reward_asset, &farm_account));
+        let paid = with_transaction(|| {
+            match T::MultiAssetsHandler::transfer(farm.reward_asset, &farm_account, who, payable) {
+                Ok(()) => TransactionOutcome::Commit(payable),
+                Err(_) => TransactionOutcome::Rollback(Zero::zero()),
+            }
+        });
+        stake.unpaid_rewards = rewards.saturating_sub(paid);
+        if paid.is_zero() {
+            return Ok(());
+        }
+
+        Self::deposit_event(Event::FarmRewardsClaimed(
+            who.clone(),
+            farm_id,
+            farm.reward_asset,
+            paid,
+        ));
+
+        Ok(())
+    }
+
+    fn move_staked_liquidity(
+        pair: (AssetId, AssetId),
+        from: &T::AccountId,
+        to: &T::AccountId,
+        amount: AssetBalance,
+    ) -> DispatchResult {
+        LiquidityLedger::<T>::try_mutate((pair, from), |balance| -> DispatchResult {
+            *balance = balance.checked_sub(amount).ok_or(Error::<T>::InsufficientAssetBalance)?;
+            Ok(())
+        })?;
+        LiquidityLedger::<T>::mutate((pair, to), |balance| {
+            *balance = balance.saturating_add(amount)
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,327 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::DispatchError;
+
+use super::{AssetId, Error};
+use crate::mock::*;
+
+const DOT_ASSET_ID: AssetId = AssetId { chain_id: 200, asset_type: LOCAL, asset_index: 2 };
+
+const BTC_ASSET_ID: AssetId = AssetId { chain_id: 300, asset_type: RESERVED, asset_index: 3 };
+
+const ETH_ASSET_ID: AssetId = AssetId { chain_id: 400, asset_type: NATIVE, asset_index: 0 };
+
+const PAIR_DOT_BTC: (AssetId, AssetId) = (DOT_ASSET_ID, BTC_ASSET_ID);
+
+const ALICE: u128 = 1;
+const BOB: u128 = 2;
+
+const DOT_UNIT: u128 = 1000_000_000_000_000;
+const BTC_UNIT: u128 = 1000_000_00;
+const ETH_UNIT: u128 = 1000_000_000_000;
+
+const STAKE: u128 = 1_000_000_000_000;
+
+/// The DOT-BTC pair with the liquidity of ALICE and BOB, and a farm of it which emits one ETH
+/// every block from block 10 to block 20.
+fn create_farm() {
+    for who in [ALICE, BOB].iter() {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, who, 10000 * DOT_UNIT));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, who, 10000 * BTC_UNIT));
+    }
+    assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+    assert_ok!(DexPallet::inner_add_liquidity(
+        &ALICE,
+        DOT_ASSET_ID,
+        BTC_ASSET_ID,
+        1000 * DOT_UNIT,
+        1000 * BTC_UNIT,
+        0,
+        0
+    ));
+    assert_ok!(DexPallet::inner_add_liquidity(
+        &BOB,
+        DOT_ASSET_ID,
+        BTC_ASSET_ID,
+        10 * DOT_UNIT,
+        10 * BTC_UNIT,
+        0,
+        0
+    ));
+
+    assert_ok!(DexPallet::create_farm(
+        Origin::root(),
+        DOT_ASSET_ID,
+        BTC_ASSET_ID,
+        ETH_ASSET_ID,
+        ETH_UNIT,
+        10,
+        20
+    ));
+    assert_ok!(DexPallet::foreign_mint(
+        ETH_ASSET_ID,
+        &DexPallet::farm_account_id(0),
+        100 * ETH_UNIT
+    ));
+}
+
+#[test]
+fn create_farm_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_farm();
+
+        assert!(has_event(crate::Event::FarmCreated(
+            0,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            ETH_ASSET_ID,
+            ETH_UNIT,
+            10,
+            20
+        )));
+        let farm = DexPallet::farm(0).unwrap();
+        assert_eq!(farm.pair, PAIR_DOT_BTC);
+        assert_eq!(farm.last_reward_block, 10);
+        assert_eq!(DexPallet::next_farm_id(), 1);
+
+        assert_noop!(
+            DexPallet::create_farm(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                ETH_ASSET_ID,
+                ETH_UNIT,
+                10,
+                20
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            DexPallet::create_farm(
+                Origin::root(),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                ETH_ASSET_ID,
+                ETH_UNIT,
+                20,
+                20
+            ),
+            Error::<Test>::InvalidFarm
+        );
+        assert_noop!(
+            DexPallet::create_farm(
+                Origin::root(),
+                DOT_ASSET_ID,
+                ETH_ASSET_ID,
+                ETH_ASSET_ID,
+                ETH_UNIT,
+                10,
+                20
+            ),
+            Error::<Test>::PairNotExists
+        );
+    });
+}
+
+#[test]
+fn farm_rewards_should_be_shared_by_stakes() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(5);
+        create_farm();
+
+        let alice_liquidity = DexPallet::lp_ledger((PAIR_DOT_BTC, ALICE));
+        assert_ok!(DexPallet::stake_farm(Origin::signed(ALICE), 0, STAKE));
+        assert!(has_event(crate::Event::FarmStaked(ALICE, 0, STAKE)));
+        assert_eq!(DexPallet::lp_ledger((PAIR_DOT_BTC, ALICE)), alice_liquidity - STAKE);
+        assert_eq!(DexPallet::lp_ledger((PAIR_DOT_BTC, DexPallet::farm_account_id(0))), STAKE);
+        assert_eq!(DexPallet::get_pending_farm_rewards(0, &ALICE), 0);
+
+        // ALICE alone earns the rewards of blocks 10 to 12.
+        System::set_block_number(12);
+        assert_eq!(DexPallet::get_pending_farm_rewards(0, &ALICE), 2 * ETH_UNIT);
+        assert_ok!(DexPallet::claim_farm_rewards(Origin::signed(ALICE), 0));
+        assert!(has_event(crate::Event::FarmRewardsClaimed(ALICE, 0, ETH_ASSET_ID, 2 * ETH_UNIT)));
+        assert_eq!(balance_of(ETH_ASSET_ID, &ALICE), 2 * ETH_UNIT);
+        assert_eq!(DexPallet::get_pending_farm_rewards(0, &ALICE), 0);
+
+        // ALICE and BOB share the rewards of blocks 15 to 20.
+        System::set_block_number(15);
+        assert_ok!(DexPallet::stake_farm(Origin::signed(BOB), 0, STAKE));
+
+        System::set_block_number(25);
+        assert_eq!(DexPallet::get_pending_farm_rewards(0, &ALICE), 5_500_000_000_000);
+        assert_eq!(DexPallet::get_pending_farm_rewards(0, &BOB), 2_500_000_000_000);
+
+        assert_ok!(DexPallet::unstake_farm(Origin::signed(ALICE), 0, STAKE));
+        assert!(has_event(crate::Event::FarmUnstaked(ALICE, 0, STAKE)));
+        assert_eq!(balance_of(ETH_ASSET_ID, &ALICE), 7_500_000_000_000);
+        assert_eq!(DexPallet::lp_ledger((PAIR_DOT_BTC, ALICE)), alice_liquidity);
+        assert_eq!(DexPallet::farm_stake((0, ALICE)).amount, 0);
+
+        assert_ok!(DexPallet::claim_farm_rewards(Origin::signed(BOB), 0));
+        assert_eq!(balance_of(ETH_ASSET_ID, &BOB), 2_500_000_000_000);
+        assert_eq!(balance_of(ETH_ASSET_ID, &DexPallet::farm_account_id(0)), 90 * ETH_UNIT);
+        assert_eq!(DexPallet::farm(0).unwrap().total_staked, STAKE);
+    });
+}
+
+#[test]
+fn invalid_farm_stakes_should_not_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(5);
+        create_farm();
+
+        let alice_liquidity = DexPallet::lp_ledger((PAIR_DOT_BTC, ALICE));
+        assert_noop!(
+            DexPallet::stake_farm(Origin::signed(ALICE), 1, STAKE),
+            Error::<Test>::FarmNotExists
+        );
+        assert_noop!(
+            DexPallet::stake_farm(Origin::signed(ALICE), 0, alice_liquidity + 1),
+            Error::<Test>::InsufficientAssetBalance
+        );
+        assert_noop!(
+            DexPallet::unstake_farm(Origin::signed(ALICE), 0, STAKE),
+            Error::<Test>::InsufficientFarmStake
+        );
+        assert_noop!(
+            DexPallet::claim_farm_rewards(Origin::signed(ALICE), 0),
+            Error::<Test>::InsufficientFarmStake
+        );
+
+        System::set_block_number(20);
+        assert_noop!(
+            DexPallet::stake_farm(Origin::signed(ALICE), 0, STAKE),
+            Error::<Test>::FarmEnded
+        );
+    });
+}
+
+#[test]
+fn unstake_from_unfunded_farm_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(5);
+        create_farm();
+        assert_ok!(DexPallet::create_farm(
+            Origin::root(),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            ETH_ASSET_ID,
+            ETH_UNIT,
+            10,
+            20
+        ));
+
+        let alice_liquidity = DexPallet::lp_ledger((PAIR_DOT_BTC, ALICE));
+        assert_ok!(DexPallet::stake_farm(Origin::signed(ALICE), 1, STAKE));
+
+        // The farm account has no rewards, the liquidity is unstaked and the rewards are kept.
+        System::set_block_number(15);
+        assert_ok!(DexPallet::unstake_farm(Origin::signed(ALICE), 1, STAKE));
+        assert_eq!(DexPallet::lp_ledger((PAIR_DOT_BTC, ALICE)), alice_liquidity);
+        assert_eq!(balance_of(ETH_ASSET_ID, &ALICE), 0);
+        assert_eq!(DexPallet::farm_stake((1, ALICE)).unpaid_rewards, 5 * ETH_UNIT);
+        assert_eq!(DexPallet::get_pending_farm_rewards(1, &ALICE), 5 * ETH_UNIT);
+        assert_eq!(DexPallet::farm(1).unwrap().total_staked, 0);
+
+        // The rewards are paid as the farm account is funded.
+        assert_ok!(DexPallet::foreign_mint(
+            ETH_ASSET_ID,
+            &DexPallet::farm_account_id(1),
+            2 * ETH_UNIT
+        ));
+        assert_ok!(DexPallet::claim_farm_rewards(Origin::signed(ALICE), 1));
+        assert!(has_event(crate::Event::FarmRewardsClaimed(ALICE, 1, ETH_ASSET_ID, 2 * ETH_UNIT)));
+        assert_eq!(balance_of(ETH_ASSET_ID, &ALICE), 2 * ETH_UNIT);
+        assert_eq!(DexPallet::get_pending_farm_rewards(1, &ALICE), 3 * ETH_UNIT);
+
+        assert_ok!(DexPallet::foreign_mint(
+            ETH_ASSET_ID,
+            &DexPallet::farm_account_id(1),
+            10 * ETH_UNIT
+        ));
+        assert_ok!(DexPallet::claim_farm_rewards(Origin::signed(ALICE), 1));
+        assert_eq!(balance_of(ETH_ASSET_ID, &ALICE), 5 * ETH_UNIT);
+        assert_eq!(DexPallet::farm_stake((1, ALICE)), Default::default());
+        assert_noop!(
+            DexPallet::claim_farm_rewards(Origin::signed(ALICE), 1),
+            Error::<Test>::InsufficientFarmStake
+        );
+    });
+}
+
+#[test]
+fn emergency_unstake_farm_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(5);
+        create_farm();
+
+        let alice_liquidity = DexPallet::lp_ledger((PAIR_DOT_BTC, ALICE));
+        assert_ok!(DexPallet::stake_farm(Origin::signed(ALICE), 0, STAKE));
+        assert_ok!(DexPallet::stake_farm(Origin::signed(BOB), 0, STAKE));
+
+        System::set_block_number(15);
+        assert_ok!(DexPallet::emergency_unstake_farm(Origin::signed(ALICE), 0));
+        assert!(has_event(crate::Event::FarmEmergencyUnstaked(ALICE, 0, STAKE)));
+        assert_eq!(DexPallet::lp_ledger((PAIR_DOT_BTC, ALICE)), alice_liquidity);
+        assert_eq!(balance_of(ETH_ASSET_ID, &ALICE), 0);
+        assert_eq!(DexPallet::farm_stake((0, ALICE)), Default::default());
+        assert_eq!(DexPallet::farm(0).unwrap().total_staked, STAKE);
+        assert_noop!(
+            DexPallet::emergency_unstake_farm(Origin::signed(ALICE), 0),
+            Error::<Test>::InsufficientFarmStake
+        );
+
+        // BOB shares the rewards until ALICE leaves and earns the rest alone.
+        System::set_block_number(20);
+        assert_eq!(DexPallet::get_pending_farm_rewards(0, &BOB), 7_500_000_000_000);
+    });
+}
+
+#[test]
+fn small_early_stake_should_not_overflow_farm() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(5);
+        create_farm();
+        let reward_per_block = 1_000_000_000_000_000_000_000_000_000_000;
+        assert_ok!(DexPallet::create_farm(
+            Origin::root(),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            ETH_ASSET_ID,
+            reward_per_block,
+            10,
+            20
+        ));
+        assert_ok!(DexPallet::foreign_mint(
+            ETH_ASSET_ID,
+            &DexPallet::farm_account_id(1),
+            2 * reward_per_block
+        ));
+
+        // ALICE alone stakes one unit, the rewards accrued per share exceed u128 at block 11.
+        assert_ok!(DexPallet::stake_farm(Origin::signed(ALICE), 1, 1));
+        System::set_block_number(11);
+        assert_ok!(DexPallet::stake_farm(Origin::signed(BOB), 1, STAKE - 1));
+        assert_eq!(DexPallet::get_pending_farm_rewards(1, &ALICE), reward_per_block);
+
+        System::set_block_number(12);
+        assert_eq!(
+            DexPallet::get_pending_farm_rewards(1, &ALICE),
+            reward_per_block + 1_000_000_000_000_000_000
+        );
+        assert_eq!(
+            DexPallet::get_pending_farm_rewards(1, &BOB),
+            reward_per_block - 1_000_000_000_000_000_000
+        );
+
+        assert_ok!(DexPallet::unstake_farm(Origin::signed(BOB), 1, STAKE - 1));
+        assert_eq!(balance_of(ETH_ASSET_ID, &BOB), reward_per_block - 1_000_000_000_000_000_000);
+        assert_ok!(DexPallet::unstake_farm(Origin::signed(ALICE), 1, 1));
+        assert_eq!(balance_of(ETH_ASSET_ID, &ALICE), reward_per_block + 1_000_000_000_000_000_000);
+        assert_eq!(balance_of(ETH_ASSET_ID, &DexPallet::farm_account_id(1)), 0);
+    });
+}
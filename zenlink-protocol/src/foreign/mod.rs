@@ -10,8 +10,6 @@
 
 use super::*;
 
-#[cfg(test)]
-mod mock;
 #[cfg(test)]
 mod tests;
 
@@ -3,7 +3,8 @@
 
 use frame_support::{assert_noop, assert_ok};
 
-use super::{mock::*, AssetId, Error, MultiAssetsHandler};
+use super::{AssetId, Error, MultiAssetsHandler};
+use crate::mock::*;
 
 // Native Currency
 const DEV_ASSET_ID: AssetId = AssetId { chain_id: 0, asset_type: NATIVE, asset_index: 0 };
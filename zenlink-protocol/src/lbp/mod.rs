@@ -0,0 +1,408 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+//! # Liquidity Bootstrapping Pool Module
+//!
+//! ## Overview
+//!
+//! Pools of two assets for token launches, the weights of the assets move linearly from the
+//! start weights to the end weights over the blocks of the sale, so the price falls until it
+//! meets the demand. The swap mechanism refers to the liquidity bootstrapping pools of
+//! Balancer, the pools are apart from the swap paths and only swapped by their own calls.
+//! The pools are created by `CreatePairOrigin`, and a sale ends within `MAX_LBP_DURATION`
+//! blocks.
+
+use super::*;
+
+#[cfg(test)]
+mod tests;
+
+/// The max number of blocks from the creation or update of a pool to the end of the sale.
+pub const MAX_LBP_DURATION: u32 = 100_800;
+
+impl<T: Config> Pallet<T> {
+    /// The account ID of the liquidity bootstrapping pool of the pair
+    pub fn lbp_account_id(asset_0: AssetId, asset_1: AssetId) -> T::AccountId {
+        let (asset_0, asset_1) = Self::sort_asset_id(asset_0, asset_1);
+        let pool_hash: T::Hash = T::Hashing::hash_of(&(b"lbp", asset_0, asset_1));
+
+        T::PalletId::get().into_sub_account(pool_hash.as_ref())
+    }
+
+    /// Create the liquidity bootstrapping pool of the pair with the assets of the owner, the
+    /// weights are of `asset_0`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn inner_create_lbp(
+        owner: &T::AccountId,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        amount_0: AssetBalance,
+        amount_1: AssetBalance,
+        start_weight: u128,
+        end_weight: u128,
+        start_block: T::BlockNumber,
+        end_block: T::BlockNumber,
+    ) -> DispatchResult {
+        let pair = Self::sort_asset_id(asset_0, asset_1);
+        ensure!(!LbpPools::<T>::contains_key(pair), Error::<T>::PoolAlreadyExists);
+        ensure!(amount_0 > Zero::zero() && amount_1 > Zero::zero(), Error::<T>::InvalidPoolAssets);
+        let (start_weight, end_weight) = Self::check_lbp_schedule(
+            asset_0,
+            asset_1,
+            start_weight,
+            end_weight,
+            start_block,
+            end_block,
+        )?;
+
+        let pool_account = Self::lbp_account_id(asset_0, asset_1);
+        T::MultiAssetsHandler::transfer(asset_0, owner, &pool_account, amount_0)?;
+        T::MultiAssetsHandler::transfer(asset_1, owner, &pool_account, amount_1)?;
+
+        let reserves = if asset_0 < asset_1 { (amount_0, amount_1) } else { (amount_1, amount_0) };
+        LbpPools::<T>::insert(
+            pair,
+            LbpPool {
+                owner: owner.clone(),
+                pool_account,
+                reserves,
+                start_weight,
+                end_weight,
+                start_block,
+                end_block,
+                fee: T::GetExchangeFee::get(),
+            },
+        );
+
+        Self::deposit_event(Event::LbpCreated(
+            owner.clone(),
+            pair.0,
+            pair.1,
+            reserves.0,
+            reserves.1,
+        ));
+        Self::deposit_event(Event::LbpScheduleUpdated(
+            pair.0,
+            pair.1,
+            start_weight,
+            end_weight,
+            start_block,
+            end_block,
+        ));
+
+        Ok(())
+    }
+
+    /// Change the weights and blocks of the sale before it starts, the weights are of
+    /// `asset_0`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn inner_update_lbp(
+        owner: &T::AccountId,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        start_weight: u128,
+        end_weight: u128,
+        start_block: T::BlockNumber,
+        end_block: T::BlockNumber,
+    ) -> DispatchResult {
+        let pair = Self::sort_asset_id(asset_0, asset_1);
+
+        LbpPools::<T>::try_mutate(pair, |pool| {
+            let pool = pool.as_mut().ok_or(Error::<T>::PoolNotExists)?;
+            ensure!(pool.owner == *owner, Error::<T>::NotLbpOwner);
+            ensure!(
+                frame_system::Pallet::<T>::block_number() < pool.start_block,
+                Error::<T>::LbpAlreadyStarted
+            );
+            let (start_weight, end_weight) = Self::check_lbp_schedule(
+                asset_0,
+                asset_1,
+                start_weight,
+                end_weight,
+                start_block,
+                end_block,
+            )?;
+
+            pool.start_weight = start_weight;
+            pool.end_weight = end_weight;
+            pool.start_block = start_block;
+            pool.end_block = end_block;
+
+            Self::deposit_event(Event::LbpScheduleUpdated(
+                pair.0,
+                pair.1,
+                start_weight,
+                end_weight,
+                start_block,
+                end_block,
+            ));
+
+            Ok(())
+        })
+    }
+
+    /// Withdraw all the assets of the pool to the recipient after the sale ends, the pool
+    /// is removed.
+    pub fn inner_withdraw_lbp(
+        owner: &T::AccountId,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        let pair = Self::sort_asset_id(asset_0, asset_1);
+        let pool = Self::lbp_pool(pair).ok_or(Error::<T>::PoolNotExists)?;
+        ensure!(pool.owner == *owner, Error::<T>::NotLbpOwner);
+        ensure!(
+            frame_system::Pallet::<T>::block_number() >= pool.end_block,
+            Error::<T>::LbpNotEnded
+        );
+
+        let (amount_0, amount_1) = pool.reserves;
+        T::MultiAssetsHandler::transfer(pair.0, &pool.pool_account, recipient, amount_0)?;
+        T::MultiAssetsHandler::transfer(pair.1, &pool.pool_account, recipient, amount_1)?;
+        LbpPools::<T>::remove(pair);
+
+        Self::deposit_event(Event::LbpWithdrawn(
+            owner.clone(),
+            recipient.clone(),
+            pair.0,
+            pair.1,
+            amount_0,
+            amount_1,
+        ));
+
+        Ok(())
+    }
+
+    pub fn inner_lbp_swap_exact_assets_for_assets(
+        who: &T::AccountId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: AssetBalance,
+        amount_out_min: AssetBalance,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        Self::ensure_lbp_active(asset_in, asset_out)?;
+        let amount_out = Self::get_lbp_amount_out(asset_in, asset_out, amount_in);
+        ensure!(amount_out > Zero::zero(), Error::<T>::ExcessiveRatio);
+        ensure!(amount_out >= amount_out_min, Error::<T>::InsufficientTargetAmount);
+
+        Self::lbp_swap(who, asset_in, asset_out, amount_in, amount_out, recipient)
+    }
+
+    pub fn inner_lbp_swap_assets_for_exact_assets(
+        who: &T::AccountId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_out: AssetBalance,
+        amount_in_max: AssetBalance,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        Self::ensure_lbp_active(asset_in, asset_out)?;
+        let amount_in = Self::get_lbp_amount_in(asset_in, asset_out, amount_out);
+        ensure!(amount_in > Zero::zero(), Error::<T>::ExcessiveRatio);
+        ensure!(amount_in <= amount_in_max, Error::<T>::ExcessiveSoldAmount);
+
+        Self::lbp_swap(who, asset_in, asset_out, amount_in, amount_out, recipient)
+    }
+
+    /// The current weights of the pool of the pair, in the order of `asset_0` and `asset_1`.
+    pub fn get_lbp_weights(asset_0: AssetId, asset_1: AssetId) -> Option<(u128, u128)> {
+        let pool = Self::lbp_pool(Self::sort_asset_id(asset_0, asset_1))?;
+        let (weight_0, weight_1) =
+            Self::lbp_weights(&pool, frame_system::Pallet::<T>::block_number());
+
+        Some(if asset_0 < asset_1 { (weight_0, weight_1) } else { (weight_1, weight_0) })
+    }
+
+    /// The current spot price of `asset_in` quoted in `asset_out` without the exchange fee,
+    /// in `BONE`.
+    pub fn get_lbp_spot_price(asset_in: AssetId, asset_out: AssetId) -> AssetBalance {
+        Self::lbp_state(asset_in, asset_out)
+            .and_then(|(reserve_in, weight_in, reserve_out, weight_out, _)| {
+                let numerator = Self::bdiv(U256::from(reserve_out), U256::from(weight_out))?;
+                let denominator = Self::bdiv(U256::from(reserve_in), U256::from(weight_in))?;
+
+                Self::bdiv(numerator, denominator)
+            })
+            .and_then(|n| TryInto::<AssetBalance>::try_into(n).ok())
+            .unwrap_or_else(Zero::zero)
+    }
+
+    /// The amount of `asset_out` got by swapping `amount_in` of `asset_in` in the pool at the
+    /// current weights.
+    pub fn get_lbp_amount_out(
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: AssetBalance,
+    ) -> AssetBalance {
+        Self::lbp_state(asset_in, asset_out)
+            .and_then(|(reserve_in, weight_in, reserve_out, weight_out, fee)| {
+                let reserve_in = U256::from(reserve_in);
+                if U256::from(amount_in) > Self::bmul(reserve_in, U256::from(MAX_IN_RATIO)) {
+                    return None;
+                }
+
+                Self::calculate_out_given_in(
+                    reserve_in,
+                    U256::from(weight_in),
+                    U256::from(reserve_out),
+                    U256::from(weight_out),
+                    U256::from(amount_in),
+                    Self::weighted_fee(fee),
+                )
+            })
+            .and_then(|n| TryInto::<AssetBalance>::try_into(n).ok())
+            .unwrap_or_else(Zero::zero)
+    }
+
+    /// The amount of `asset_in` needed to get `amount_out` of `asset_out` from the pool at
+    /// the current weights.
+    pub fn get_lbp_amount_in(
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_out: AssetBalance,
+    ) -> AssetBalance {
+        Self::lbp_state(asset_in, asset_out)
+            .and_then(|(reserve_in, weight_in, reserve_out, weight_out, fee)| {
+                let reserve_out = U256::from(reserve_out);
+                if U256::from(amount_out) > Self::bmul(reserve_out, U256::from(MAX_OUT_RATIO)) {
+                    return None;
+                }
+
+                Self::calculate_in_given_out(
+                    U256::from(reserve_in),
+                    U256::from(weight_in),
+                    reserve_out,
+                    U256::from(weight_out),
+                    U256::from(amount_out),
+                    Self::weighted_fee(fee),
+                )
+            })
+            .and_then(|n| n.checked_add(U256::one()))
+            .and_then(|n| TryInto::<AssetBalance>::try_into(n).ok())
+            .unwrap_or_else(Zero::zero)
+    }
+
+    /// The weights of `asset_0` and `asset_1` of the pool at block `now`.
+    pub(crate) fn lbp_weights(
+        pool: &LbpPool<T::AccountId, T::BlockNumber>,
+        now: T::BlockNumber,
+    ) -> (u128, u128) {
+        let weight_0 = if now <= pool.start_block {
+            pool.start_weight
+        } else if now >= pool.end_block {
+            pool.end_weight
+        } else {
+            let elapsed = U256::from((now - pool.start_block).saturated_into::<u128>());
+            let duration = U256::from((pool.end_block - pool.start_block).saturated_into::<u128>());
+            if pool.end_weight >= pool.start_weight {
+                let delta = U256::from(pool.end_weight - pool.start_weight) * elapsed / duration;
+                pool.start_weight.saturating_add(delta.low_u128())
+            } else {
+                let delta = U256::from(pool.start_weight - pool.end_weight) * elapsed / duration;
+                pool.start_weight.saturating_sub(delta.low_u128())
+            }
+        };
+
+        (weight_0, BONE.saturating_sub(weight_0))
+    }
+
+    /// The reserves and current weights of the pool in the order of `asset_in` and
+    /// `asset_out`, with the exchange fee.
+    fn lbp_state(
+        asset_in: AssetId,
+        asset_out: AssetId,
+    ) -> Option<(AssetBalance, u128, AssetBalance, u128, (u32, u32))> {
+        let pool = Self::lbp_pool(Self::sort_asset_id(asset_in, asset_out))?;
+        let (reserve_0, reserve_1) = pool.reserves;
+        let (weight_0, weight_1) =
+            Self::lbp_weights(&pool, frame_system::Pallet::<T>::block_number());
+
+        Some(if asset_in < asset_out {
+            (reserve_0, weight_0, reserve_1, weight_1, pool.fee)
+        } else {
+            (reserve_1, weight_1, reserve_0, weight_0, pool.fee)
+        })
+    }
+
+    fn ensure_lbp_active(asset_in: AssetId, asset_out: AssetId) -> DispatchResult {
+        let pool = Self::lbp_pool(Self::sort_asset_id(asset_in, asset_out))
+            .ok_or(Error::<T>::PoolNotExists)?;
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(now >= pool.start_block && now < pool.end_block, Error::<T>::LbpNotActive);
+
+        Ok(())
+    }
+
+    fn lbp_swap(
+        who: &T::AccountId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: AssetBalance,
+        amount_out: AssetBalance,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        LbpPools::<T>::try_mutate(Self::sort_asset_id(asset_in, asset_out), |pool| {
+            let pool = pool.as_mut().ok_or(Error::<T>::PoolNotExists)?;
+            let (reserve_in, reserve_out) = if asset_in < asset_out {
+                (&mut pool.reserves.0, &mut pool.reserves.1)
+            } else {
+                (&mut pool.reserves.1, &mut pool.reserves.0)
+            };
+            *reserve_in = reserve_in.checked_add(amount_in).ok_or(Error::<T>::Overflow)?;
+            *reserve_out =
+                reserve_out.checked_sub(amount_out).ok_or(Error::<T>::InsufficientPairReserve)?;
+
+            T::MultiAssetsHandler::transfer(asset_in, who, &pool.pool_account, amount_in)?;
+            T::MultiAssetsHandler::transfer(asset_out, &pool.pool_account, recipient, amount_out)
+        })?;
+
+        let (_, weight_in, _, weight_out, _) =
+            Self::lbp_state(asset_in, asset_out).ok_or(Error::<T>::PoolNotExists)?;
+        Self::deposit_event(Event::LbpSwapped(
+            who.clone(),
+            recipient.clone(),
+            asset_in,
+            asset_out,
+            amount_in,
+            amount_out,
+            weight_in,
+            weight_out,
+            Self::get_lbp_spot_price(asset_in, asset_out),
+        ));
+
+        Ok(())
+    }
+
+    /// The weights of `asset_0` given in the order of the sorted pair, after they are
+    /// checked with the blocks.
+    fn check_lbp_schedule(
+        asset_0: AssetId,
+        asset_1: AssetId,
+        start_weight: u128,
+        end_weight: u128,
+        start_block: T::BlockNumber,
+        end_block: T::BlockNumber,
+    ) -> Result<(u128, u128), DispatchError> {
+        let max_weight = BONE - MIN_WEIGHT;
+        ensure!(
+            (MIN_WEIGHT..=max_weight).contains(&start_weight)
+                && (MIN_WEIGHT..=max_weight).contains(&end_weight),
+            Error::<T>::InvalidWeights
+        );
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(
+            start_block >= now
+                && end_block > start_block
+                && end_block <= now.saturating_add(MAX_LBP_DURATION.into()),
+            Error::<T>::InvalidLbpSchedule
+        );
+
+        Ok(if asset_0 < asset_1 {
+            (start_weight, end_weight)
+        } else {
+            (BONE - start_weight, BONE - end_weight)
+        })
+    }
+}
@@ -0,0 +1,337 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::DispatchError;
+
+use super::{AssetId, Error, MultiAssetsHandler, BONE, MAX_LBP_DURATION};
+use crate::mock::*;
+
+const DOT_ASSET_ID: AssetId = AssetId { chain_id: 200, asset_type: LOCAL, asset_index: 2 };
+
+const BTC_ASSET_ID: AssetId = AssetId { chain_id: 300, asset_type: RESERVED, asset_index: 3 };
+
+const ALICE: u128 = 1;
+const BOB: u128 = 2;
+const CHARLIE: u128 = 3;
+
+const DOT_UNIT: u128 = 1000_000_000_000_000;
+const BTC_UNIT: u128 = 1000_000_00;
+
+const PAIR_DOT_BTC: (AssetId, AssetId) = (DOT_ASSET_ID, BTC_ASSET_ID);
+
+/// The pool of 10000 DOT and 1000 BTC, whose weight of DOT moves from 0.9 to 0.5 between
+/// block 10 and block 110, created with the assets in the reversed order.
+fn create_dot_btc_lbp() {
+    assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, 10000 * DOT_UNIT));
+    assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, 1000 * BTC_UNIT));
+    for who in [BOB, CHARLIE].iter() {
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, who, 100 * BTC_UNIT));
+    }
+
+    assert_ok!(DexPallet::create_lbp(
+        Origin::signed(ALICE),
+        BTC_ASSET_ID,
+        DOT_ASSET_ID,
+        1000 * BTC_UNIT,
+        10000 * DOT_UNIT,
+        BONE / 10,
+        BONE / 2,
+        10,
+        110
+    ));
+}
+
+#[test]
+fn lbp_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_dot_btc_lbp();
+
+        let pool = DexPallet::lbp_pool(PAIR_DOT_BTC).unwrap();
+        assert_eq!(pool.reserves, (10000 * DOT_UNIT, 1000 * BTC_UNIT));
+        assert_eq!((pool.start_weight, pool.end_weight), (BONE * 9 / 10, BONE / 2));
+        assert_eq!(
+            DexPallet::get_lbp_weights(BTC_ASSET_ID, DOT_ASSET_ID),
+            Some((BONE / 10, BONE * 9 / 10))
+        );
+        assert_eq!(
+            DexPallet::get_lbp_spot_price(BTC_ASSET_ID, DOT_ASSET_ID),
+            11111111111111111111000000
+        );
+        assert_noop!(
+            DexPallet::lbp_swap_exact_assets_for_assets(
+                Origin::signed(BOB),
+                BTC_ASSET_ID,
+                DOT_ASSET_ID,
+                10 * BTC_UNIT,
+                0,
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::LbpNotActive
+        );
+
+        // The price of DOT falls as the weight of it does.
+        System::set_block_number(60);
+        assert_eq!(
+            DexPallet::get_lbp_weights(DOT_ASSET_ID, BTC_ASSET_ID),
+            Some((BONE * 7 / 10, BONE * 3 / 10))
+        );
+        assert_eq!(
+            DexPallet::get_lbp_spot_price(BTC_ASSET_ID, DOT_ASSET_ID),
+            42857142857185714284857186
+        );
+        assert_noop!(
+            DexPallet::update_lbp(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                BONE / 2,
+                BONE / 2,
+                100,
+                200
+            ),
+            Error::<Test>::LbpAlreadyStarted
+        );
+
+        assert_eq!(
+            DexPallet::get_lbp_amount_out(BTC_ASSET_ID, DOT_ASSET_ID, 10 * BTC_UNIT),
+            42426718052994560
+        );
+        assert_noop!(
+            DexPallet::lbp_swap_exact_assets_for_assets(
+                Origin::signed(BOB),
+                BTC_ASSET_ID,
+                DOT_ASSET_ID,
+                10 * BTC_UNIT,
+                42426718052994561,
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientTargetAmount
+        );
+        assert_ok!(DexPallet::lbp_swap_exact_assets_for_assets(
+            Origin::signed(BOB),
+            BTC_ASSET_ID,
+            DOT_ASSET_ID,
+            10 * BTC_UNIT,
+            42426718052994560,
+            BOB,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &BOB),
+            42426718052994560
+        );
+        assert!(System::events().iter().any(|record| record.event
+            == Event::Zenlink(crate::Event::LbpSwapped(
+                BOB,
+                BOB,
+                BTC_ASSET_ID,
+                DOT_ASSET_ID,
+                10 * BTC_UNIT,
+                42426718052994560,
+                BONE * 3 / 10,
+                BONE * 7 / 10,
+                42252786203410805331481771,
+            ))));
+
+        assert_eq!(
+            DexPallet::get_lbp_amount_in(BTC_ASSET_ID, DOT_ASSET_ID, 100 * DOT_UNIT),
+            2414145889
+        );
+        assert_noop!(
+            DexPallet::lbp_swap_assets_for_exact_assets(
+                Origin::signed(CHARLIE),
+                BTC_ASSET_ID,
+                DOT_ASSET_ID,
+                100 * DOT_UNIT,
+                2414145888,
+                CHARLIE,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::ExcessiveSoldAmount
+        );
+        assert_ok!(DexPallet::lbp_swap_assets_for_exact_assets(
+            Origin::signed(CHARLIE),
+            BTC_ASSET_ID,
+            DOT_ASSET_ID,
+            100 * DOT_UNIT,
+            2414145889,
+            CHARLIE,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &CHARLIE),
+            100 * BTC_UNIT - 2414145889
+        );
+
+        assert_noop!(
+            DexPallet::withdraw_lbp(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID, ALICE),
+            Error::<Test>::LbpNotEnded
+        );
+
+        System::set_block_number(110);
+        assert_noop!(
+            DexPallet::lbp_swap_exact_assets_for_assets(
+                Origin::signed(BOB),
+                BTC_ASSET_ID,
+                DOT_ASSET_ID,
+                BTC_UNIT,
+                0,
+                BOB,
+                Deadline::BlockNumber(200)
+            ),
+            Error::<Test>::LbpNotActive
+        );
+        assert_noop!(
+            DexPallet::withdraw_lbp(Origin::signed(BOB), DOT_ASSET_ID, BTC_ASSET_ID, BOB),
+            Error::<Test>::NotLbpOwner
+        );
+        assert_ok!(DexPallet::withdraw_lbp(
+            Origin::signed(ALICE),
+            BTC_ASSET_ID,
+            DOT_ASSET_ID,
+            ALICE
+        ));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &ALICE),
+            9857573281947005440
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &ALICE),
+            103414145889
+        );
+        assert!(DexPallet::lbp_pool(PAIR_DOT_BTC).is_none());
+    });
+}
+
+#[test]
+fn update_lbp_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_dot_btc_lbp();
+
+        assert_noop!(
+            DexPallet::create_lbp(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                DOT_UNIT,
+                BTC_UNIT,
+                BONE / 2,
+                BONE / 2,
+                10,
+                110
+            ),
+            Error::<Test>::PoolAlreadyExists
+        );
+        assert_noop!(
+            DexPallet::update_lbp(
+                Origin::signed(BOB),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                BONE / 2,
+                BONE / 2,
+                20,
+                120
+            ),
+            Error::<Test>::NotLbpOwner
+        );
+        assert_noop!(
+            DexPallet::update_lbp(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                BONE,
+                BONE / 2,
+                20,
+                120
+            ),
+            Error::<Test>::InvalidWeights
+        );
+        assert_noop!(
+            DexPallet::update_lbp(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                BONE * 9 / 10,
+                BONE / 2,
+                120,
+                120
+            ),
+            Error::<Test>::InvalidLbpSchedule
+        );
+
+        // The weight of DOT moves from 0.8 to 0.4 between block 20 and block 60.
+        assert_ok!(DexPallet::update_lbp(
+            Origin::signed(ALICE),
+            BTC_ASSET_ID,
+            DOT_ASSET_ID,
+            BONE / 5,
+            BONE * 3 / 5,
+            20,
+            60
+        ));
+        System::set_block_number(30);
+        assert_eq!(
+            DexPallet::get_lbp_weights(DOT_ASSET_ID, BTC_ASSET_ID),
+            Some((BONE * 7 / 10, BONE * 3 / 10))
+        );
+        System::set_block_number(100);
+        assert_eq!(
+            DexPallet::get_lbp_weights(DOT_ASSET_ID, BTC_ASSET_ID),
+            Some((BONE * 2 / 5, BONE * 3 / 5))
+        );
+    });
+}
+
+#[test]
+fn create_lbp_should_not_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, 10000 * DOT_UNIT));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, 1000 * BTC_UNIT));
+
+        assert_noop!(
+            DexPallet::create_lbp(
+                Origin::root(),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                DOT_UNIT,
+                BTC_UNIT,
+                BONE / 2,
+                BONE / 2,
+                10,
+                110
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            DexPallet::create_lbp(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                DOT_UNIT,
+                BTC_UNIT,
+                BONE / 2,
+                BONE / 2,
+                10,
+                2 + MAX_LBP_DURATION as u64
+            ),
+            Error::<Test>::InvalidLbpSchedule
+        );
+        assert_ok!(DexPallet::create_lbp(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            DOT_UNIT,
+            BTC_UNIT,
+            BONE / 2,
+            BONE / 2,
+            10,
+            1 + MAX_LBP_DURATION as u64
+        ));
+    });
+}
@@ -15,13 +15,15 @@ use frame_support::{
     pallet_prelude::*,
     sp_runtime::SaturatedConversion,
     traits::{
-        Currency, ExistenceRequirement, ExistenceRequirement::KeepAlive, Get, WithdrawReasons,
+        Currency, ExistenceRequirement, ExistenceRequirement::KeepAlive, Get, ReservableCurrency,
+        Time, WithdrawReasons,
     },
     PalletId, RuntimeDebug,
 };
-use sp_core::U256;
+use sp_core::{U256, U512};
 use sp_runtime::traits::{
-    AccountIdConversion, Hash, IntegerSquareRoot, One, StaticLookup, UniqueSaturatedInto, Zero,
+    AccountIdConversion, Hash, IdentifyAccount, IntegerSquareRoot, One, Saturating, StaticLookup,
+    UniqueSaturatedInto, Verify, Zero,
 };
 use sp_std::{convert::TryInto, marker::PhantomData, prelude::*};
 
@@ -39,21 +41,56 @@ use xcm_executor::{
 };
 // -------xcm--------
 
+mod bootstrap;
+mod concentrated;
+mod dca;
+mod farm;
 mod foreign;
+mod lbp;
 mod liquidity;
+mod migration;
+#[cfg(test)]
+mod mock;
 mod multiassets;
+mod pause;
+mod price_order;
 mod primitives;
+mod route;
+mod router;
 mod rpc;
+mod signed_order;
+mod stable;
 mod swap;
 mod traits;
 mod transactor;
 mod transfer;
+mod weighted;
 
+pub use concentrated::{MAX_SQRT_RATIO, MAX_TICK, MIN_SQRT_RATIO, MIN_TICK};
+pub use farm::FARM_REWARD_PRECISION;
+pub use migration::PAIR_METADATA_VERSION;
 pub use multiassets::{MultiAssetsHandler, ZenlinkMultiAssets};
-pub use primitives::{AssetBalance, AssetId, LIQUIDITY, LOCAL, NATIVE, RESERVED};
-pub use rpc::PairInfo;
-pub use traits::{LocalAssetHandler, OtherAssetHandler};
+pub use primitives::{
+    AssetBalance, AssetId, BootstrapParameter, ConcentratedPool, ConcentratedPosition,
+    ConcentratedTick, DcaOrder, Deadline, Farm, FarmStake, LbpPool, PairMetadata, PairStatus,
+    PriceOrder, PriceOrderKind, RouterAction, RouterAmount, StablePool, SwapOrder, WeightedPool,
+    LIQUIDITY, LOCAL, NATIVE, RESERVED,
+};
+pub use route::SPLIT_ROUTE_PARTS;
+pub use router::MAX_ROUTER_ACTIONS;
+pub use rpc::{calculate_twap_amount, ConcentratedPositionInfo, PairInfo};
+pub use stable::{
+    A_PRECISION, MAX_A, MAX_A_CHANGE, MAX_STABLE_ASSETS, MIN_RAMP_BLOCKS, STABLE_LP_INDEX_OFFSET,
+};
+pub use swap::{Hop, MINIMUM_LIQUIDITY};
+pub use traits::{
+    FlashSwapHandler, LocalAssetHandler, OrderSignatureVerifier, OtherAssetHandler, VerifySignature,
+};
 pub use transactor::{TransactorAdaptor, TrustedParas};
+pub use weighted::{
+    BONE, INIT_POOL_SUPPLY, MAX_IN_RATIO, MAX_OUT_RATIO, MAX_WEIGHTED_ASSETS, MIN_WEIGHT,
+    WEIGHTED_LP_INDEX_OFFSET,
+};
 
 const LOG_TARGET: &str = "zenlink_protocol";
 pub fn make_x2_location(para_id: u32) -> MultiLocation {
@@ -66,6 +103,19 @@ pub mod pallet {
     use frame_support::dispatch::DispatchResult;
     use frame_system::pallet_prelude::*;
 
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+    pub type MomentOf<T> = <<T as Config>::Time as Time>::Moment;
+    pub type SwapOrderOf<T> = SwapOrder<
+        <T as frame_system::Config>::AccountId,
+        <T as frame_system::Config>::BlockNumber,
+        MomentOf<T>,
+    >;
+    pub type OrderSignatureOf<T> =
+        <<T as Config>::OrderSignatureVerifier as OrderSignatureVerifier<
+            <T as frame_system::Config>::AccountId,
+        >>::Signature;
+
     /// Configure the pallet by specifying the parameters and types on which it depends.
     #[pallet::config]
     pub trait Config: frame_system::Config {
@@ -80,6 +130,37 @@ pub mod pallet {
         type MultiAssetsHandler: MultiAssetsHandler<Self::AccountId>;
         /// This pallet id.
         type PalletId: Get<PalletId>;
+        /// The origin which may change the parameters of the trading pairs.
+        type ControlOrigin: EnsureOrigin<Self::Origin>;
+        /// The origin which may create the trading pairs and the liquidity bootstrapping pools,
+        /// it's successful with the creator.
+        type CreatePairOrigin: EnsureOrigin<Self::Origin, Success = Self::AccountId>;
+        /// The currency which the deposits of the trading pairs are reserved in.
+        type Currency: ReservableCurrency<Self::AccountId>;
+        /// The deposit reserved from the creator of a trading pair until the pair is delisted,
+        /// zero for no deposit.
+        type PairCreationDeposit: Get<BalanceOf<Self>>;
+        /// The origin which may pause the pallet, the trading pairs and the calls in an
+        /// emergency.
+        type EmergencyOrigin: EnsureOrigin<Self::Origin>;
+        /// The time which the timestamp deadlines are compared with, `pallet_timestamp` in
+        /// general.
+        type Time: Time;
+        /// The verifier of the signatures of the swap orders signed off-chain.
+        type OrderSignatureVerifier: OrderSignatureVerifier<Self::AccountId>;
+        /// The borrower which pays back the flash swaps.
+        type FlashSwapHandler: FlashSwapHandler<Self::AccountId>;
+        /// The maximum number of hops of the swap paths searched by the best-route swaps.
+        type MaxSwapHops: Get<u32>;
+        /// The maximum number of the DCA orders scheduled at a block, the executions due at a
+        /// full block are postponed to the next blocks.
+        type MaxDcaExecutionsPerBlock: Get<u32>;
+        /// The maximum number of the price orders checked in a block for the trading pairs
+        /// swapped, the rest are checked in the next blocks.
+        type MaxPriceOrderChecksPerBlock: Get<u32>;
+        /// The deposit reserved from the owner of a price order until the order is filled,
+        /// cancelled or removed after the expiry, zero for no deposit.
+        type PriceOrderDeposit: Get<BalanceOf<Self>>;
 
         /// XCM
 
@@ -119,9 +200,9 @@ pub mod pallet {
     #[pallet::storage]
     #[pallet::getter(fn lp_metadata)]
     /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
-    /// (AssetId, AssetId) -> (PairAccountId, TotalSupply)
+    /// (AssetId, AssetId) -> PairMetadata
     pub type LiquidityMeta<T: Config> =
-        StorageMap<_, Twox64Concat, (AssetId, AssetId), (T::AccountId, AssetBalance)>;
+        StorageMap<_, Twox64Concat, (AssetId, AssetId), PairMetadata<T::AccountId>>;
 
     #[pallet::storage]
     #[pallet::getter(fn lp_ledger)]
@@ -134,10 +215,274 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    #[pallet::storage]
+    #[pallet::getter(fn lp_price_cumulative)]
+    /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+    /// (AssetId, AssetId) -> (Price0CumulativeLast, Price1CumulativeLast, BlockNumberLast)
+    pub type LiquidityPriceCumulative<T: Config> =
+        StorageMap<_, Twox64Concat, (AssetId, AssetId), (U256, U256, T::BlockNumber), ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn lp_exchange_fee)]
+    /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+    /// (AssetId, AssetId) -> (FeeNumerator, FeeDenominator)
+    pub type LiquidityExchangeFee<T: Config> =
+        StorageMap<_, Twox64Concat, (AssetId, AssetId), (u32, u32)>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn global_max_price_impact)]
+    /// (ImpactNumerator, ImpactDenominator)
+    /// The maximum price impact of a hop through any trading pair, no limit when it's not set.
+    pub type GlobalMaxPriceImpact<T: Config> = StorageValue<_, (u32, u32)>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn pair_max_price_impact)]
+    /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+    /// (AssetId, AssetId) -> (ImpactNumerator, ImpactDenominator)
+    pub type PairMaxPriceImpact<T: Config> =
+        StorageMap<_, Twox64Concat, (AssetId, AssetId), (u32, u32)>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn k_last)]
+    /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+    /// (AssetId, AssetId) -> reserve_0 * reserve_1, as of immediately after the most recent
+    /// liquidity event
+    pub type KLast<T: Config> = StorageMap<_, Twox64Concat, (AssetId, AssetId), U256, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn fee_meta)]
+    /// (Option<FeeReceiver>, (FeeNumerator, FeeDenominator))
+    /// The fraction of the fee growth which is minted as liquidity to the fee receiver.
+    pub type FeeMeta<T: Config> = StorageValue<_, (Option<T::AccountId>, (u32, u32)), ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn flash_swap_locked)]
+    /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+    /// (AssetId, AssetId) -> Whether a flash swap of the pair is in progress
+    pub type FlashSwapLocked<T: Config> =
+        StorageMap<_, Twox64Concat, (AssetId, AssetId), bool, ValueQuery>;
+
     #[pallet::storage]
     #[pallet::getter(fn lp_pairs)]
     pub type LiquidityPairs<T: Config> = StorageValue<_, Vec<(AssetId, AssetId)>, ValueQuery>;
 
+    #[pallet::storage]
+    #[pallet::getter(fn pair_deposit)]
+    /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+    /// (AssetId, AssetId) -> (Creator, Deposit)
+    pub type PairDeposits<T: Config> =
+        StorageMap<_, Twox64Concat, (AssetId, AssetId), (T::AccountId, BalanceOf<T>)>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn asset_allowlist)]
+    /// The assets which the trading pairs can be created of, any asset when it's not set.
+    pub type AssetAllowlist<T: Config> = StorageValue<_, Vec<AssetId>>;
+
+    /// Circuit breaker storage
+    #[pallet::storage]
+    #[pallet::getter(fn pallet_paused)]
+    /// Whether all the calls of this pallet are paused
+    pub type PalletPaused<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn pair_paused)]
+    /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+    /// (AssetId, AssetId) -> Whether the swaps and liquidity changes of the pair are paused
+    pub type PausedPairs<T: Config> =
+        StorageMap<_, Twox64Concat, (AssetId, AssetId), bool, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn call_paused)]
+    /// CallName -> Whether the call is paused
+    pub type PausedCalls<T: Config> = StorageMap<_, Blake2_128Concat, Vec<u8>, bool, ValueQuery>;
+
+    /// Signed order storage
+    #[pallet::storage]
+    #[pallet::getter(fn order_nonce)]
+    /// AccountId -> The nonce of the next signed swap order of the account
+    pub type OrderNonces<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u64, ValueQuery>;
+
+    /// DCA order storage
+    #[pallet::storage]
+    #[pallet::getter(fn dca_order)]
+    /// TWOX-NOTE: `OrderId` is trusted, so this is safe.
+    /// OrderId -> DcaOrder
+    pub type DcaOrders<T: Config> =
+        StorageMap<_, Twox64Concat, u64, DcaOrder<T::AccountId, T::BlockNumber>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn next_dca_order_id)]
+    pub type NextDcaOrderId<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn dca_schedule)]
+    /// TWOX-NOTE: `BlockNumber` is trusted, so this is safe.
+    /// BlockNumber -> The DCA orders due at the block
+    pub type DcaSchedule<T: Config> =
+        StorageMap<_, Twox64Concat, T::BlockNumber, Vec<u64>, ValueQuery>;
+
+    /// Price order storage
+    #[pallet::storage]
+    #[pallet::getter(fn price_order)]
+    /// TWOX-NOTE: `OrderId` is trusted, so this is safe.
+    /// OrderId -> PriceOrder
+    pub type PriceOrders<T: Config> =
+        StorageMap<_, Twox64Concat, u64, PriceOrder<T::AccountId, T::BlockNumber>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn next_price_order_id)]
+    pub type NextPriceOrderId<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn price_orders_of_pair)]
+    /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+    /// (AssetId, AssetId) -> The open price orders through the pair
+    pub type PriceOrdersOfPair<T: Config> =
+        StorageMap<_, Twox64Concat, (AssetId, AssetId), Vec<u64>, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn price_order_deposit)]
+    /// TWOX-NOTE: `OrderId` is trusted, so this is safe.
+    /// OrderId -> The deposit reserved from the owner of the price order
+    pub type PriceOrderDeposits<T: Config> = StorageMap<_, Twox64Concat, u64, BalanceOf<T>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn price_order_count)]
+    /// AccountId -> The number of the open price orders of the owner
+    pub type PriceOrderCount<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn swapped_pairs)]
+    /// The trading pairs with open price orders, which are swapped since they were last checked
+    pub type SwappedPairs<T: Config> = StorageValue<_, Vec<(AssetId, AssetId)>, ValueQuery>;
+
+    /// Farm storage
+    #[pallet::storage]
+    #[pallet::getter(fn farm)]
+    /// TWOX-NOTE: `FarmId` is trusted, so this is safe.
+    /// FarmId -> Farm
+    pub type Farms<T: Config> = StorageMap<_, Twox64Concat, u32, Farm<T::BlockNumber>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn next_farm_id)]
+    pub type NextFarmId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn farm_stake)]
+    /// (FarmId, AccountId) -> FarmStake
+    pub type FarmStakes<T: Config> =
+        StorageMap<_, Blake2_128Concat, (u32, T::AccountId), FarmStake, ValueQuery>;
+
+    /// Stable pool storage
+    #[pallet::storage]
+    #[pallet::getter(fn stable_pool)]
+    /// TWOX-NOTE: `PoolId` is trusted, so this is safe.
+    /// PoolId -> StablePool
+    pub type StablePools<T: Config> =
+        StorageMap<_, Twox64Concat, u32, StablePool<T::AccountId, T::BlockNumber>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn next_stable_pool_id)]
+    pub type NextStablePoolId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn stable_ledger)]
+    /// (PoolId, AccountId) -> AssetBalance
+    pub type StableLedger<T: Config> =
+        StorageMap<_, Blake2_128Concat, (u32, T::AccountId), AssetBalance, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn stable_pool_of_assets)]
+    /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+    /// (AssetId, AssetId) -> PoolId, the first stable pool holding both assets
+    pub type StablePoolOfAssets<T: Config> = StorageMap<_, Twox64Concat, (AssetId, AssetId), u32>;
+
+    /// Weighted pool storage
+    #[pallet::storage]
+    #[pallet::getter(fn weighted_pool)]
+    /// TWOX-NOTE: `PoolId` is trusted, so this is safe.
+    /// PoolId -> WeightedPool
+    pub type WeightedPools<T: Config> =
+        StorageMap<_, Twox64Concat, u32, WeightedPool<T::AccountId>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn next_weighted_pool_id)]
+    pub type NextWeightedPoolId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn weighted_ledger)]
+    /// (PoolId, AccountId) -> AssetBalance
+    pub type WeightedLedger<T: Config> =
+        StorageMap<_, Blake2_128Concat, (u32, T::AccountId), AssetBalance, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn weighted_pool_of_assets)]
+    /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+    /// (AssetId, AssetId) -> PoolId, the first weighted pool holding both assets
+    pub type WeightedPoolOfAssets<T: Config> = StorageMap<_, Twox64Concat, (AssetId, AssetId), u32>;
+
+    /// Concentrated liquidity pool storage
+    #[pallet::storage]
+    #[pallet::getter(fn concentrated_pool)]
+    /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+    /// (AssetId, AssetId) -> ConcentratedPool
+    pub type ConcentratedPools<T: Config> =
+        StorageMap<_, Twox64Concat, (AssetId, AssetId), ConcentratedPool<T::AccountId>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn concentrated_tick)]
+    /// ((AssetId, AssetId), Tick) -> ConcentratedTick
+    pub type ConcentratedTicks<T: Config> =
+        StorageMap<_, Blake2_128Concat, ((AssetId, AssetId), i32), ConcentratedTick>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn concentrated_initialized_ticks)]
+    /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+    /// (AssetId, AssetId) -> Vec<Tick>, the sorted ticks referenced by the positions
+    pub type ConcentratedInitializedTicks<T: Config> =
+        StorageMap<_, Twox64Concat, (AssetId, AssetId), Vec<i32>, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn concentrated_position)]
+    /// AccountId, ((AssetId, AssetId), TickLower, TickUpper) -> ConcentratedPosition
+    pub type ConcentratedPositions<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        ((AssetId, AssetId), i32, i32),
+        ConcentratedPosition,
+    >;
+
+    /// Bootstrap storage
+    #[pallet::storage]
+    #[pallet::getter(fn bootstrap_parameter)]
+    /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+    /// (AssetId, AssetId) -> BootstrapParameter
+    pub type BootstrapParameters<T: Config> =
+        StorageMap<_, Twox64Concat, (AssetId, AssetId), BootstrapParameter<T::BlockNumber>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn bootstrap_contribution)]
+    /// ((AssetId, AssetId), AccountId) -> (AssetBalance, AssetBalance)
+    pub type BootstrapContributions<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        ((AssetId, AssetId), T::AccountId),
+        (AssetBalance, AssetBalance),
+        ValueQuery,
+    >;
+
+    /// Liquidity bootstrapping pool storage
+    #[pallet::storage]
+    #[pallet::getter(fn lbp_pool)]
+    /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+    /// (AssetId, AssetId) -> LbpPool
+    pub type LbpPools<T: Config> =
+        StorageMap<_, Twox64Concat, (AssetId, AssetId), LbpPool<T::AccountId, T::BlockNumber>>;
+
     #[pallet::event]
     #[pallet::metadata(T::AccountId = "AccountId")]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -169,6 +514,181 @@ pub mod pallet {
         ),
         /// Transact in trading \[owner, recipient, swap_path, balance_in, balance_out\]
         AssetSwap(T::AccountId, T::AccountId, Vec<AssetId>, AssetBalance, AssetBalance),
+        /// Transact in trading through split routes, besides the `AssetSwap` of every route.
+        /// \[owner, recipient, asset_in, asset_out, balance_in, balance_out\]
+        AssetSplitSwap(T::AccountId, T::AccountId, AssetId, AssetId, AssetBalance, AssetBalance),
+        /// Router batch executed, with the outputs left by it. \[owner, outputs\]
+        RouterBatchExecuted(T::AccountId, Vec<(AssetId, AssetBalance)>),
+        /// Swap order signed off-chain executed by a relayer. \[owner, relayer, nonce, relayer_fee\]
+        SwapOrderExecuted(T::AccountId, T::AccountId, u64, AssetBalance),
+        /// Swap orders signed off-chain below the next nonce cancelled by the owner. \[owner, next_nonce\]
+        SwapOrdersCancelled(T::AccountId, u64),
+        /// DCA order created. \[owner, order_id, amount_per_execution, executions\]
+        DcaOrderCreated(T::AccountId, u64, AssetBalance, u32),
+        /// Execution of a DCA order swapped. \[owner, order_id, amount_in, amount_out\]
+        DcaOrderExecuted(T::AccountId, u64, AssetBalance, AssetBalance),
+        /// Execution of a DCA order failed and was skipped. \[owner, order_id, error\]
+        DcaOrderExecutionFailed(T::AccountId, u64, DispatchError),
+        /// DCA order finished its executions. \[owner, order_id, refund\]
+        DcaOrderCompleted(T::AccountId, u64, AssetBalance),
+        /// DCA order cancelled by its owner. \[owner, order_id, refund\]
+        DcaOrderCancelled(T::AccountId, u64, AssetBalance),
+        /// Price order placed. \[owner, order_id, asset_in, asset_out, amount_in\]
+        PriceOrderPlaced(T::AccountId, u64, AssetId, AssetId, AssetBalance),
+        /// Price order filled. \[owner, order_id, amount_in, amount_out\]
+        PriceOrderFilled(T::AccountId, u64, AssetBalance, AssetBalance),
+        /// Keeper rewarded for filling a price order. \[keeper, order_id, keeper_reward\]
+        PriceOrderKeeperRewarded(T::AccountId, u64, AssetBalance),
+        /// Price order cancelled by its owner. \[owner, order_id\]
+        PriceOrderCancelled(T::AccountId, u64),
+        /// Price order removed after its expiry. \[owner, order_id\]
+        PriceOrderExpired(T::AccountId, u64),
+        /// Farm created. \[farm_id, asset_0, asset_1, reward_asset, reward_per_block, start_block, end_block\]
+        FarmCreated(u32, AssetId, AssetId, AssetId, AssetBalance, T::BlockNumber, T::BlockNumber),
+        /// Liquidity staked in a farm. \[owner, farm_id, amount\]
+        FarmStaked(T::AccountId, u32, AssetBalance),
+        /// Liquidity unstaked from a farm. \[owner, farm_id, amount\]
+        FarmUnstaked(T::AccountId, u32, AssetBalance),
+        /// Rewards of a farm claimed. \[owner, farm_id, reward_asset, reward\]
+        FarmRewardsClaimed(T::AccountId, u32, AssetId, AssetBalance),
+        /// Liquidity unstaked from a farm without the rewards. \[owner, farm_id, amount\]
+        FarmEmergencyUnstaked(T::AccountId, u32, AssetBalance),
+        /// Exchange fee of a trading pair changed. \[asset_0, asset_1, fee_numerator, fee_denominator\]
+        ExchangeFeeChanged(AssetId, AssetId, u32, u32),
+        /// Maximum price impact of the hops through any trading pair changed. \[max_price_impact\]
+        GlobalMaxPriceImpactChanged(Option<(u32, u32)>),
+        /// Maximum price impact of the hops through a trading pair changed. \[asset_0, asset_1, max_price_impact\]
+        PairMaxPriceImpactChanged(AssetId, AssetId, Option<(u32, u32)>),
+        /// Receiver of the protocol fee changed. \[fee_receiver\]
+        FeeReceiverChanged(Option<T::AccountId>),
+        /// Fraction of the fee growth minted as protocol fee changed. \[fee_numerator, fee_denominator\]
+        ProtocolFeeRateChanged(u32, u32),
+        /// Reserves of a trading pair updated. \[asset_0, asset_1, reserve_0, reserve_1\]
+        Sync(AssetId, AssetId, AssetBalance, AssetBalance),
+        /// Status of a trading pair changed. \[asset_0, asset_1, status\]
+        PairStatusChanged(AssetId, AssetId, PairStatus),
+        /// Assets which the trading pairs can be created of changed. \[allowlist\]
+        AssetAllowlistChanged(Option<Vec<AssetId>>),
+        /// All the calls of this pallet are paused or unpaused. \[paused\]
+        PalletPauseChanged(bool),
+        /// Trading pair is paused or unpaused. \[asset_0, asset_1, paused\]
+        PairPauseChanged(AssetId, AssetId, bool),
+        /// Call is paused or unpaused. \[call_name, paused\]
+        CallPauseChanged(Vec<u8>, bool),
+        /// Flash swap in a trading pair. \[owner, recipient, asset_0, asset_1, amount_0_out, amount_1_out, amount_0_in, amount_1_in\]
+        FlashSwap(
+            T::AccountId,
+            T::AccountId,
+            AssetId,
+            AssetId,
+            AssetBalance,
+            AssetBalance,
+            AssetBalance,
+            AssetBalance,
+        ),
+
+        /// Stable Swap
+
+        /// Create a stable pool. \[pool_id, assets\]
+        StablePoolCreated(u32, Vec<AssetId>),
+        /// Add liquidity to a stable pool. \[owner, pool_id, add_balances, mint_balance_lp\]
+        StableLiquidityAdded(T::AccountId, u32, Vec<AssetBalance>, AssetBalance),
+        /// Remove liquidity from a stable pool. \[owner, recipient, pool_id, rm_balances, burn_balance_lp\]
+        StableLiquidityRemoved(T::AccountId, T::AccountId, u32, Vec<AssetBalance>, AssetBalance),
+        /// Ramp the amplification coefficient of a stable pool. \[pool_id, initial_a, future_a, initial_block, future_block\]
+        RampA(u32, u128, u128, T::BlockNumber, T::BlockNumber),
+        /// Stop ramping the amplification coefficient of a stable pool. \[pool_id, current_a\]
+        StopRampA(u32, u128),
+
+        /// Weighted Pool
+
+        /// Create a weighted pool. \[pool_id, assets, normalized_weights\]
+        WeightedPoolCreated(u32, Vec<AssetId>, Vec<u128>),
+        /// Add liquidity to a weighted pool. \[owner, pool_id, add_balances, mint_balance_lp\]
+        WeightedLiquidityAdded(T::AccountId, u32, Vec<AssetBalance>, AssetBalance),
+        /// Remove liquidity from a weighted pool. \[owner, recipient, pool_id, rm_balances, burn_balance_lp\]
+        WeightedLiquidityRemoved(T::AccountId, T::AccountId, u32, Vec<AssetBalance>, AssetBalance),
+
+        /// Concentrated Liquidity
+
+        /// Create a concentrated liquidity pool. \[asset_0, asset_1, tick_spacing, tick\]
+        ConcentratedPoolCreated(AssetId, AssetId, u32, i32),
+        /// Add liquidity to a position. \[owner, asset_0, asset_1, tick_lower, tick_upper, liquidity, add_balance_0, add_balance_1\]
+        ConcentratedLiquidityAdded(
+            T::AccountId,
+            AssetId,
+            AssetId,
+            i32,
+            i32,
+            u128,
+            AssetBalance,
+            AssetBalance,
+        ),
+        /// Remove liquidity from a position. \[owner, recipient, asset_0, asset_1, tick_lower, tick_upper, liquidity, rm_balance_0, rm_balance_1\]
+        ConcentratedLiquidityRemoved(
+            T::AccountId,
+            T::AccountId,
+            AssetId,
+            AssetId,
+            i32,
+            i32,
+            u128,
+            AssetBalance,
+            AssetBalance,
+        ),
+        /// Collect the fees earned by a position. \[owner, recipient, asset_0, asset_1, tick_lower, tick_upper, fee_balance_0, fee_balance_1\]
+        ConcentratedFeesCollected(
+            T::AccountId,
+            T::AccountId,
+            AssetId,
+            AssetId,
+            i32,
+            i32,
+            AssetBalance,
+            AssetBalance,
+        ),
+
+        /// Bootstrap
+
+        /// Put a trading pair into bootstrap. \[asset_0, asset_1, target_supply_0, target_supply_1, end_block_number\]
+        BootstrapCreated(AssetId, AssetId, AssetBalance, AssetBalance, T::BlockNumber),
+        /// Contribute to the bootstrap of a trading pair. \[contributor, asset_0, asset_1, amount_0, amount_1\]
+        BootstrapContributed(T::AccountId, AssetId, AssetId, AssetBalance, AssetBalance),
+        /// The bootstrap of a trading pair ends and the pair opens. \[asset_0, asset_1, accumulated_supply_0, accumulated_supply_1, total_liquidity\]
+        BootstrapEnded(AssetId, AssetId, AssetBalance, AssetBalance, AssetBalance),
+        /// Claim the liquidity of the contributions to a bootstrap. \[contributor, recipient, asset_0, asset_1, amount_0, amount_1, liquidity\]
+        BootstrapClaimed(
+            T::AccountId,
+            T::AccountId,
+            AssetId,
+            AssetId,
+            AssetBalance,
+            AssetBalance,
+            AssetBalance,
+        ),
+        /// Refund the contributions to a bootstrap. \[contributor, asset_0, asset_1, amount_0, amount_1\]
+        BootstrapRefunded(T::AccountId, AssetId, AssetId, AssetBalance, AssetBalance),
+
+        /// Liquidity Bootstrapping Pool
+
+        /// Create a liquidity bootstrapping pool. \[owner, asset_0, asset_1, amount_0, amount_1\]
+        LbpCreated(T::AccountId, AssetId, AssetId, AssetBalance, AssetBalance),
+        /// Schedule of a liquidity bootstrapping pool updated. \[asset_0, asset_1, start_weight_0, end_weight_0, start_block, end_block\]
+        LbpScheduleUpdated(AssetId, AssetId, u128, u128, T::BlockNumber, T::BlockNumber),
+        /// Swap in a liquidity bootstrapping pool, with the weights and the spot price of asset_in after it. \[owner, recipient, asset_in, asset_out, balance_in, balance_out, weight_in, weight_out, spot_price\]
+        LbpSwapped(
+            T::AccountId,
+            T::AccountId,
+            AssetId,
+            AssetId,
+            AssetBalance,
+            AssetBalance,
+            u128,
+            u128,
+            AssetBalance,
+        ),
+        /// Withdraw the assets of a liquidity bootstrapping pool. \[owner, recipient, asset_0, asset_1, amount_0, amount_1\]
+        LbpWithdrawn(T::AccountId, T::AccountId, AssetId, AssetId, AssetBalance, AssetBalance),
 
         /// Transfer by xcm
 
@@ -205,7 +725,7 @@ pub mod pallet {
         IncorrectAssetAmountRange,
         /// Overflow.
         Overflow,
-        /// Transaction block number is larger than the end block number.
+        /// Transaction block number or timestamp is not before the deadline.
         Deadline,
         /// Location given was invalid or unsupported.
         AccountIdBadLocation,
@@ -215,10 +735,123 @@ pub mod pallet {
         DeniedTransferToSelf,
         /// Not in ZenlinkRegistedParaChains
         TargetChainNotRegistered,
+        /// Exchange fee rate must be less than one.
+        InvalidExchangeFee,
+        /// Maximum price impact must be more than zero and less than one.
+        InvalidPriceImpact,
+        /// Hop of the swap moves the spot price of the pair more than the maximum price impact.
+        ExcessivePriceImpact,
+        /// Router batch is empty or has more than `MAX_ROUTER_ACTIONS` actions.
+        InvalidRouterActions,
+        /// No output of the asset is left by the earlier actions of the router batch.
+        NoRouterOutput,
+        /// Signature of the swap order is not of the owner.
+        InvalidOrderSignature,
+        /// Nonce of the swap order is not the next one of the owner, or the nonce to cancel the
+        /// swap orders below is not more than it.
+        InvalidOrderNonce,
+        /// Relayer fee is more than the maximum of the swap order.
+        ExcessiveRelayerFee,
+        /// Amount, number of executions or period of the DCA order is zero.
+        InvalidDcaOrder,
+        /// DCA order does not exist.
+        DcaOrderNotExists,
+        /// Only the owner of the DCA order can do this.
+        NotDcaOrderOwner,
+        /// The blocks which the DCA order can be scheduled at are full.
+        DcaScheduleFull,
+        /// Amount or trigger price of the price order is zero, or its expiry is in the past or
+        /// over `MAX_PRICE_ORDER_LIFETIME` blocks later.
+        InvalidPriceOrder,
+        /// Price order does not exist.
+        PriceOrderNotExists,
+        /// Only the owner of the price order can cancel it before the expiry.
+        NotPriceOrderOwner,
+        /// Spot price of the pair doesn't cross the trigger price of the price order.
+        PriceOrderNotTriggered,
+        /// Price order is expired.
+        PriceOrderExpired,
+        /// Trading pair or owner has too many open price orders.
+        TooManyPriceOrders,
+        /// Reward or blocks of the farm are invalid.
+        InvalidFarm,
+        /// Farm does not exist.
+        FarmNotExists,
+        /// Farm doesn't accept stakes after the end block.
+        FarmEnded,
+        /// Liquidity staked in the farm is not enough.
+        InsufficientFarmStake,
+        /// Protocol fee rate must be less than one.
+        InvalidProtocolFee,
+        /// Nothing is paid back to the pair by the flash swap.
+        InsufficientInputAmount,
+        /// Constant product of the pair decreases after the flash swap.
+        InvalidInvariant,
+        /// Trading pair is locked by a flash swap in progress.
+        PairLocked,
+        /// Pool does not exist.
+        PoolNotExists,
+        /// Assets of the pool, or amounts of them, are invalid.
+        InvalidPoolAssets,
+        /// Amplification coefficient, or the ramping of it, is invalid.
+        InvalidAmplification,
+        /// Weights of the pool are invalid.
+        InvalidWeights,
+        /// Amount is too large a fraction of the reserve of the pool.
+        ExcessiveRatio,
+        /// Pool already exists.
+        PoolAlreadyExists,
+        /// Tick is out of range, or not a multiple of the tick spacing.
+        InvalidTick,
+        /// Position does not exist.
+        PositionNotExists,
+        /// Trading pair is not enabled for swaps and liquidity adds.
+        PairNotEnabled,
+        /// Trading pair can't be changed to the status.
+        InvalidPairStatus,
+        /// Asset is not in the allowlist of the pair creation.
+        AssetNotAllowed,
+        /// The pallet, the trading pair or the call is paused.
+        Paused,
+        /// Targets or end block of the bootstrap are invalid.
+        InvalidBootstrapParameter,
+        /// Trading pair is already in bootstrap, or has been.
+        BootstrapAlreadyExists,
+        /// Trading pair is not in bootstrap.
+        BootstrapNotExists,
+        /// Bootstrap doesn't accept contributions any more.
+        BootstrapClosed,
+        /// Bootstrap has not ended yet.
+        BootstrapNotEnded,
+        /// Bootstrap doesn't reach the targets.
+        BootstrapTargetNotReached,
+        /// Bootstrap can't be refunded unless it ends without reaching the targets.
+        BootstrapNotRefundable,
+        /// Nothing is contributed to the bootstrap.
+        NoBootstrapContribution,
+        /// Start and end blocks of the liquidity bootstrapping pool are invalid, or the end block
+        /// is over `MAX_LBP_DURATION` blocks later.
+        InvalidLbpSchedule,
+        /// Liquidity bootstrapping pool is not between the start and end blocks.
+        LbpNotActive,
+        /// Liquidity bootstrapping pool can't be changed after it starts.
+        LbpAlreadyStarted,
+        /// Liquidity bootstrapping pool can't be withdrawn before it ends.
+        LbpNotEnded,
+        /// Only the owner of the liquidity bootstrapping pool can do this.
+        NotLbpOwner,
     }
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_runtime_upgrade() -> Weight {
+            migration::migrate_to_pair_metadata::<T>()
+        }
+
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            Self::execute_due_dca_orders(now).saturating_add(Self::fill_triggered_price_orders(now))
+        }
+    }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
@@ -236,6 +869,7 @@ pub mod pallet {
             recipient: <T::Lookup as StaticLookup>::Source,
             #[pallet::compact] amount: AssetBalance,
         ) -> DispatchResult {
+            Self::ensure_not_paused(b"transfer")?;
             let origin = ensure_signed(origin)?;
             let target = T::Lookup::lookup(recipient)?;
             let balance = T::MultiAssetsHandler::balance_of(asset_id, &origin);
@@ -266,6 +900,7 @@ pub mod pallet {
             #[pallet::compact] amount: AssetBalance,
             max_weight: Weight,
         ) -> DispatchResult {
+            Self::ensure_not_paused(b"transfer_to_parachain")?;
             let who = ensure_signed(origin.clone())?;
             let balance = T::MultiAssetsHandler::balance_of(asset_id, &who);
             let checked = Self::check_existential_deposit(asset_id, amount);
@@ -323,41 +958,47 @@ pub mod pallet {
 
         /// Create pair by two assets.
         ///
-        /// The order of foreign dot effect result.
+        /// The order of foreign dot effect result. Both assets must be in the allowlist if
+        /// it's set, and the creation deposit is reserved from the creator until the pair is
+        /// delisted.
         ///
         /// # Arguments
         ///
         /// - `asset_0`: Asset which make up Pair
         /// - `asset_1`: Asset which make up Pair
         #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
         pub fn create_pair(
             origin: OriginFor<T>,
             asset_0: AssetId,
             asset_1: AssetId,
         ) -> DispatchResult {
-            let who = ensure_signed(origin)?;
+            Self::ensure_not_paused(b"create_pair")?;
+            let who = T::CreatePairOrigin::ensure_origin(origin)?;
             ensure!(asset_0.is_support() && asset_1.is_support(), Error::<T>::UnsupportedAssetType);
             ensure!(asset_0 != asset_1, Error::<T>::DeniedCreatePair);
             ensure!(T::MultiAssetsHandler::is_exists(asset_0), Error::<T>::AssetNotExists);
             ensure!(T::MultiAssetsHandler::is_exists(asset_1), Error::<T>::AssetNotExists);
+            if let Some(allowlist) = Self::asset_allowlist() {
+                ensure!(
+                    allowlist.contains(&asset_0) && allowlist.contains(&asset_1),
+                    Error::<T>::AssetNotAllowed
+                );
+            }
 
             let (asset_0, asset_1) = Self::sort_asset_id(asset_0, asset_1);
 
-            let pair_account = Self::pair_account_id(asset_0, asset_1);
+            Self::inner_create_pair(asset_0, asset_1, PairStatus::Enabled)?;
 
-            LiquidityMeta::<T>::try_mutate((asset_0, asset_1), |meta| {
-                if meta.is_none() {
-                    *meta = Some((pair_account, Default::default()));
-
-                    Self::mutate_lp_pairs(asset_0, asset_1);
+            let deposit = T::PairCreationDeposit::get();
+            if !deposit.is_zero() {
+                T::Currency::reserve(&who, deposit)?;
+                PairDeposits::<T>::insert((asset_0, asset_1), (who.clone(), deposit));
+            }
 
-                    Self::deposit_event(Event::PairCreated(who, asset_0, asset_1));
+            Self::deposit_event(Event::PairCreated(who, asset_0, asset_1));
 
-                    Ok(())
-                } else {
-                    Err(Error::<T>::PairAlreadyExists.into())
-                }
-            })
+            Ok(())
         }
 
         /// Provide liquidity to a pair.
@@ -372,7 +1013,7 @@ pub mod pallet {
         /// - `amount_1_desired`: Maximum amount of asset_1 added to the pair
         /// - `amount_0_min`: Minimum amount of asset_0 added to the pair
         /// - `amount_1_min`: Minimum amount of asset_1 added to the pair
-        /// - `deadline`: Height of the cutoff block of this transaction
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
         #[pallet::weight(1_000_000)]
         #[frame_support::transactional]
         #[allow(clippy::too_many_arguments)]
@@ -384,12 +1025,12 @@ pub mod pallet {
             #[pallet::compact] amount_1_desired: AssetBalance,
             #[pallet::compact] amount_0_min: AssetBalance,
             #[pallet::compact] amount_1_min: AssetBalance,
-            #[pallet::compact] deadline: T::BlockNumber,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
         ) -> DispatchResult {
+            Self::ensure_not_paused(b"add_liquidity")?;
             ensure!(asset_0.is_support() && asset_1.is_support(), Error::<T>::UnsupportedAssetType);
             let who = ensure_signed(origin)?;
-            let now = frame_system::Pallet::<T>::block_number();
-            ensure!(deadline > now, Error::<T>::Deadline);
+            Self::ensure_deadline(deadline)?;
 
             Self::inner_add_liquidity(
                 &who,
@@ -402,6 +1043,34 @@ pub mod pallet {
             )
         }
 
+        /// Provide liquidity to a pair with one asset only, part of it is swapped for the
+        /// other asset through the pair first.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which is added
+        /// - `asset_1`: The other asset which make up pair
+        /// - `amount_0`: Amount of asset_0 added to the pair, the dust is left to the caller
+        /// - `liquidity_min`: Minimum amount of the liquidity minted
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn add_liquidity_single_asset(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            #[pallet::compact] amount_0: AssetBalance,
+            #[pallet::compact] liquidity_min: AssetBalance,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"add_liquidity_single_asset")?;
+            ensure!(asset_0.is_support() && asset_1.is_support(), Error::<T>::UnsupportedAssetType);
+            let who = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_add_liquidity_single_asset(&who, asset_0, asset_1, amount_0, liquidity_min)
+        }
+
         /// Extract liquidity.
         ///
         /// The order of foreign dot effect result.
@@ -413,7 +1082,7 @@ pub mod pallet {
         /// - `amount_asset_0_min`: Minimum amount of asset_0 to exact
         /// - `amount_asset_1_min`: Minimum amount of asset_1 to exact
         /// - `recipient`: Account that accepts withdrawal of assets
-        /// - `deadline`: Height of the cutoff block of this transaction
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
         #[pallet::weight(1_000_000)]
         #[frame_support::transactional]
         #[allow(clippy::too_many_arguments)]
@@ -425,13 +1094,13 @@ pub mod pallet {
             #[pallet::compact] amount_0_min: AssetBalance,
             #[pallet::compact] amount_1_min: AssetBalance,
             recipient: <T::Lookup as StaticLookup>::Source,
-            #[pallet::compact] deadline: T::BlockNumber,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
         ) -> DispatchResult {
+            Self::ensure_not_paused(b"remove_liquidity")?;
             ensure!(asset_0.is_support() && asset_1.is_support(), Error::<T>::UnsupportedAssetType);
             let who = ensure_signed(origin)?;
             let recipient = T::Lookup::lookup(recipient)?;
-            let now = frame_system::Pallet::<T>::block_number();
-            ensure!(deadline > now, Error::<T>::Deadline);
+            Self::ensure_deadline(deadline)?;
 
             Self::inner_remove_liquidity(
                 &who,
@@ -444,6 +1113,44 @@ pub mod pallet {
             )
         }
 
+        /// Extract liquidity into one asset only, the other asset extracted is swapped for
+        /// it through the pair.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which is received
+        /// - `asset_1`: The other asset which make up pair
+        /// - `liquidity`: Liquidity amount to remove
+        /// - `amount_0_min`: Minimum amount of asset_0 to receive
+        /// - `recipient`: Account that accepts withdrawal of assets
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn remove_liquidity_single_asset(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            #[pallet::compact] liquidity: AssetBalance,
+            #[pallet::compact] amount_0_min: AssetBalance,
+            recipient: <T::Lookup as StaticLookup>::Source,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"remove_liquidity_single_asset")?;
+            ensure!(asset_0.is_support() && asset_1.is_support(), Error::<T>::UnsupportedAssetType);
+            let who = ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_remove_liquidity_single_asset(
+                &who,
+                asset_0,
+                asset_1,
+                liquidity,
+                amount_0_min,
+                &recipient,
+            )
+        }
+
         /// Sell amount of foreign by path.
         ///
         /// # Arguments
@@ -452,7 +1159,7 @@ pub mod pallet {
         /// - `amount_out_min`: Minimum amount of target foreign
         /// - `path`: path can convert to pairs.
         /// - `recipient`: Account that receive the target foreign
-        /// - `deadline`: Height of the cutoff block of this transaction
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
         #[pallet::weight(1_000_000)]
         #[frame_support::transactional]
         pub fn swap_exact_assets_for_assets(
@@ -461,13 +1168,13 @@ pub mod pallet {
             #[pallet::compact] amount_out_min: AssetBalance,
             path: Vec<AssetId>,
             recipient: <T::Lookup as StaticLookup>::Source,
-            #[pallet::compact] deadline: T::BlockNumber,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
         ) -> DispatchResult {
+            Self::ensure_not_paused(b"swap_exact_assets_for_assets")?;
             ensure!(path.iter().all(|id| id.is_support()), Error::<T>::UnsupportedAssetType);
             let who = ensure_signed(origin)?;
             let recipient = T::Lookup::lookup(recipient)?;
-            let now = frame_system::Pallet::<T>::block_number();
-            ensure!(deadline > now, Error::<T>::Deadline);
+            Self::ensure_deadline(deadline)?;
 
             Self::inner_swap_exact_assets_for_assets(
                 &who,
@@ -486,7 +1193,7 @@ pub mod pallet {
         /// - `amount_in_max`: Maximum amount of sold foreign
         /// - `path`: path can convert to pairs.
         /// - `recipient`: Account that receive the target foreign
-        /// - `deadline`: Height of the cutoff block of this transaction
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
         #[pallet::weight(1_000_000)]
         #[frame_support::transactional]
         pub fn swap_assets_for_exact_assets(
@@ -495,13 +1202,13 @@ pub mod pallet {
             #[pallet::compact] amount_in_max: AssetBalance,
             path: Vec<AssetId>,
             recipient: <T::Lookup as StaticLookup>::Source,
-            #[pallet::compact] deadline: T::BlockNumber,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
         ) -> DispatchResult {
+            Self::ensure_not_paused(b"swap_assets_for_exact_assets")?;
             ensure!(path.iter().all(|id| id.is_support()), Error::<T>::UnsupportedAssetType);
             let who = ensure_signed(origin)?;
             let recipient = T::Lookup::lookup(recipient)?;
-            let now = frame_system::Pallet::<T>::block_number();
-            ensure!(deadline > now, Error::<T>::Deadline);
+            Self::ensure_deadline(deadline)?;
 
             Self::inner_swap_assets_for_exact_assets(
                 &who,
@@ -511,5 +1218,1607 @@ pub mod pallet {
                 &recipient,
             )
         }
+
+        /// Sell amount of foreign by path whose hops are named explicitly. A path without
+        /// the hops always goes through the trading pair of two assets which have one, the
+        /// pools of such assets are only reached through the hops.
+        ///
+        /// # Arguments
+        ///
+        /// - `amount_in`: Amount of the foreign will be sold
+        /// - `amount_out_min`: Minimum amount of target foreign
+        /// - `path`: path can convert to pairs.
+        /// - `hops`: The trading pair or pool of every two adjacent foreigns of the path.
+        /// - `recipient`: Account that receive the target foreign
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn swap_exact_assets_for_assets_through_hops(
+            origin: OriginFor<T>,
+            #[pallet::compact] amount_in: AssetBalance,
+            #[pallet::compact] amount_out_min: AssetBalance,
+            path: Vec<AssetId>,
+            hops: Vec<Hop>,
+            recipient: <T::Lookup as StaticLookup>::Source,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"swap_exact_assets_for_assets_through_hops")?;
+            ensure!(path.iter().all(|id| id.is_support()), Error::<T>::UnsupportedAssetType);
+            let who = ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_swap_exact_assets_for_assets_through_hops(
+                &who,
+                amount_in,
+                amount_out_min,
+                &path,
+                &hops,
+                &recipient,
+            )
+        }
+
+        /// Sell amount of foreign through the swap path which gives the most of the target
+        /// foreign, searched in the trading pairs.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_in`: The foreign sold
+        /// - `asset_out`: The foreign bought
+        /// - `amount_in`: Amount of the foreign will be sold
+        /// - `amount_out_min`: Minimum amount of target foreign
+        /// - `recipient`: Account that receive the target foreign
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(Pallet::<T>::best_route_swap_weight())]
+        #[frame_support::transactional]
+        pub fn swap_exact_assets_for_assets_through_best_route(
+            origin: OriginFor<T>,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            #[pallet::compact] amount_in: AssetBalance,
+            #[pallet::compact] amount_out_min: AssetBalance,
+            recipient: <T::Lookup as StaticLookup>::Source,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"swap_exact_assets_for_assets_through_best_route")?;
+            ensure!(
+                asset_in.is_support() && asset_out.is_support(),
+                Error::<T>::UnsupportedAssetType
+            );
+            let who = ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_swap_exact_assets_for_assets_through_best_route(
+                &who,
+                asset_in,
+                asset_out,
+                amount_in,
+                amount_out_min,
+                &recipient,
+            )
+        }
+
+        /// Buy amount of foreign through the swap path which takes the least of the sold
+        /// foreign, searched in the trading pairs.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_in`: The foreign sold
+        /// - `asset_out`: The foreign bought
+        /// - `amount_out`: Amount of the foreign will be bought
+        /// - `amount_in_max`: Maximum amount of sold foreign
+        /// - `recipient`: Account that receive the target foreign
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(Pallet::<T>::best_route_swap_weight())]
+        #[frame_support::transactional]
+        pub fn swap_assets_for_exact_assets_through_best_route(
+            origin: OriginFor<T>,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            #[pallet::compact] amount_out: AssetBalance,
+            #[pallet::compact] amount_in_max: AssetBalance,
+            recipient: <T::Lookup as StaticLookup>::Source,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"swap_assets_for_exact_assets_through_best_route")?;
+            ensure!(
+                asset_in.is_support() && asset_out.is_support(),
+                Error::<T>::UnsupportedAssetType
+            );
+            let who = ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_swap_assets_for_exact_assets_through_best_route(
+                &who,
+                asset_in,
+                asset_out,
+                amount_out,
+                amount_in_max,
+                &recipient,
+            )
+        }
+
+        /// Sell amount of foreign split across several paths by their proportions.
+        ///
+        /// # Arguments
+        ///
+        /// - `amount_in`: Amount of the foreign will be sold
+        /// - `amount_out_min`: Minimum amount of target foreign of all the routes
+        /// - `routes`: The paths with the proportion of `amount_in` sold through each of
+        ///   them, all of them start and end with the same foreign.
+        /// - `recipient`: Account that receive the target foreign
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn swap_exact_assets_for_assets_through_split_routes(
+            origin: OriginFor<T>,
+            #[pallet::compact] amount_in: AssetBalance,
+            #[pallet::compact] amount_out_min: AssetBalance,
+            routes: Vec<(Vec<AssetId>, u32)>,
+            recipient: <T::Lookup as StaticLookup>::Source,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"swap_exact_assets_for_assets_through_split_routes")?;
+            ensure!(
+                routes.iter().all(|(path, _)| path.iter().all(|id| id.is_support())),
+                Error::<T>::UnsupportedAssetType
+            );
+            let who = ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_swap_exact_assets_for_assets_through_split_routes(
+                &who,
+                amount_in,
+                amount_out_min,
+                &routes,
+                &recipient,
+            )
+        }
+
+        /// Run a batch of swaps, liquidity changes and transfers in order, all or nothing.
+        ///
+        /// The outputs of the actions go to the caller, `RouterAmount::AllOutput` takes all
+        /// the output of the asset left by the earlier actions. The minimums are only checked
+        /// against the outputs left after the last action.
+        ///
+        /// # Arguments
+        ///
+        /// - `actions`: The actions run in order, at most `MAX_ROUTER_ACTIONS`
+        /// - `amounts_out_min`: Minimum amounts of the assets left by the batch
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000u64.saturating_mul(actions.len().max(1) as u64))]
+        #[frame_support::transactional]
+        pub fn router_batch(
+            origin: OriginFor<T>,
+            actions: Vec<RouterAction<T::AccountId>>,
+            amounts_out_min: Vec<(AssetId, AssetBalance)>,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"router_batch")?;
+            let who = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+
+            let outputs = Self::inner_router_batch(&who, actions, &amounts_out_min)?;
+
+            Self::deposit_event(Event::RouterBatchExecuted(who, outputs));
+
+            Ok(())
+        }
+
+        /// Execute a swap order signed off-chain on behalf of its owner, the relayer is paid
+        /// `relayer_fee` of the first asset of the path by the owner.
+        ///
+        /// # Arguments
+        ///
+        /// - `order`: The swap order
+        /// - `signature`: The signature of the order by its owner
+        /// - `relayer_fee`: The fee to the relayer, at most the maximum of the order
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn execute_swap_order(
+            origin: OriginFor<T>,
+            order: SwapOrderOf<T>,
+            signature: OrderSignatureOf<T>,
+            #[pallet::compact] relayer_fee: AssetBalance,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"execute_swap_order")?;
+            let relayer = ensure_signed(origin)?;
+            Self::ensure_deadline(order.deadline)?;
+
+            Self::inner_execute_swap_order(&relayer, &order, &signature, relayer_fee)
+        }
+
+        /// Cancel the outstanding swap orders signed off-chain by the caller, all the orders
+        /// below `nonce` can't be executed any more.
+        ///
+        /// # Arguments
+        ///
+        /// - `nonce`: The next nonce of the caller, more than the current one
+        #[pallet::weight(1_000_000)]
+        pub fn cancel_swap_orders(origin: OriginFor<T>, nonce: u64) -> DispatchResult {
+            Self::ensure_not_paused(b"cancel_swap_orders")?;
+            let who = ensure_signed(origin)?;
+
+            Self::inner_cancel_swap_orders(&who, nonce)
+        }
+
+        /// Create a DCA order, which locks `amount_per_execution * executions` of the first
+        /// asset of the path and swaps `amount_per_execution` every `period` blocks.
+        ///
+        /// # Arguments
+        ///
+        /// - `path`: path that can convert the locked asset to the target asset, of at most
+        ///   `MaxSwapHops` hops
+        /// - `amount_per_execution`: Amount of the locked asset swapped by each execution
+        /// - `amount_out_min`: Minimum amount of the target asset of each execution
+        /// - `executions`: Number of the executions
+        /// - `period`: Blocks between the executions, the first one is `period` blocks later
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn create_dca_order(
+            origin: OriginFor<T>,
+            path: Vec<AssetId>,
+            #[pallet::compact] amount_per_execution: AssetBalance,
+            #[pallet::compact] amount_out_min: AssetBalance,
+            executions: u32,
+            period: T::BlockNumber,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"create_dca_order")?;
+            let who = ensure_signed(origin)?;
+
+            Self::inner_create_dca_order(
+                &who,
+                path,
+                amount_per_execution,
+                amount_out_min,
+                executions,
+                period,
+            )?;
+
+            Ok(())
+        }
+
+        /// Cancel a DCA order, the locked amount of the executions left is refunded.
+        ///
+        /// # Arguments
+        ///
+        /// - `order_id`: ID of the DCA order
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn cancel_dca_order(origin: OriginFor<T>, order_id: u64) -> DispatchResult {
+            Self::ensure_not_paused(b"cancel_dca_order")?;
+            let who = ensure_signed(origin)?;
+
+            Self::inner_cancel_dca_order(&who, order_id)
+        }
+
+        /// Place a limit or stop-loss order, which escrows `amount_in` and `keeper_reward` of
+        /// `asset_in` until the spot price of the pair crosses `trigger_price`.
+        ///
+        /// # Arguments
+        ///
+        /// - `kind`: Limit to sell at or above the trigger price after the slippage, StopLoss
+        ///   once the spot price is at or below it
+        /// - `asset_in`: Asset to sell
+        /// - `asset_out`: Asset to buy
+        /// - `amount_in`: Amount of asset_in to sell
+        /// - `trigger_price`: Spot price of asset_in quoted in asset_out, in `BONE`
+        /// - `amount_out_min`: Minimum amount of asset_out of the fill
+        /// - `keeper_reward`: Reward of asset_in to the keeper which fills the order
+        /// - `expiry`: The last block which the order can be filled in
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        #[allow(clippy::too_many_arguments)]
+        pub fn place_price_order(
+            origin: OriginFor<T>,
+            kind: PriceOrderKind,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            #[pallet::compact] amount_in: AssetBalance,
+            #[pallet::compact] trigger_price: u128,
+            #[pallet::compact] amount_out_min: AssetBalance,
+            #[pallet::compact] keeper_reward: AssetBalance,
+            expiry: T::BlockNumber,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"place_price_order")?;
+            let who = ensure_signed(origin)?;
+
+            Self::inner_place_price_order(PriceOrder {
+                owner: who,
+                kind,
+                asset_in,
+                asset_out,
+                amount_in,
+                trigger_price,
+                amount_out_min,
+                keeper_reward,
+                expiry,
+            })?;
+
+            Ok(())
+        }
+
+        /// Fill a triggered price order as a keeper, which is paid the keeper reward.
+        ///
+        /// # Arguments
+        ///
+        /// - `order_id`: ID of the price order
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn fill_price_order(origin: OriginFor<T>, order_id: u64) -> DispatchResult {
+            Self::ensure_not_paused(b"fill_price_order")?;
+            let keeper = ensure_signed(origin)?;
+
+            Self::inner_fill_price_order(&keeper, order_id)
+        }
+
+        /// Cancel a price order by its owner, or by anyone after the expiry, the escrowed
+        /// assets are refunded to the owner.
+        ///
+        /// # Arguments
+        ///
+        /// - `order_id`: ID of the price order
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn cancel_price_order(origin: OriginFor<T>, order_id: u64) -> DispatchResult {
+            Self::ensure_not_paused(b"cancel_price_order")?;
+            let who = ensure_signed(origin)?;
+
+            Self::inner_cancel_price_order(&who, order_id)
+        }
+
+        /// Stake liquidity of the pair in a farm, the rewards accrued so far are claimed.
+        ///
+        /// # Arguments
+        ///
+        /// - `farm_id`: ID of the farm
+        /// - `amount`: Amount of the liquidity to stake
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn stake_farm(
+            origin: OriginFor<T>,
+            farm_id: u32,
+            #[pallet::compact] amount: AssetBalance,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"stake_farm")?;
+            let who = ensure_signed(origin)?;
+
+            Self::inner_stake_farm(&who, farm_id, amount)
+        }
+
+        /// Unstake liquidity from a farm, the rewards accrued so far are claimed up to the
+        /// reward balance of the farm.
+        ///
+        /// # Arguments
+        ///
+        /// - `farm_id`: ID of the farm
+        /// - `amount`: Amount of the liquidity to unstake
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn unstake_farm(
+            origin: OriginFor<T>,
+            farm_id: u32,
+            #[pallet::compact] amount: AssetBalance,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"unstake_farm")?;
+            let who = ensure_signed(origin)?;
+
+            Self::inner_unstake_farm(&who, farm_id, amount)
+        }
+
+        /// Claim the rewards accrued by the liquidity staked in a farm.
+        ///
+        /// # Arguments
+        ///
+        /// - `farm_id`: ID of the farm
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn claim_farm_rewards(origin: OriginFor<T>, farm_id: u32) -> DispatchResult {
+            Self::ensure_not_paused(b"claim_farm_rewards")?;
+            let who = ensure_signed(origin)?;
+
+            Self::inner_claim_farm_rewards(&who, farm_id)
+        }
+
+        /// Unstake all the liquidity from a farm and give up the rewards, which works even if
+        /// the farm isn't funded.
+        ///
+        /// # Arguments
+        ///
+        /// - `farm_id`: ID of the farm
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn emergency_unstake_farm(origin: OriginFor<T>, farm_id: u32) -> DispatchResult {
+            Self::ensure_not_paused(b"emergency_unstake_farm")?;
+            let who = ensure_signed(origin)?;
+
+            Self::inner_emergency_unstake_farm(&who, farm_id)
+        }
+
+        /// Borrow assets from a pair, which must be paid back with the exchange fee by
+        /// `FlashSwapHandler` in the same call.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up pair
+        /// - `asset_1`: Asset which make up pair
+        /// - `amount_0_out`: Amount of asset_0 to borrow
+        /// - `amount_1_out`: Amount of asset_1 to borrow
+        /// - `recipient`: Account that receive the borrowed assets
+        /// - `data`: Passed through to `FlashSwapHandler`
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        #[allow(clippy::too_many_arguments)]
+        pub fn flash_swap(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            #[pallet::compact] amount_0_out: AssetBalance,
+            #[pallet::compact] amount_1_out: AssetBalance,
+            recipient: <T::Lookup as StaticLookup>::Source,
+            data: Vec<u8>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"flash_swap")?;
+            let who = ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+
+            Self::inner_flash_swap(
+                &who,
+                asset_0,
+                asset_1,
+                amount_0_out,
+                amount_1_out,
+                &recipient,
+                &data,
+            )
+        }
+
+        /// Send the balance of the pair account beyond the reserves to the recipient.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up pair
+        /// - `asset_1`: Asset which make up pair
+        /// - `recipient`: Account that receive the excess assets
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn skim(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            recipient: <T::Lookup as StaticLookup>::Source,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"skim")?;
+            ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+
+            Self::inner_skim(asset_0, asset_1, &recipient)
+        }
+
+        /// Set the reserves of the pair to the balances of the pair account.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up pair
+        /// - `asset_1`: Asset which make up pair
+        #[pallet::weight(1_000_000)]
+        pub fn sync(origin: OriginFor<T>, asset_0: AssetId, asset_1: AssetId) -> DispatchResult {
+            Self::ensure_not_paused(b"sync")?;
+            ensure_signed(origin)?;
+
+            Self::inner_sync(asset_0, asset_1)
+        }
+
+        /// Set the exchange fee rate of a pair.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up pair
+        /// - `asset_1`: Asset which make up pair
+        /// - `fee_rate`: The (numerator, denominator) of the fee rate, `None` means
+        ///   fall back to `GetExchangeFee`
+        #[pallet::weight(1_000_000)]
+        pub fn set_exchange_fee(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            fee_rate: Option<(u32, u32)>,
+        ) -> DispatchResult {
+            T::ControlOrigin::ensure_origin(origin)?;
+            let (asset_0, asset_1) = Self::sort_asset_id(asset_0, asset_1);
+            ensure!(
+                LiquidityMeta::<T>::contains_key((asset_0, asset_1)),
+                Error::<T>::PairNotExists
+            );
+            if let Some((fee_numerator, fee_denominator)) = fee_rate {
+                ensure!(fee_numerator < fee_denominator, Error::<T>::InvalidExchangeFee);
+            }
+
+            LiquidityExchangeFee::<T>::set((asset_0, asset_1), fee_rate);
+
+            let (fee_numerator, fee_denominator) = Self::get_exchange_fee(asset_0, asset_1);
+            Self::deposit_event(Event::ExchangeFeeChanged(
+                asset_0,
+                asset_1,
+                fee_numerator,
+                fee_denominator,
+            ));
+
+            Ok(())
+        }
+
+        /// Set the maximum price impact of the hops through any trading pair.
+        ///
+        /// # Arguments
+        ///
+        /// - `max_price_impact`: The (numerator, denominator) of the maximum price impact,
+        ///   `None` means no limit
+        #[pallet::weight(1_000_000)]
+        pub fn set_global_max_price_impact(
+            origin: OriginFor<T>,
+            max_price_impact: Option<(u32, u32)>,
+        ) -> DispatchResult {
+            T::ControlOrigin::ensure_origin(origin)?;
+            if let Some((impact_numerator, impact_denominator)) = max_price_impact {
+                ensure!(
+                    impact_numerator > 0 && impact_numerator < impact_denominator,
+                    Error::<T>::InvalidPriceImpact
+                );
+            }
+
+            GlobalMaxPriceImpact::<T>::set(max_price_impact);
+
+            Self::deposit_event(Event::GlobalMaxPriceImpactChanged(max_price_impact));
+
+            Ok(())
+        }
+
+        /// Set the maximum price impact of the hops through a trading pair.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up pair
+        /// - `asset_1`: Asset which make up pair
+        /// - `max_price_impact`: The (numerator, denominator) of the maximum price impact,
+        ///   `None` means fall back to the global one
+        #[pallet::weight(1_000_000)]
+        pub fn set_pair_max_price_impact(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            max_price_impact: Option<(u32, u32)>,
+        ) -> DispatchResult {
+            T::ControlOrigin::ensure_origin(origin)?;
+            let (asset_0, asset_1) = Self::sort_asset_id(asset_0, asset_1);
+            ensure!(
+                LiquidityMeta::<T>::contains_key((asset_0, asset_1)),
+                Error::<T>::PairNotExists
+            );
+            if let Some((impact_numerator, impact_denominator)) = max_price_impact {
+                ensure!(
+                    impact_numerator > 0 && impact_numerator < impact_denominator,
+                    Error::<T>::InvalidPriceImpact
+                );
+            }
+
+            PairMaxPriceImpact::<T>::set((asset_0, asset_1), max_price_impact);
+
+            Self::deposit_event(Event::PairMaxPriceImpactChanged(
+                asset_0,
+                asset_1,
+                max_price_impact,
+            ));
+
+            Ok(())
+        }
+
+        /// Set the receiver of the protocol fee.
+        ///
+        /// The protocol fee is switched on when the receiver is set.
+        ///
+        /// # Arguments
+        ///
+        /// - `receiver`: The account which the protocol fee liquidity is minted to
+        #[pallet::weight(1_000_000)]
+        pub fn set_fee_receiver(
+            origin: OriginFor<T>,
+            receiver: Option<<T::Lookup as StaticLookup>::Source>,
+        ) -> DispatchResult {
+            T::ControlOrigin::ensure_origin(origin)?;
+            let receiver = receiver.map(T::Lookup::lookup).transpose()?;
+
+            FeeMeta::<T>::mutate(|(fee_receiver, _)| *fee_receiver = receiver.clone());
+
+            Self::deposit_event(Event::FeeReceiverChanged(receiver));
+
+            Ok(())
+        }
+
+        /// Set the fraction of the fee growth which is minted to the fee receiver.
+        ///
+        /// # Arguments
+        ///
+        /// - `fee_numerator`: The numerator of the protocol fee rate
+        /// - `fee_denominator`: The denominator of the protocol fee rate
+        #[pallet::weight(1_000_000)]
+        pub fn set_protocol_fee_rate(
+            origin: OriginFor<T>,
+            fee_numerator: u32,
+            fee_denominator: u32,
+        ) -> DispatchResult {
+            T::ControlOrigin::ensure_origin(origin)?;
+            ensure!(fee_numerator < fee_denominator, Error::<T>::InvalidProtocolFee);
+
+            FeeMeta::<T>::mutate(|(_, fee_rate)| *fee_rate = (fee_numerator, fee_denominator));
+
+            Self::deposit_event(Event::ProtocolFeeRateChanged(fee_numerator, fee_denominator));
+
+            Ok(())
+        }
+
+        /// Set the assets which the trading pairs can be created of.
+        ///
+        /// # Arguments
+        ///
+        /// - `allowlist`: The allowed assets, any asset is allowed if it's none
+        #[pallet::weight(1_000_000)]
+        pub fn set_asset_allowlist(
+            origin: OriginFor<T>,
+            allowlist: Option<Vec<AssetId>>,
+        ) -> DispatchResult {
+            T::ControlOrigin::ensure_origin(origin)?;
+
+            AssetAllowlist::<T>::set(allowlist.clone());
+
+            Self::deposit_event(Event::AssetAllowlistChanged(allowlist));
+
+            Ok(())
+        }
+
+        /// Set the status of a trading pair.
+        ///
+        /// Only enabled pairs accept swaps and liquidity adds, the liquidity can be removed
+        /// from a pair of any status. A delisted pair can't be changed any more, and only a
+        /// pair without liquidity can be changed to bootstrap. A pair in bootstrap can only be
+        /// changed once the bootstrap ends without contributions to refund. The creation
+        /// deposit of the pair is returned to the creator when it's delisted.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up pair
+        /// - `asset_1`: Asset which make up pair
+        /// - `status`: The new status of the pair
+        #[pallet::weight(1_000_000)]
+        pub fn set_pair_status(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            status: PairStatus,
+        ) -> DispatchResult {
+            T::ControlOrigin::ensure_origin(origin)?;
+            let (asset_0, asset_1) = Self::sort_asset_id(asset_0, asset_1);
+
+            LiquidityMeta::<T>::try_mutate((asset_0, asset_1), |meta| {
+                let meta = meta.as_mut().ok_or(Error::<T>::PairNotExists)?;
+                ensure!(meta.status != PairStatus::Delisted, Error::<T>::InvalidPairStatus);
+                ensure!(
+                    status != PairStatus::Bootstrap || meta.total_supply.is_zero(),
+                    Error::<T>::InvalidPairStatus
+                );
+                // The contributions to the bootstrap are settled by ending or refunding it, a
+                // bootstrap without contributions is removed after the end block.
+                if meta.status == PairStatus::Bootstrap {
+                    if let Some(parameter) = Self::bootstrap_parameter((asset_0, asset_1)) {
+                        ensure!(
+                            Self::is_bootstrap_refunded(&parameter),
+                            Error::<T>::InvalidPairStatus
+                        );
+                        BootstrapParameters::<T>::remove((asset_0, asset_1));
+                    }
+                }
+
+                meta.status = status;
+
+                if status == PairStatus::Delisted {
+                    if let Some((creator, deposit)) = PairDeposits::<T>::take((asset_0, asset_1)) {
+                        T::Currency::unreserve(&creator, deposit);
+                    }
+                }
+
+                Self::deposit_event(Event::PairStatusChanged(asset_0, asset_1, status));
+
+                Ok(())
+            })
+        }
+
+        /// Pause or unpause all the calls of this pallet except the governance ones.
+        ///
+        /// # Arguments
+        ///
+        /// - `paused`: Whether the calls are paused
+        #[pallet::weight(1_000_000)]
+        pub fn set_pallet_paused(origin: OriginFor<T>, paused: bool) -> DispatchResult {
+            T::EmergencyOrigin::ensure_origin(origin)?;
+
+            PalletPaused::<T>::put(paused);
+
+            Self::deposit_event(Event::PalletPauseChanged(paused));
+
+            Ok(())
+        }
+
+        /// Pause or unpause the swaps and liquidity changes of a trading pair.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up pair
+        /// - `asset_1`: Asset which make up pair
+        /// - `paused`: Whether the pair is paused
+        #[pallet::weight(1_000_000)]
+        pub fn set_pair_paused(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            paused: bool,
+        ) -> DispatchResult {
+            T::EmergencyOrigin::ensure_origin(origin)?;
+            let (asset_0, asset_1) = Self::sort_asset_id(asset_0, asset_1);
+            ensure!(
+                LiquidityMeta::<T>::contains_key((asset_0, asset_1)),
+                Error::<T>::PairNotExists
+            );
+
+            if paused {
+                PausedPairs::<T>::insert((asset_0, asset_1), true);
+            } else {
+                PausedPairs::<T>::remove((asset_0, asset_1));
+            }
+
+            Self::deposit_event(Event::PairPauseChanged(asset_0, asset_1, paused));
+
+            Ok(())
+        }
+
+        /// Pause or unpause a call of this pallet by its name, such as `b"add_liquidity"`.
+        ///
+        /// # Arguments
+        ///
+        /// - `call_name`: The name of the call
+        /// - `paused`: Whether the call is paused
+        #[pallet::weight(1_000_000)]
+        pub fn set_call_paused(
+            origin: OriginFor<T>,
+            call_name: Vec<u8>,
+            paused: bool,
+        ) -> DispatchResult {
+            T::EmergencyOrigin::ensure_origin(origin)?;
+
+            if paused {
+                PausedCalls::<T>::insert(&call_name, true);
+            } else {
+                PausedCalls::<T>::remove(&call_name);
+            }
+
+            Self::deposit_event(Event::CallPauseChanged(call_name, paused));
+
+            Ok(())
+        }
+
+        /// Put a trading pair into bootstrap, the pair is created if it doesn't exist.
+        ///
+        /// The pair opens at the ratio of the contributions once both targets are reached,
+        /// the contributions are refundable if the targets are not reached before the end
+        /// block.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up pair
+        /// - `asset_1`: Asset which make up pair
+        /// - `target_supply_0`: Minimum amount of asset_0 contributed for the pair to open
+        /// - `target_supply_1`: Minimum amount of asset_1 contributed for the pair to open
+        /// - `end`: Height of the block before which the contributions are accepted
+        #[pallet::weight(1_000_000)]
+        pub fn bootstrap_create(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            #[pallet::compact] target_supply_0: AssetBalance,
+            #[pallet::compact] target_supply_1: AssetBalance,
+            #[pallet::compact] end: T::BlockNumber,
+        ) -> DispatchResult {
+            T::ControlOrigin::ensure_origin(origin)?;
+            ensure!(asset_0.is_support() && asset_1.is_support(), Error::<T>::UnsupportedAssetType);
+            ensure!(asset_0 != asset_1, Error::<T>::DeniedCreatePair);
+            ensure!(T::MultiAssetsHandler::is_exists(asset_0), Error::<T>::AssetNotExists);
+            ensure!(T::MultiAssetsHandler::is_exists(asset_1), Error::<T>::AssetNotExists);
+
+            Self::inner_bootstrap_create(asset_0, asset_1, target_supply_0, target_supply_1, end)
+        }
+
+        /// Contribute to the bootstrap of a trading pair.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up pair
+        /// - `asset_1`: Asset which make up pair
+        /// - `amount_0`: Amount of asset_0 contributed
+        /// - `amount_1`: Amount of asset_1 contributed
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn bootstrap_contribute(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            #[pallet::compact] amount_0: AssetBalance,
+            #[pallet::compact] amount_1: AssetBalance,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"bootstrap_contribute")?;
+            ensure!(asset_0.is_support() && asset_1.is_support(), Error::<T>::UnsupportedAssetType);
+            let who = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_bootstrap_contribute(&who, asset_0, asset_1, amount_0, amount_1)
+        }
+
+        /// End the bootstrap of a trading pair which reaches the targets, the pair opens at
+        /// the ratio of the contributions.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up pair
+        /// - `asset_1`: Asset which make up pair
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn bootstrap_end(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"bootstrap_end")?;
+            ensure_signed(origin)?;
+
+            Self::inner_bootstrap_end(asset_0, asset_1)
+        }
+
+        /// Claim the liquidity of the contributions to the bootstrap of a trading pair,
+        /// after the pair opens.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up pair
+        /// - `asset_1`: Asset which make up pair
+        /// - `recipient`: Account that receive the liquidity
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn bootstrap_claim(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            recipient: <T::Lookup as StaticLookup>::Source,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"bootstrap_claim")?;
+            let who = ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_bootstrap_claim(&who, asset_0, asset_1, &recipient)
+        }
+
+        /// Refund the contributions to the bootstrap of a trading pair, which ends without
+        /// reaching the targets.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up pair
+        /// - `asset_1`: Asset which make up pair
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn bootstrap_refund(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"bootstrap_refund")?;
+            let who = ensure_signed(origin)?;
+
+            Self::inner_bootstrap_refund(&who, asset_0, asset_1)
+        }
+
+        /// Create a liquidity mining farm of a trading pair, the rewards are paid from the
+        /// account of the farm, which must be funded with the reward asset.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up pair
+        /// - `asset_1`: Asset which make up pair
+        /// - `reward_asset`: Asset of the rewards
+        /// - `reward_per_block`: Rewards emitted to the stakers every block
+        /// - `start_block`: The block which the rewards start from
+        /// - `end_block`: The block which the rewards end at
+        #[pallet::weight(1_000_000)]
+        pub fn create_farm(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            reward_asset: AssetId,
+            #[pallet::compact] reward_per_block: AssetBalance,
+            start_block: T::BlockNumber,
+            end_block: T::BlockNumber,
+        ) -> DispatchResult {
+            T::ControlOrigin::ensure_origin(origin)?;
+
+            Self::inner_create_farm(
+                asset_0,
+                asset_1,
+                reward_asset,
+                reward_per_block,
+                start_block,
+                end_block,
+            )?;
+
+            Ok(())
+        }
+
+        /// Create a stable pool of pegged assets.
+        ///
+        /// # Arguments
+        ///
+        /// - `assets`: Assets which make up the pool
+        /// - `precisions`: Multipliers which normalize the balances of the assets to the
+        ///   same decimals
+        /// - `a`: Amplification coefficient
+        /// - `fee_numerator`: The numerator of the exchange fee rate
+        /// - `fee_denominator`: The denominator of the exchange fee rate
+        #[pallet::weight(1_000_000)]
+        pub fn create_stable_pool(
+            origin: OriginFor<T>,
+            assets: Vec<AssetId>,
+            precisions: Vec<AssetBalance>,
+            a: u128,
+            fee_numerator: u32,
+            fee_denominator: u32,
+        ) -> DispatchResult {
+            T::ControlOrigin::ensure_origin(origin)?;
+
+            Self::inner_create_stable_pool(assets, precisions, a, fee_numerator, fee_denominator)
+        }
+
+        /// Provide liquidity to a stable pool.
+        ///
+        /// # Arguments
+        ///
+        /// - `pool_id`: The stable pool
+        /// - `amounts`: Amounts of the assets added to the pool, in the order of the pool
+        /// - `min_mint_amount`: Minimum amount of liquidity minted
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn add_stable_liquidity(
+            origin: OriginFor<T>,
+            pool_id: u32,
+            amounts: Vec<AssetBalance>,
+            #[pallet::compact] min_mint_amount: AssetBalance,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"add_stable_liquidity")?;
+            let who = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_add_stable_liquidity(&who, pool_id, amounts, min_mint_amount)
+        }
+
+        /// Extract liquidity from a stable pool, in proportion to the reserves.
+        ///
+        /// # Arguments
+        ///
+        /// - `pool_id`: The stable pool
+        /// - `liquidity`: Liquidity token amount to remove
+        /// - `min_amounts`: Minimum amounts of the assets removed, in the order of the pool
+        /// - `recipient`: Account that accepts withdrawal of assets
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn remove_stable_liquidity(
+            origin: OriginFor<T>,
+            pool_id: u32,
+            #[pallet::compact] liquidity: AssetBalance,
+            min_amounts: Vec<AssetBalance>,
+            recipient: <T::Lookup as StaticLookup>::Source,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"remove_stable_liquidity")?;
+            let who = ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_remove_stable_liquidity(&who, pool_id, liquidity, min_amounts, &recipient)
+        }
+
+        /// Ramp the amplification coefficient of a stable pool linearly until `future_block`.
+        ///
+        /// # Arguments
+        ///
+        /// - `pool_id`: The stable pool
+        /// - `future_a`: Amplification coefficient at the end of the ramping
+        /// - `future_block`: Height of the block the ramping ends at, at least
+        ///   `MIN_RAMP_BLOCKS` blocks later
+        #[pallet::weight(1_000_000)]
+        pub fn ramp_a(
+            origin: OriginFor<T>,
+            pool_id: u32,
+            future_a: u128,
+            #[pallet::compact] future_block: T::BlockNumber,
+        ) -> DispatchResult {
+            T::ControlOrigin::ensure_origin(origin)?;
+
+            Self::inner_ramp_a(pool_id, future_a, future_block)
+        }
+
+        /// Stop the ramping of the amplification coefficient at the current value.
+        ///
+        /// # Arguments
+        ///
+        /// - `pool_id`: The stable pool
+        #[pallet::weight(1_000_000)]
+        pub fn stop_ramp_a(origin: OriginFor<T>, pool_id: u32) -> DispatchResult {
+            T::ControlOrigin::ensure_origin(origin)?;
+
+            Self::inner_stop_ramp_a(pool_id)
+        }
+
+        /// Create a weighted pool of assets.
+        ///
+        /// # Arguments
+        ///
+        /// - `assets`: Assets which make up the pool
+        /// - `weights`: Weights of the assets, normalized by the sum of them
+        /// - `fee_numerator`: The numerator of the exchange fee rate
+        /// - `fee_denominator`: The denominator of the exchange fee rate
+        #[pallet::weight(1_000_000)]
+        pub fn create_weighted_pool(
+            origin: OriginFor<T>,
+            assets: Vec<AssetId>,
+            weights: Vec<u128>,
+            fee_numerator: u32,
+            fee_denominator: u32,
+        ) -> DispatchResult {
+            T::ControlOrigin::ensure_origin(origin)?;
+
+            Self::inner_create_weighted_pool(assets, weights, fee_numerator, fee_denominator)
+        }
+
+        /// Provide liquidity to a weighted pool, in proportion to the reserves.
+        ///
+        /// The first deposit sets the reserves, and so the prices, of the pool.
+        ///
+        /// # Arguments
+        ///
+        /// - `pool_id`: The weighted pool
+        /// - `max_amounts`: Maximum amounts of the assets added to the pool, in the order of
+        ///   the pool
+        /// - `min_mint_amount`: Minimum amount of liquidity minted
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn add_weighted_liquidity(
+            origin: OriginFor<T>,
+            pool_id: u32,
+            max_amounts: Vec<AssetBalance>,
+            #[pallet::compact] min_mint_amount: AssetBalance,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"add_weighted_liquidity")?;
+            let who = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_add_weighted_liquidity(&who, pool_id, max_amounts, min_mint_amount)
+        }
+
+        /// Extract liquidity from a weighted pool, in proportion to the reserves.
+        ///
+        /// # Arguments
+        ///
+        /// - `pool_id`: The weighted pool
+        /// - `liquidity`: Liquidity token amount to remove
+        /// - `min_amounts`: Minimum amounts of the assets removed, in the order of the pool
+        /// - `recipient`: Account that accepts withdrawal of assets
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn remove_weighted_liquidity(
+            origin: OriginFor<T>,
+            pool_id: u32,
+            #[pallet::compact] liquidity: AssetBalance,
+            min_amounts: Vec<AssetBalance>,
+            recipient: <T::Lookup as StaticLookup>::Source,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"remove_weighted_liquidity")?;
+            let who = ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_remove_weighted_liquidity(&who, pool_id, liquidity, min_amounts, &recipient)
+        }
+
+        /// Provide liquidity to a weighted pool with a single asset.
+        ///
+        /// # Arguments
+        ///
+        /// - `pool_id`: The weighted pool
+        /// - `asset_id`: Asset added to the pool
+        /// - `amount`: Amount of the asset added to the pool
+        /// - `min_mint_amount`: Minimum amount of liquidity minted
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn add_weighted_liquidity_single_asset(
+            origin: OriginFor<T>,
+            pool_id: u32,
+            asset_id: AssetId,
+            #[pallet::compact] amount: AssetBalance,
+            #[pallet::compact] min_mint_amount: AssetBalance,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"add_weighted_liquidity_single_asset")?;
+            let who = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_add_weighted_liquidity_single_asset(
+                &who,
+                pool_id,
+                asset_id,
+                amount,
+                min_mint_amount,
+            )
+        }
+
+        /// Extract liquidity from a weighted pool as a single asset.
+        ///
+        /// # Arguments
+        ///
+        /// - `pool_id`: The weighted pool
+        /// - `asset_id`: Asset removed from the pool
+        /// - `liquidity`: Liquidity token amount to remove
+        /// - `min_amount`: Minimum amount of the asset removed
+        /// - `recipient`: Account that accepts withdrawal of assets
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        #[allow(clippy::too_many_arguments)]
+        pub fn remove_weighted_liquidity_single_asset(
+            origin: OriginFor<T>,
+            pool_id: u32,
+            asset_id: AssetId,
+            #[pallet::compact] liquidity: AssetBalance,
+            #[pallet::compact] min_amount: AssetBalance,
+            recipient: <T::Lookup as StaticLookup>::Source,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"remove_weighted_liquidity_single_asset")?;
+            let who = ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_remove_weighted_liquidity_single_asset(
+                &who, pool_id, asset_id, liquidity, min_amount, &recipient,
+            )
+        }
+
+        /// Create a concentrated liquidity pool of two assets.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up the pool
+        /// - `asset_1`: Asset which make up the pool
+        /// - `fee_numerator`: The numerator of the exchange fee rate
+        /// - `fee_denominator`: The denominator of the exchange fee rate
+        /// - `tick_spacing`: The ticks of the positions must be multiples of it
+        /// - `initial_tick`: The tick of the initial price of `asset_1` quoted in `asset_0`
+        #[pallet::weight(1_000_000)]
+        pub fn create_concentrated_pool(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            fee_numerator: u32,
+            fee_denominator: u32,
+            tick_spacing: u32,
+            initial_tick: i32,
+        ) -> DispatchResult {
+            T::ControlOrigin::ensure_origin(origin)?;
+
+            Self::inner_create_concentrated_pool(
+                asset_0,
+                asset_1,
+                fee_numerator,
+                fee_denominator,
+                tick_spacing,
+                initial_tick,
+            )
+        }
+
+        /// Provide liquidity to a position between two ticks of the price of `asset_1` quoted
+        /// in `asset_0`.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up the pool
+        /// - `asset_1`: Asset which make up the pool
+        /// - `tick_lower`: The lower tick of the position
+        /// - `tick_upper`: The upper tick of the position
+        /// - `amount_0_desired`: Maximum amount of asset_0 added to the position
+        /// - `amount_1_desired`: Maximum amount of asset_1 added to the position
+        /// - `amount_0_min`: Minimum amount of asset_0 added to the position
+        /// - `amount_1_min`: Minimum amount of asset_1 added to the position
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        #[allow(clippy::too_many_arguments)]
+        pub fn add_concentrated_liquidity(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            tick_lower: i32,
+            tick_upper: i32,
+            #[pallet::compact] amount_0_desired: AssetBalance,
+            #[pallet::compact] amount_1_desired: AssetBalance,
+            #[pallet::compact] amount_0_min: AssetBalance,
+            #[pallet::compact] amount_1_min: AssetBalance,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"add_concentrated_liquidity")?;
+            let who = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_add_concentrated_liquidity(
+                &who,
+                asset_0,
+                asset_1,
+                tick_lower,
+                tick_upper,
+                amount_0_desired,
+                amount_1_desired,
+                amount_0_min,
+                amount_1_min,
+            )
+        }
+
+        /// Extract liquidity from a position, the fees earned by it are kept until collected.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up the pool
+        /// - `asset_1`: Asset which make up the pool
+        /// - `tick_lower`: The lower tick of the position
+        /// - `tick_upper`: The upper tick of the position
+        /// - `liquidity`: Liquidity amount to remove
+        /// - `amount_0_min`: Minimum amount of asset_0 to receive
+        /// - `amount_1_min`: Minimum amount of asset_1 to receive
+        /// - `recipient`: Account that accepts withdrawal of assets
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        #[allow(clippy::too_many_arguments)]
+        pub fn remove_concentrated_liquidity(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            tick_lower: i32,
+            tick_upper: i32,
+            #[pallet::compact] liquidity: u128,
+            #[pallet::compact] amount_0_min: AssetBalance,
+            #[pallet::compact] amount_1_min: AssetBalance,
+            recipient: <T::Lookup as StaticLookup>::Source,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"remove_concentrated_liquidity")?;
+            let who = ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_remove_concentrated_liquidity(
+                &who,
+                asset_0,
+                asset_1,
+                tick_lower,
+                tick_upper,
+                liquidity,
+                amount_0_min,
+                amount_1_min,
+                &recipient,
+            )
+        }
+
+        /// Collect the fees earned by a position.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up the pool
+        /// - `asset_1`: Asset which make up the pool
+        /// - `tick_lower`: The lower tick of the position
+        /// - `tick_upper`: The upper tick of the position
+        /// - `recipient`: Account that accepts the fees
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn collect_concentrated_fees(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            tick_lower: i32,
+            tick_upper: i32,
+            recipient: <T::Lookup as StaticLookup>::Source,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"collect_concentrated_fees")?;
+            let who = ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+
+            Self::inner_collect_concentrated_fees(
+                &who, asset_0, asset_1, tick_lower, tick_upper, &recipient,
+            )
+        }
+
+        /// Sell the exact amount of an asset in the concentrated liquidity pool.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_in`: Asset sold
+        /// - `asset_out`: Asset bought
+        /// - `amount_in`: Amount of asset_in sold
+        /// - `amount_out_min`: Minimum amount of asset_out bought
+        /// - `recipient`: Account that receives the asset bought
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn concentrated_swap_exact_assets_for_assets(
+            origin: OriginFor<T>,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            #[pallet::compact] amount_in: AssetBalance,
+            #[pallet::compact] amount_out_min: AssetBalance,
+            recipient: <T::Lookup as StaticLookup>::Source,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"concentrated_swap_exact_assets_for_assets")?;
+            let who = ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_concentrated_swap_exact_assets_for_assets(
+                &who,
+                asset_in,
+                asset_out,
+                amount_in,
+                amount_out_min,
+                &recipient,
+            )
+        }
+
+        /// Buy the exact amount of an asset in the concentrated liquidity pool.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_in`: Asset sold
+        /// - `asset_out`: Asset bought
+        /// - `amount_out`: Amount of asset_out bought
+        /// - `amount_in_max`: Maximum amount of asset_in sold
+        /// - `recipient`: Account that receives the asset bought
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn concentrated_swap_assets_for_exact_assets(
+            origin: OriginFor<T>,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            #[pallet::compact] amount_out: AssetBalance,
+            #[pallet::compact] amount_in_max: AssetBalance,
+            recipient: <T::Lookup as StaticLookup>::Source,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"concentrated_swap_assets_for_exact_assets")?;
+            let who = ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_concentrated_swap_assets_for_exact_assets(
+                &who,
+                asset_in,
+                asset_out,
+                amount_out,
+                amount_in_max,
+                &recipient,
+            )
+        }
+
+        /// Create a liquidity bootstrapping pool of two assets with the assets of the owner.
+        ///
+        /// The weights move linearly from the start weights at the start block to the end
+        /// weights at the end block, the pool is swapped between the two blocks. The end block
+        /// is at most `MAX_LBP_DURATION` blocks later.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up the pool
+        /// - `asset_1`: Asset which make up the pool
+        /// - `amount_0`: Amount of asset_0 deposited
+        /// - `amount_1`: Amount of asset_1 deposited
+        /// - `start_weight_0`: Normalized weight of asset_0 at the start block, in `BONE`
+        /// - `end_weight_0`: Normalized weight of asset_0 at the end block, in `BONE`
+        /// - `start_block`: Height of the block which the swaps start at
+        /// - `end_block`: Height of the block which the swaps end at
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        #[allow(clippy::too_many_arguments)]
+        pub fn create_lbp(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            #[pallet::compact] amount_0: AssetBalance,
+            #[pallet::compact] amount_1: AssetBalance,
+            start_weight_0: u128,
+            end_weight_0: u128,
+            #[pallet::compact] start_block: T::BlockNumber,
+            #[pallet::compact] end_block: T::BlockNumber,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"create_lbp")?;
+            let who = T::CreatePairOrigin::ensure_origin(origin)?;
+            ensure!(asset_0.is_support() && asset_1.is_support(), Error::<T>::UnsupportedAssetType);
+            ensure!(asset_0 != asset_1, Error::<T>::InvalidPoolAssets);
+
+            Self::inner_create_lbp(
+                &who,
+                asset_0,
+                asset_1,
+                amount_0,
+                amount_1,
+                start_weight_0,
+                end_weight_0,
+                start_block,
+                end_block,
+            )
+        }
+
+        /// Change the weights and blocks of a liquidity bootstrapping pool before it starts.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up the pool
+        /// - `asset_1`: Asset which make up the pool
+        /// - `start_weight_0`: Normalized weight of asset_0 at the start block, in `BONE`
+        /// - `end_weight_0`: Normalized weight of asset_0 at the end block, in `BONE`
+        /// - `start_block`: Height of the block which the swaps start at
+        /// - `end_block`: Height of the block which the swaps end at
+        #[pallet::weight(1_000_000)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn update_lbp(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            start_weight_0: u128,
+            end_weight_0: u128,
+            #[pallet::compact] start_block: T::BlockNumber,
+            #[pallet::compact] end_block: T::BlockNumber,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"update_lbp")?;
+            let who = ensure_signed(origin)?;
+
+            Self::inner_update_lbp(
+                &who,
+                asset_0,
+                asset_1,
+                start_weight_0,
+                end_weight_0,
+                start_block,
+                end_block,
+            )
+        }
+
+        /// Withdraw all the assets of a liquidity bootstrapping pool after it ends.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_0`: Asset which make up the pool
+        /// - `asset_1`: Asset which make up the pool
+        /// - `recipient`: Account that receives the assets
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn withdraw_lbp(
+            origin: OriginFor<T>,
+            asset_0: AssetId,
+            asset_1: AssetId,
+            recipient: <T::Lookup as StaticLookup>::Source,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"withdraw_lbp")?;
+            let who = ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+
+            Self::inner_withdraw_lbp(&who, asset_0, asset_1, &recipient)
+        }
+
+        /// Sell the exact amount of an asset in the liquidity bootstrapping pool.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_in`: Asset sold
+        /// - `asset_out`: Asset bought
+        /// - `amount_in`: Amount of asset_in sold
+        /// - `amount_out_min`: Minimum amount of asset_out bought
+        /// - `recipient`: Account that receives the asset bought
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn lbp_swap_exact_assets_for_assets(
+            origin: OriginFor<T>,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            #[pallet::compact] amount_in: AssetBalance,
+            #[pallet::compact] amount_out_min: AssetBalance,
+            recipient: <T::Lookup as StaticLookup>::Source,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"lbp_swap_exact_assets_for_assets")?;
+            ensure!(
+                asset_in.is_support() && asset_out.is_support(),
+                Error::<T>::UnsupportedAssetType
+            );
+            let who = ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_lbp_swap_exact_assets_for_assets(
+                &who,
+                asset_in,
+                asset_out,
+                amount_in,
+                amount_out_min,
+                &recipient,
+            )
+        }
+
+        /// Buy the exact amount of an asset in the liquidity bootstrapping pool.
+        ///
+        /// # Arguments
+        ///
+        /// - `asset_in`: Asset sold
+        /// - `asset_out`: Asset bought
+        /// - `amount_out`: Amount of asset_out bought
+        /// - `amount_in_max`: Maximum amount of asset_in sold
+        /// - `recipient`: Account that receives the asset bought
+        /// - `deadline`: Height or timestamp of the cutoff block of this transaction
+        #[pallet::weight(1_000_000)]
+        #[frame_support::transactional]
+        pub fn lbp_swap_assets_for_exact_assets(
+            origin: OriginFor<T>,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            #[pallet::compact] amount_out: AssetBalance,
+            #[pallet::compact] amount_in_max: AssetBalance,
+            recipient: <T::Lookup as StaticLookup>::Source,
+            deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+        ) -> DispatchResult {
+            Self::ensure_not_paused(b"lbp_swap_assets_for_exact_assets")?;
+            ensure!(
+                asset_in.is_support() && asset_out.is_support(),
+                Error::<T>::UnsupportedAssetType
+            );
+            let who = ensure_signed(origin)?;
+            let recipient = T::Lookup::lookup(recipient)?;
+            Self::ensure_deadline(deadline)?;
+
+            Self::inner_lbp_swap_assets_for_exact_assets(
+                &who,
+                asset_in,
+                asset_out,
+                amount_out,
+                amount_in_max,
+                &recipient,
+            )
+        }
     }
 }
@@ -10,8 +10,6 @@
 
 use super::*;
 
-#[cfg(test)]
-mod mock;
 #[cfg(test)]
 mod tests;
 
@@ -26,6 +24,13 @@ impl<T: Config> Pallet<T> {
         target: &T::AccountId,
         amount: AssetBalance,
     ) -> DispatchResult {
+        if let Some(pool_id) = Self::stable_pool_id_of_lp(id.asset_index) {
+            return Self::stable_lp_transfer(id, pool_id, owner, target, amount);
+        }
+        if let Some(pool_id) = Self::weighted_pool_id_of_lp(id.asset_index) {
+            return Self::weighted_lp_transfer(id, pool_id, owner, target, amount);
+        }
+
         let pair = Self::get_lp_pair(id.asset_index).ok_or(Error::<T>::AssetNotExists)?;
 
         let owner_balance = <LiquidityLedger<T>>::get((&pair, owner));
@@ -50,6 +55,13 @@ impl<T: Config> Pallet<T> {
         owner: &T::AccountId,
         amount: AssetBalance,
     ) -> DispatchResult {
+        if let Some(pool_id) = Self::stable_pool_id_of_lp(id.asset_index) {
+            return Self::stable_lp_mint(id, pool_id, owner, amount);
+        }
+        if let Some(pool_id) = Self::weighted_pool_id_of_lp(id.asset_index) {
+            return Self::weighted_lp_mint(id, pool_id, owner, amount);
+        }
+
         let pair = Self::get_lp_pair(id.asset_index).ok_or(Error::<T>::AssetNotExists)?;
 
         let new_balance = <LiquidityLedger<T>>::get((pair, owner))
@@ -61,7 +73,8 @@ impl<T: Config> Pallet<T> {
         <LiquidityMeta<T>>::try_mutate_exists::<_, _, Error<T>, _>(pair, |meta| {
             let meta = meta.as_mut().ok_or(Error::<T>::AssetNotExists)?;
 
-            meta.1 = meta.1.checked_add(amount).ok_or(Error::<T>::Overflow)?;
+            meta.total_supply =
+                meta.total_supply.checked_add(amount).ok_or(Error::<T>::Overflow)?;
 
             Ok(())
         })?;
@@ -77,6 +90,13 @@ impl<T: Config> Pallet<T> {
         owner: &T::AccountId,
         amount: AssetBalance,
     ) -> DispatchResult {
+        if let Some(pool_id) = Self::stable_pool_id_of_lp(id.asset_index) {
+            return Self::stable_lp_burn(id, pool_id, owner, amount);
+        }
+        if let Some(pool_id) = Self::weighted_pool_id_of_lp(id.asset_index) {
+            return Self::weighted_lp_burn(id, pool_id, owner, amount);
+        }
+
         let pair = Self::get_lp_pair(id.asset_index).ok_or(Error::<T>::AssetNotExists)?;
 
         let new_balance = <LiquidityLedger<T>>::get((pair, owner))
@@ -88,7 +108,8 @@ impl<T: Config> Pallet<T> {
         <LiquidityMeta<T>>::try_mutate::<_, _, Error<T>, _>(pair, |meta| {
             let meta = meta.as_mut().ok_or(Error::<T>::AssetNotExists)?;
 
-            meta.1 = meta.1.checked_sub(amount).ok_or(Error::<T>::InsufficientLiquidity)?;
+            meta.total_supply =
+                meta.total_supply.checked_sub(amount).ok_or(Error::<T>::InsufficientLiquidity)?;
 
             Ok(())
         })?;
@@ -102,7 +123,11 @@ impl<T: Config> Pallet<T> {
 
     /// Get the local liquidity `id` balance of `owner`.
     pub fn lp_balance_of(id: AssetId, owner: &T::AccountId) -> AssetBalance {
-        if let Some(pair) = Self::get_lp_pair(id.asset_index) {
+        if let Some(pool_id) = Self::stable_pool_id_of_lp(id.asset_index) {
+            Self::stable_ledger((pool_id, owner))
+        } else if let Some(pool_id) = Self::weighted_pool_id_of_lp(id.asset_index) {
+            Self::weighted_ledger((pool_id, owner))
+        } else if let Some(pair) = Self::get_lp_pair(id.asset_index) {
             Self::lp_ledger((pair, owner))
         } else {
             Default::default()
@@ -112,14 +137,24 @@ impl<T: Config> Pallet<T> {
     /// Get the total supply of an foreign `id`.
     /// return default value if none
     pub fn lp_total_supply(id: AssetId) -> AssetBalance {
-        if let Some(pair) = Self::get_lp_pair(id.asset_index) {
-            Self::lp_metadata(pair).map(|(_, total)| total).unwrap_or_default()
+        if let Some(pool_id) = Self::stable_pool_id_of_lp(id.asset_index) {
+            Self::stable_pool(pool_id).map(|pool| pool.total_supply).unwrap_or_default()
+        } else if let Some(pool_id) = Self::weighted_pool_id_of_lp(id.asset_index) {
+            Self::weighted_pool(pool_id).map(|pool| pool.total_supply).unwrap_or_default()
+        } else if let Some(pair) = Self::get_lp_pair(id.asset_index) {
+            Self::lp_metadata(pair).map(|meta| meta.total_supply).unwrap_or_default()
         } else {
             Default::default()
         }
     }
 
     pub fn lp_is_exists(id: AssetId) -> bool {
-        Self::get_lp_pair(id.asset_index).is_some()
+        if let Some(pool_id) = Self::stable_pool_id_of_lp(id.asset_index) {
+            StablePools::<T>::contains_key(pool_id)
+        } else if let Some(pool_id) = Self::weighted_pool_id_of_lp(id.asset_index) {
+            WeightedPools::<T>::contains_key(pool_id)
+        } else {
+            Self::get_lp_pair(id.asset_index).is_some()
+        }
     }
 }
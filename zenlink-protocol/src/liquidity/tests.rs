@@ -3,7 +3,8 @@
 
 use frame_support::{assert_noop, assert_ok};
 
-use super::{mock::*, AssetId, Error, MultiAssetsHandler};
+use super::{AssetId, Error, MultiAssetsHandler};
+use crate::mock::*;
 
 const DOT_ASSET_ID: AssetId = AssetId { chain_id: 200, asset_type: LOCAL, asset_index: 2 };
 
@@ -54,11 +55,11 @@ fn local_lp_mint_should_work() {
         assert!(DexPallet::lp_is_exists(LOCAL_LP_BTC_ETH));
 
         let meta_dot_btc = DexPallet::lp_metadata(PAIR_DOT_BTC).unwrap();
-        assert_eq!(meta_dot_btc.0, PAIR_DOT_BTC_ACCOUNT);
-        assert_eq!(meta_dot_btc.1, 0);
+        assert_eq!(meta_dot_btc.pair_account, PAIR_DOT_BTC_ACCOUNT);
+        assert_eq!(meta_dot_btc.total_supply, 0);
         let meta_btc_eth = DexPallet::lp_metadata(PAIR_BTC_ETH).unwrap();
-        assert_eq!(meta_btc_eth.0, PAIR_BTC_ETH_ACCOUNT);
-        assert_eq!(meta_btc_eth.1, 0);
+        assert_eq!(meta_btc_eth.pair_account, PAIR_BTC_ETH_ACCOUNT);
+        assert_eq!(meta_btc_eth.total_supply, 0);
 
         assert_eq!(DexPallet::lp_total_supply(LOCAL_LP_DOT_BTC), 0);
         assert_eq!(DexPallet::lp_total_supply(LOCAL_LP_BTC_ETH), 0);
@@ -0,0 +1,42 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+//! # Migration Module
+//!
+//! ## Overview
+//!
+//! The storage migrations of the pallet, which run in `on_runtime_upgrade` when the storage
+//! version of the pallet is below the version of the migration. The storage version is the
+//! crate version, which is stored at the genesis and after every runtime upgrade.
+
+use super::*;
+
+use frame_support::traits::{GetPalletVersion, PalletVersion};
+
+/// The version which `LiquidityMeta` is stored as `PairMetadata` since.
+pub const PAIR_METADATA_VERSION: PalletVersion = PalletVersion { major: 0, minor: 5, patch: 0 };
+
+/// Migrate `LiquidityMeta` from `(PairAccountId, TotalSupply)` to `PairMetadata`. The reserves
+/// are the balances of the pair account and every pair is enabled.
+pub fn migrate_to_pair_metadata<T: Config>() -> Weight {
+    if Pallet::<T>::storage_version().map_or(false, |version| version >= PAIR_METADATA_VERSION) {
+        return T::DbWeight::get().reads(1);
+    }
+
+    let mut translated: Weight = 0;
+    LiquidityMeta::<T>::translate::<(T::AccountId, AssetBalance), _>(
+        |(asset_0, asset_1), (pair_account, total_supply)| {
+            translated = translated.saturating_add(1);
+
+            Some(PairMetadata {
+                reserve_0: T::MultiAssetsHandler::balance_of(asset_0, &pair_account),
+                reserve_1: T::MultiAssetsHandler::balance_of(asset_1, &pair_account),
+                pair_account,
+                total_supply,
+                status: PairStatus::Enabled,
+            })
+        },
+    );
+
+    T::DbWeight::get().reads_writes(translated.saturating_mul(3).saturating_add(1), translated)
+}
@@ -0,0 +1,209 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+//! Test utilities
+use codec::{Decode, Encode};
+use frame_support::{
+    dispatch::DispatchResult, parameter_types, traits::Get, PalletId, RuntimeDebug,
+};
+use frame_system::{EnsureRoot, EnsureSigned};
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+    DispatchError,
+};
+use std::cell::RefCell;
+
+use crate as pallet_zenlink;
+pub use crate::{
+    AssetBalance, AssetId, Config, Deadline, FlashSwapHandler, MultiAssetsHandler,
+    OrderSignatureVerifier, Pallet, ParaId, ZenlinkMultiAssets, LIQUIDITY, LOCAL, NATIVE, RESERVED,
+};
+
+thread_local! {
+    static PAIR_CREATION_DEPOSIT: RefCell<u128> = RefCell::new(0);
+}
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>} = 0,
+        Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent} = 1,
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>} = 8,
+        Zenlink: pallet_zenlink::{Pallet, Call, Storage, Event<T>} = 9,
+    }
+);
+
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 1;
+
+    pub const BlockHashCount: u64 = 250;
+    pub const MinimumPeriod: u64 = 1;
+    pub const ZenlinkPalletId: PalletId = PalletId(*b"/zenlink");
+    pub const GetExchangeFee: (u32, u32) = (3, 1000);   // 0.3%
+    pub const MaxSwapHops: u32 = 2;
+    pub const MaxDcaExecutionsPerBlock: u32 = 2;
+    pub const MaxPriceOrderChecksPerBlock: u32 = 4;
+    pub const PriceOrderDeposit: u128 = 1;
+}
+
+/// The deposit of creating a pair, which is 0 unless a test sets it.
+pub struct PairCreationDeposit;
+
+impl PairCreationDeposit {
+    pub fn set(deposit: u128) {
+        PAIR_CREATION_DEPOSIT.with(|v| *v.borrow_mut() = deposit);
+    }
+}
+
+impl Get<u128> for PairCreationDeposit {
+    fn get() -> u128 {
+        PAIR_CREATION_DEPOSIT.with(|v| *v.borrow())
+    }
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = ();
+    type Origin = Origin;
+    type Index = u64;
+    type Call = Call;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u128;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type DbWeight = ();
+    type Version = ();
+    type AccountData = pallet_balances::AccountData<u128>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type PalletInfo = PalletInfo;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+}
+
+impl pallet_balances::Config for Test {
+    type Balance = u128;
+    type DustRemoval = ();
+    type Event = Event;
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = frame_system::Pallet<Test>;
+    type WeightInfo = ();
+    type MaxLocks = ();
+}
+
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = MinimumPeriod;
+    type WeightInfo = ();
+}
+
+impl Config for Test {
+    type Event = Event;
+    type GetExchangeFee = GetExchangeFee;
+    type MultiAssetsHandler = ZenlinkMultiAssets<Zenlink, Balances>;
+    type PalletId = ZenlinkPalletId;
+    type ControlOrigin = EnsureRoot<u128>;
+    type CreatePairOrigin = EnsureSigned<u128>;
+    type Currency = Balances;
+    type PairCreationDeposit = PairCreationDeposit;
+    type EmergencyOrigin = EnsureRoot<u128>;
+    type Time = Timestamp;
+    type OrderSignatureVerifier = TestSignatureVerifier;
+    type FlashSwapHandler = MockFlashSwapHandler;
+    type MaxSwapHops = MaxSwapHops;
+    type MaxDcaExecutionsPerBlock = MaxDcaExecutionsPerBlock;
+    type MaxPriceOrderChecksPerBlock = MaxPriceOrderChecksPerBlock;
+    type PriceOrderDeposit = PriceOrderDeposit;
+    type TargetChains = ();
+    type SelfParaId = ();
+    type XcmExecutor = ();
+    type Conversion = ();
+}
+
+pub type DexPallet = Pallet<Test>;
+
+pub fn balance_of(asset_id: AssetId, who: &u128) -> u128 {
+    <Test as Config>::MultiAssetsHandler::balance_of(asset_id, who)
+}
+
+pub fn has_event(event: crate::Event<Test>) -> bool {
+    System::events().iter().any(|record| record.event == Event::Zenlink(event.clone()))
+}
+
+/// Pays back the flash swap from `who` by the (amount_0, amount_1, reenter) encoded in `data`,
+/// swaps in the borrowed pair first if `reenter` is set.
+pub struct MockFlashSwapHandler;
+
+impl FlashSwapHandler<u128> for MockFlashSwapHandler {
+    fn on_flash_swap(
+        who: &u128,
+        _recipient: &u128,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        _amount_0: AssetBalance,
+        _amount_1: AssetBalance,
+        data: &[u8],
+    ) -> DispatchResult {
+        let (pay_0, pay_1, reenter) = <(AssetBalance, AssetBalance, bool)>::decode(&mut &data[..])
+            .map_err(|_| DispatchError::Other("Invalid flash swap data"))?;
+
+        if reenter {
+            DexPallet::inner_swap_exact_tokens_for_tokens(who, pay_0, 0, &[asset_0, asset_1], who)?;
+        }
+
+        let pair_account = DexPallet::pair_account_id(asset_0, asset_1);
+        if pay_0 > 0 {
+            <Test as Config>::MultiAssetsHandler::transfer(asset_0, who, &pair_account, pay_0)?;
+        }
+        if pay_1 > 0 {
+            <Test as Config>::MultiAssetsHandler::transfer(asset_1, who, &pair_account, pay_1)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The signature of the tests, which is valid when it's made of the signer and the message.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct TestSignature(pub u128, pub Vec<u8>);
+
+pub struct TestSignatureVerifier;
+
+impl OrderSignatureVerifier<u128> for TestSignatureVerifier {
+    type Signature = TestSignature;
+
+    fn verify(signature: &Self::Signature, message: &[u8], signer: &u128) -> bool {
+        signature.0 == *signer && signature.1 == message
+    }
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into();
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![
+            (1, 34028236692093846346337460743176821145),
+            (2, 10),
+            (3, 10),
+            (4, 10),
+            (5, 10),
+        ],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+    t.into()
+}
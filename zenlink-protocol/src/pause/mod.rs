@@ -0,0 +1,40 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+//! # Circuit Breaker Module
+//!
+//! ## Overview
+//!
+//! Pause flags which stop the calls of this pallet in an emergency, such as an exploit or a
+//! depeg, without a runtime upgrade. The whole pallet, a trading pair or a single call can
+//! be paused by the emergency origin, the governance calls and the read-only runtime APIs
+//! are never paused.
+
+use super::*;
+
+#[cfg(test)]
+mod tests;
+
+impl<T: Config> Pallet<T> {
+    /// Whether the call named `call_name` is paused, by the pallet or by itself.
+    pub fn is_call_paused(call_name: &[u8]) -> bool {
+        Self::pallet_paused() || PausedCalls::<T>::get(call_name)
+    }
+
+    /// Whether the swaps and liquidity changes of the trading pair are paused.
+    pub fn is_pair_paused(asset_0: AssetId, asset_1: AssetId) -> bool {
+        Self::pair_paused(Self::sort_asset_id(asset_0, asset_1))
+    }
+
+    pub(crate) fn ensure_not_paused(call_name: &[u8]) -> DispatchResult {
+        ensure!(!Self::is_call_paused(call_name), Error::<T>::Paused);
+
+        Ok(())
+    }
+
+    pub(crate) fn ensure_pair_not_paused(asset_0: AssetId, asset_1: AssetId) -> DispatchResult {
+        ensure!(!Self::is_pair_paused(asset_0, asset_1), Error::<T>::Paused);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::DispatchError;
+
+use super::{AssetId, Error, MultiAssetsHandler};
+use crate::mock::*;
+
+const DOT_ASSET_ID: AssetId = AssetId { chain_id: 200, asset_type: LOCAL, asset_index: 2 };
+
+const BTC_ASSET_ID: AssetId = AssetId { chain_id: 300, asset_type: RESERVED, asset_index: 3 };
+
+const ETH_ASSET_ID: AssetId = AssetId { chain_id: 400, asset_type: NATIVE, asset_index: 0 };
+
+const ALICE: u128 = 1;
+const BOB: u128 = 2;
+
+const DOT_UNIT: u128 = 1000_000_000_000_000;
+const BTC_UNIT: u128 = 1000_000_00;
+const ETH_UNIT: u128 = 1000_000_000_000;
+
+/// The DOT-BTC and DOT-ETH pairs with liquidity of ALICE.
+fn create_pairs() {
+    for asset_id in [DOT_ASSET_ID, BTC_ASSET_ID, ETH_ASSET_ID].iter() {
+        assert_ok!(DexPallet::foreign_mint(*asset_id, &ALICE, u128::MAX / 2));
+    }
+    assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+    assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, ETH_ASSET_ID));
+
+    assert_ok!(DexPallet::inner_add_liquidity(
+        &ALICE,
+        DOT_ASSET_ID,
+        BTC_ASSET_ID,
+        1000 * DOT_UNIT,
+        1000 * BTC_UNIT,
+        0,
+        0
+    ));
+    assert_ok!(DexPallet::inner_add_liquidity(
+        &ALICE,
+        DOT_ASSET_ID,
+        ETH_ASSET_ID,
+        1000 * DOT_UNIT,
+        1000 * ETH_UNIT,
+        0,
+        0
+    ));
+}
+
+#[test]
+fn set_pallet_paused_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pairs();
+
+        assert_noop!(
+            DexPallet::set_pallet_paused(Origin::signed(ALICE), true),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(DexPallet::set_pallet_paused(Origin::root(), true));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == Event::Zenlink(crate::Event::PalletPauseChanged(true))));
+
+        assert_noop!(
+            DexPallet::add_liquidity(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                DOT_UNIT,
+                BTC_UNIT,
+                0,
+                0,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::Paused
+        );
+        assert_noop!(
+            DexPallet::transfer(Origin::signed(ALICE), DOT_ASSET_ID, BOB, DOT_UNIT),
+            Error::<Test>::Paused
+        );
+        assert_noop!(
+            DexPallet::create_pair(Origin::signed(ALICE), BTC_ASSET_ID, ETH_ASSET_ID),
+            Error::<Test>::Paused
+        );
+
+        // The read-only functions keep working.
+        assert_eq!(
+            DexPallet::get_reserves(DOT_ASSET_ID, BTC_ASSET_ID),
+            (1000 * DOT_UNIT, 1000 * BTC_UNIT)
+        );
+        assert!(DexPallet::get_amount_out_by_path(DOT_UNIT, &[DOT_ASSET_ID, BTC_ASSET_ID]).is_ok());
+
+        assert_ok!(DexPallet::set_pallet_paused(Origin::root(), false));
+        assert_ok!(DexPallet::transfer(Origin::signed(ALICE), DOT_ASSET_ID, BOB, DOT_UNIT));
+        assert_eq!(<Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &BOB), DOT_UNIT);
+    });
+}
+
+#[test]
+fn set_pair_paused_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pairs();
+
+        assert_noop!(
+            DexPallet::set_pair_paused(Origin::root(), BTC_ASSET_ID, ETH_ASSET_ID, true),
+            Error::<Test>::PairNotExists
+        );
+        assert_noop!(
+            DexPallet::set_pair_paused(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID, true),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(DexPallet::set_pair_paused(Origin::root(), BTC_ASSET_ID, DOT_ASSET_ID, true));
+        assert!(DexPallet::is_pair_paused(DOT_ASSET_ID, BTC_ASSET_ID));
+        assert!(System::events().iter().any(|record| record.event
+            == Event::Zenlink(crate::Event::PairPauseChanged(DOT_ASSET_ID, BTC_ASSET_ID, true))));
+
+        assert_noop!(
+            DexPallet::inner_swap_exact_tokens_for_tokens(
+                &ALICE,
+                DOT_UNIT,
+                0,
+                &[DOT_ASSET_ID, BTC_ASSET_ID],
+                &BOB
+            ),
+            Error::<Test>::Paused
+        );
+        assert_noop!(
+            DexPallet::inner_add_liquidity(
+                &ALICE,
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                DOT_UNIT,
+                BTC_UNIT,
+                0,
+                0
+            ),
+            Error::<Test>::Paused
+        );
+        let liquidity = DexPallet::lp_ledger(((DOT_ASSET_ID, BTC_ASSET_ID), ALICE));
+        assert_noop!(
+            DexPallet::inner_remove_liquidity(
+                &ALICE,
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                liquidity,
+                0,
+                0,
+                &ALICE
+            ),
+            Error::<Test>::Paused
+        );
+        assert_eq!(
+            DexPallet::get_swap_paths(BTC_ASSET_ID, ETH_ASSET_ID),
+            Vec::<Vec<AssetId>>::new()
+        );
+
+        // The other pairs are not paused.
+        assert_ok!(DexPallet::inner_swap_exact_tokens_for_tokens(
+            &ALICE,
+            DOT_UNIT,
+            0,
+            &[DOT_ASSET_ID, ETH_ASSET_ID],
+            &BOB
+        ));
+
+        assert_ok!(DexPallet::set_pair_paused(Origin::root(), DOT_ASSET_ID, BTC_ASSET_ID, false));
+        assert!(!DexPallet::is_pair_paused(DOT_ASSET_ID, BTC_ASSET_ID));
+        assert_ok!(DexPallet::inner_swap_exact_tokens_for_tokens(
+            &ALICE,
+            DOT_UNIT,
+            0,
+            &[DOT_ASSET_ID, BTC_ASSET_ID],
+            &BOB
+        ));
+    });
+}
+
+#[test]
+fn set_call_paused_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pairs();
+
+        assert_noop!(
+            DexPallet::set_call_paused(Origin::signed(ALICE), b"add_liquidity".to_vec(), true),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(DexPallet::set_call_paused(Origin::root(), b"add_liquidity".to_vec(), true));
+        assert!(DexPallet::is_call_paused(b"add_liquidity"));
+        assert!(System::events().iter().any(|record| record.event
+            == Event::Zenlink(crate::Event::CallPauseChanged(b"add_liquidity".to_vec(), true))));
+
+        assert_noop!(
+            DexPallet::add_liquidity(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                DOT_UNIT,
+                BTC_UNIT,
+                0,
+                0,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::Paused
+        );
+
+        // The other calls are not paused.
+        let liquidity = DexPallet::lp_ledger(((DOT_ASSET_ID, BTC_ASSET_ID), ALICE));
+        assert_ok!(DexPallet::remove_liquidity(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            liquidity / 2,
+            0,
+            0,
+            ALICE,
+            Deadline::BlockNumber(100)
+        ));
+
+        assert_ok!(DexPallet::set_call_paused(Origin::root(), b"add_liquidity".to_vec(), false));
+        assert!(!DexPallet::is_call_paused(b"add_liquidity"));
+        assert_ok!(DexPallet::add_liquidity(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            DOT_UNIT,
+            BTC_UNIT,
+            0,
+            0,
+            Deadline::BlockNumber(100)
+        ));
+    });
+}
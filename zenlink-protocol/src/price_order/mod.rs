@@ -0,0 +1,330 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+//! # Price Order Module
+//!
+//! ## Overview
+//!
+//! Limit and stop-loss orders, which sell an escrowed amount of an asset through a trading
+//! pair when the spot price of the pair crosses the trigger price. A triggered order is filled
+//! by a keeper for the keeper reward, or by `on_initialize` of the block after a swap through
+//! the pair moves the price, which refunds the keeper reward to the owner. At most
+//! `MaxPriceOrderChecksPerBlock` orders are checked in a block, the rest are checked later.
+//! A trading pair holds at most `MAX_PRICE_ORDERS_OF_PAIR` orders and an owner at most
+//! `MAX_PRICE_ORDERS_OF_OWNER` orders, which expire within `MAX_PRICE_ORDER_LIFETIME` blocks.
+//! `PriceOrderDeposit` is reserved from the owner of every open order.
+//!
+//! The orders are triggered by the spot price rather than the TWAP, which a single swap can
+//! move across the trigger price. So a limit order is filled at no less than the trigger price
+//! after the slippage of the fill, and a stop-loss order at no less than its minimum amount
+//! out. The orders are filled through the pair which the price is read from, even if a pool
+//! of the two assets is the hop of them.
+
+use super::*;
+
+use frame_support::storage::{with_transaction, TransactionOutcome};
+
+#[cfg(test)]
+mod tests;
+
+/// The weight of a check of a price order in `on_initialize`, including the fill.
+pub const PRICE_ORDER_CHECK_WEIGHT: Weight = 1_000_000;
+/// The max number of the open price orders of a trading pair.
+pub const MAX_PRICE_ORDERS_OF_PAIR: usize = 64;
+/// The max number of the open price orders of an owner.
+pub const MAX_PRICE_ORDERS_OF_OWNER: u32 = 16;
+/// The max number of blocks from the placement to the expiry of a price order.
+pub const MAX_PRICE_ORDER_LIFETIME: u32 = 100_800;
+
+impl<T: Config> Pallet<T> {
+    /// The account ID which holds the escrowed assets of the price orders.
+    pub fn price_order_account_id() -> T::AccountId {
+        T::PalletId::get().into_sub_account(b"order")
+    }
+
+    /// The spot price of `asset_in` quoted in `asset_out` of their trading pair, in `BONE`.
+    pub fn get_spot_price(asset_in: AssetId, asset_out: AssetId) -> Option<u128> {
+        let (reserve_in, reserve_out) = Self::get_reserves(asset_in, asset_out);
+        if reserve_in.is_zero() || reserve_out.is_zero() {
+            return None;
+        }
+
+        (U256::from(reserve_out) * U256::from(BONE) / U256::from(reserve_in)).try_into().ok()
+    }
+
+    /// Whether the spot price of the pair crosses the trigger price of the order. The spot
+    /// price moves with every swap, so a swap can trigger the order and be swapped back
+    /// after the fill. The fill is bounded by the trigger price of a limit order and the
+    /// minimum amount out of a stop-loss order.
+    pub fn is_price_order_triggered(order: &PriceOrder<T::AccountId, T::BlockNumber>) -> bool {
+        Self::get_spot_price(order.asset_in, order.asset_out).map_or(false, |price| {
+            match order.kind {
+                PriceOrderKind::Limit => price >= order.trigger_price,
+                PriceOrderKind::StopLoss => price <= order.trigger_price,
+            }
+        })
+    }
+
+    pub fn inner_place_price_order(
+        order: PriceOrder<T::AccountId, T::BlockNumber>,
+    ) -> Result<u64, DispatchError> {
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(
+            !order.amount_in.is_zero()
+                && !order.trigger_price.is_zero()
+                && order.expiry >= now
+                && order.expiry <= now.saturating_add(MAX_PRICE_ORDER_LIFETIME.into()),
+            Error::<T>::InvalidPriceOrder
+        );
+        let pair = Self::sort_asset_id(order.asset_in, order.asset_out);
+        ensure!(order.asset_in != order.asset_out, Error::<T>::InvalidPath);
+        ensure!(Self::lp_metadata(pair).is_some(), Error::<T>::PairNotExists);
+        ensure!(
+            PriceOrdersOfPair::<T>::decode_len(pair).unwrap_or(0) < MAX_PRICE_ORDERS_OF_PAIR,
+            Error::<T>::TooManyPriceOrders
+        );
+        ensure!(
+            Self::price_order_count(&order.owner) < MAX_PRICE_ORDERS_OF_OWNER,
+            Error::<T>::TooManyPriceOrders
+        );
+
+        let escrow =
+            order.amount_in.checked_add(order.keeper_reward).ok_or(Error::<T>::Overflow)?;
+        T::MultiAssetsHandler::transfer(
+            order.asset_in,
+            &order.owner,
+            &Self::price_order_account_id(),
+            escrow,
+        )?;
+
+        let order_id = Self::next_price_order_id();
+        NextPriceOrderId::<T>::put(order_id.checked_add(1).ok_or(Error::<T>::Overflow)?);
+        PriceOrdersOfPair::<T>::mutate(pair, |order_ids| order_ids.push(order_id));
+        PriceOrderCount::<T>::mutate(&order.owner, |count| *count += 1);
+
+        let deposit = T::PriceOrderDeposit::get();
+        if !deposit.is_zero() {
+            T::Currency::reserve(&order.owner, deposit)?;
+            PriceOrderDeposits::<T>::insert(order_id, deposit);
+        }
+
+        Self::deposit_event(Event::PriceOrderPlaced(
+            order.owner.clone(),
+            order_id,
+            order.asset_in,
+            order.asset_out,
+            order.amount_in,
+        ));
+        PriceOrders::<T>::insert(order_id, order);
+
+        Ok(order_id)
+    }
+
+    pub fn inner_fill_price_order(keeper: &T::AccountId, order_id: u64) -> DispatchResult {
+        let order = Self::price_order(order_id).ok_or(Error::<T>::PriceOrderNotExists)?;
+        ensure!(
+            order.expiry >= frame_system::Pallet::<T>::block_number(),
+            Error::<T>::PriceOrderExpired
+        );
+        ensure!(Self::is_price_order_triggered(&order), Error::<T>::PriceOrderNotTriggered);
+
+        Self::fill_price_order_with(order_id, &order, Some(keeper))
+    }
+
+    pub fn inner_cancel_price_order(who: &T::AccountId, order_id: u64) -> DispatchResult {
+        let order = Self::price_order(order_id).ok_or(Error::<T>::PriceOrderNotExists)?;
+        let expired = order.expiry < frame_system::Pallet::<T>::block_number();
+        ensure!(order.owner == *who || expired, Error::<T>::NotPriceOrderOwner);
+
+        Self::refund_price_order(order_id, &order)?;
+
+        if expired {
+            Self::deposit_event(Event::PriceOrderExpired(order.owner, order_id));
+        } else {
+            Self::deposit_event(Event::PriceOrderCancelled(order.owner, order_id));
+        }
+
+        Ok(())
+    }
+
+    /// Note the swap through the pair, so the price orders through it are checked in the next
+    /// `on_initialize`.
+    pub(crate) fn note_pair_swapped(asset_0: AssetId, asset_1: AssetId) {
+        let pair = Self::sort_asset_id(asset_0, asset_1);
+        if !PriceOrdersOfPair::<T>::contains_key(pair) {
+            return;
+        }
+
+        SwappedPairs::<T>::mutate(|pairs| {
+            if !pairs.contains(&pair) {
+                pairs.push(pair);
+            }
+        });
+    }
+
+    /// Check the price orders through the pairs swapped, fill the triggered ones and remove
+    /// the expired ones. The orders of a pair over `MaxPriceOrderChecksPerBlock` are checked
+    /// in the next blocks, behind the ones which are checked.
+    pub(crate) fn fill_triggered_price_orders(now: T::BlockNumber) -> Weight {
+        let pairs = SwappedPairs::<T>::take();
+        let mut checks_left = T::MaxPriceOrderChecksPerBlock::get() as usize;
+        let mut checks: usize = 0;
+        let mut reads: Weight = 1;
+        let mut writes: Weight = 1;
+        let mut unchecked_pairs = Vec::new();
+
+        for pair in pairs {
+            if checks_left == 0 {
+                unchecked_pairs.push(pair);
+                continue;
+            }
+
+            // At most `MAX_PRICE_ORDERS_OF_PAIR` orders are read and sorted.
+            reads = reads.saturating_add(1);
+            let order_ids = Self::price_orders_of_pair(pair);
+            let checked_ids = &order_ids[..order_ids.len().min(checks_left)];
+            for order_id in checked_ids.iter() {
+                Self::check_price_order(*order_id, now);
+            }
+            checks_left -= checked_ids.len();
+            checks += checked_ids.len();
+
+            if checked_ids.len() < order_ids.len() {
+                writes = writes.saturating_add(1);
+                PriceOrdersOfPair::<T>::mutate(pair, |order_ids| {
+                    order_ids.sort_by_key(|order_id| checked_ids.contains(order_id))
+                });
+                unchecked_pairs.push(pair);
+            }
+        }
+
+        // The fills may have noted the pairs again.
+        SwappedPairs::<T>::mutate(|pairs| {
+            for pair in unchecked_pairs {
+                if !pairs.contains(&pair) {
+                    pairs.push(pair);
+                }
+            }
+        });
+
+        T::DbWeight::get()
+            .reads_writes(reads.saturating_add(1), writes.saturating_add(1))
+            .saturating_add(PRICE_ORDER_CHECK_WEIGHT.saturating_mul(checks as Weight))
+    }
+
+    fn check_price_order(order_id: u64, now: T::BlockNumber) {
+        let order = match Self::price_order(order_id) {
+            Some(order) => order,
+            None => return,
+        };
+
+        if order.expiry < now {
+            if Self::refund_price_order(order_id, &order).is_ok() {
+                Self::deposit_event(Event::PriceOrderExpired(order.owner, order_id));
+            }
+        } else if Self::is_price_order_triggered(&order) {
+            // The order is left open if the fill fails, such as below the minimum amount out.
+            let _ =
+                with_transaction(|| match Self::fill_price_order_with(order_id, &order, None) {
+                    Ok(()) => TransactionOutcome::Commit(Ok(())),
+                    Err(error) => TransactionOutcome::Rollback(Err(error)),
+                });
+        }
+    }
+
+    /// Fill the order, the keeper reward is paid to the keeper if any, or refunded to the
+    /// owner.
+    fn fill_price_order_with(
+        order_id: u64,
+        order: &PriceOrder<T::AccountId, T::BlockNumber>,
+        keeper: Option<&T::AccountId>,
+    ) -> DispatchResult {
+        Self::ensure_not_paused(b"fill_price_order")?;
+
+        // A limit order sells at no less than the trigger price after the slippage of the fill.
+        let amount_out_min = match order.kind {
+            PriceOrderKind::Limit => {
+                let amount_out_at_price: AssetBalance = (U256::from(order.amount_in)
+                    * U256::from(order.trigger_price)
+                    / U256::from(BONE))
+                .try_into()
+                .map_err(|_| Error::<T>::Overflow)?;
+                order.amount_out_min.max(amount_out_at_price)
+            }
+            PriceOrderKind::StopLoss => order.amount_out_min,
+        };
+
+        // The order is filled through the pair which its trigger price is read from.
+        let escrow_account = Self::price_order_account_id();
+        let amount_out = Self::swap_exact_through_pair(
+            &escrow_account,
+            order.asset_in,
+            order.asset_out,
+            order.amount_in,
+            amount_out_min,
+            &order.owner,
+        )?;
+
+        if !order.keeper_reward.is_zero() {
+            let reward_recipient = keeper.unwrap_or(&order.owner);
+            T::MultiAssetsHandler::transfer(
+                order.asset_in,
+                &escrow_account,
+                reward_recipient,
+                order.keeper_reward,
+            )?;
+        }
+        Self::remove_price_order(order_id, order);
+
+        Self::deposit_event(Event::PriceOrderFilled(
+            order.owner.clone(),
+            order_id,
+            order.amount_in,
+            amount_out,
+        ));
+        if let Some(keeper) = keeper {
+            Self::deposit_event(Event::PriceOrderKeeperRewarded(
+                keeper.clone(),
+                order_id,
+                order.keeper_reward,
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn refund_price_order(
+        order_id: u64,
+        order: &PriceOrder<T::AccountId, T::BlockNumber>,
+    ) -> DispatchResult {
+        T::MultiAssetsHandler::transfer(
+            order.asset_in,
+            &Self::price_order_account_id(),
+            &order.owner,
+            order.amount_in.saturating_add(order.keeper_reward),
+        )?;
+        Self::remove_price_order(order_id, order);
+
+        Ok(())
+    }
+
+    fn remove_price_order(order_id: u64, order: &PriceOrder<T::AccountId, T::BlockNumber>) {
+        let pair = Self::sort_asset_id(order.asset_in, order.asset_out);
+        PriceOrdersOfPair::<T>::mutate_exists(pair, |order_ids| {
+            if let Some(ids) = order_ids {
+                ids.retain(|id| *id != order_id);
+                if ids.is_empty() {
+                    *order_ids = None;
+                }
+            }
+        });
+        PriceOrders::<T>::remove(order_id);
+
+        PriceOrderCount::<T>::mutate_exists(&order.owner, |count| {
+            *count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0)
+        });
+        if let Some(deposit) = PriceOrderDeposits::<T>::take(order_id) {
+            T::Currency::unreserve(&order.owner, deposit);
+        }
+    }
+}
@@ -0,0 +1,473 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{Get, Hooks},
+};
+
+use super::{
+    AssetId, Error, PriceOrderKind, MAX_PRICE_ORDERS_OF_OWNER, MAX_PRICE_ORDERS_OF_PAIR,
+    MAX_PRICE_ORDER_LIFETIME, PRICE_ORDER_CHECK_WEIGHT,
+};
+use crate::mock::*;
+
+const DOT_ASSET_ID: AssetId = AssetId { chain_id: 200, asset_type: LOCAL, asset_index: 2 };
+
+const BTC_ASSET_ID: AssetId = AssetId { chain_id: 300, asset_type: RESERVED, asset_index: 3 };
+
+const ALICE: u128 = 1;
+const BOB: u128 = 2;
+const CHARLIE: u128 = 3;
+
+const DOT_UNIT: u128 = 1000_000_000_000_000;
+const BTC_UNIT: u128 = 1000_000_00;
+
+/// The spot price of DOT quoted in BTC of the pair before any swap, in `BONE`.
+const DOT_PRICE: u128 = 100_000_000_000;
+
+fn create_pair() {
+    assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, 10000 * DOT_UNIT));
+    assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, 10000 * BTC_UNIT));
+    assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &BOB, 10000 * DOT_UNIT));
+    assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &BOB, 10000 * BTC_UNIT));
+    assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+    assert_ok!(DexPallet::inner_add_liquidity(
+        &ALICE,
+        DOT_ASSET_ID,
+        BTC_ASSET_ID,
+        1000 * DOT_UNIT,
+        1000 * BTC_UNIT,
+        0,
+        0
+    ));
+}
+
+fn place_order(
+    kind: PriceOrderKind,
+    amount_in: u128,
+    trigger_price: u128,
+    amount_out_min: u128,
+    keeper_reward: u128,
+    expiry: u64,
+) {
+    assert_ok!(DexPallet::place_price_order(
+        Origin::signed(ALICE),
+        kind,
+        DOT_ASSET_ID,
+        BTC_ASSET_ID,
+        amount_in,
+        trigger_price,
+        amount_out_min,
+        keeper_reward,
+        expiry
+    ));
+}
+
+#[test]
+fn limit_order_should_be_filled_by_hook() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pair();
+        assert_eq!(DexPallet::get_spot_price(DOT_ASSET_ID, BTC_ASSET_ID), Some(DOT_PRICE));
+
+        let dot_balance = balance_of(DOT_ASSET_ID, &ALICE);
+        let btc_balance = balance_of(BTC_ASSET_ID, &ALICE);
+        place_order(
+            PriceOrderKind::Limit,
+            10 * DOT_UNIT,
+            DOT_PRICE * 11 / 10,
+            11 * BTC_UNIT,
+            DOT_UNIT / 10,
+            100,
+        );
+        assert!(has_event(crate::Event::PriceOrderPlaced(
+            ALICE,
+            0,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            10 * DOT_UNIT
+        )));
+        assert_eq!(
+            balance_of(DOT_ASSET_ID, &DexPallet::price_order_account_id()),
+            10 * DOT_UNIT + DOT_UNIT / 10
+        );
+        assert_eq!(DexPallet::price_orders_of_pair((DOT_ASSET_ID, BTC_ASSET_ID)), vec![0]);
+
+        assert_noop!(
+            DexPallet::fill_price_order(Origin::signed(CHARLIE), 0),
+            Error::<Test>::PriceOrderNotTriggered
+        );
+        assert_eq!(DexPallet::swapped_pairs(), vec![]);
+
+        // Buying DOT raises the price to 120966999999.
+        assert_ok!(DexPallet::inner_swap_exact_tokens_for_tokens(
+            &BOB,
+            100 * BTC_UNIT,
+            0,
+            &[BTC_ASSET_ID, DOT_ASSET_ID],
+            &BOB
+        ));
+        assert_eq!(DexPallet::swapped_pairs(), vec![(DOT_ASSET_ID, BTC_ASSET_ID)]);
+
+        System::set_block_number(2);
+        assert_eq!(DexPallet::on_initialize(2), PRICE_ORDER_CHECK_WEIGHT);
+        assert!(has_event(crate::Event::PriceOrderFilled(ALICE, 0, 10 * DOT_UNIT, 1192961351)));
+        assert_eq!(DexPallet::price_order(0), None);
+        assert_eq!(DexPallet::price_orders_of_pair((DOT_ASSET_ID, BTC_ASSET_ID)), vec![]);
+        // The keeper reward is refunded without a keeper.
+        assert_eq!(balance_of(DOT_ASSET_ID, &ALICE), dot_balance - 10 * DOT_UNIT);
+        assert_eq!(balance_of(BTC_ASSET_ID, &ALICE), btc_balance + 1192961351);
+        assert_eq!(balance_of(DOT_ASSET_ID, &DexPallet::price_order_account_id()), 0);
+    });
+}
+
+#[test]
+fn stop_loss_order_should_be_filled_by_keeper() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pair();
+
+        let btc_balance = balance_of(BTC_ASSET_ID, &ALICE);
+        place_order(
+            PriceOrderKind::StopLoss,
+            10 * DOT_UNIT,
+            DOT_PRICE * 9 / 10,
+            8 * BTC_UNIT,
+            DOT_UNIT / 10,
+            100,
+        );
+        assert_noop!(
+            DexPallet::fill_price_order(Origin::signed(CHARLIE), 0),
+            Error::<Test>::PriceOrderNotTriggered
+        );
+
+        // Selling DOT drops the price to 82667173692.
+        assert_ok!(DexPallet::inner_swap_exact_tokens_for_tokens(
+            &BOB,
+            100 * DOT_UNIT,
+            0,
+            &[DOT_ASSET_ID, BTC_ASSET_ID],
+            &BOB
+        ));
+
+        assert_ok!(DexPallet::fill_price_order(Origin::signed(CHARLIE), 0));
+        assert!(has_event(crate::Event::PriceOrderFilled(ALICE, 0, 10 * DOT_UNIT, 816788646)));
+        assert!(has_event(crate::Event::PriceOrderKeeperRewarded(CHARLIE, 0, DOT_UNIT / 10)));
+        assert_eq!(balance_of(DOT_ASSET_ID, &CHARLIE), DOT_UNIT / 10);
+        assert_eq!(balance_of(BTC_ASSET_ID, &ALICE), btc_balance + 816788646);
+
+        assert_noop!(
+            DexPallet::fill_price_order(Origin::signed(CHARLIE), 0),
+            Error::<Test>::PriceOrderNotExists
+        );
+    });
+}
+
+#[test]
+fn cancel_and_expire_price_order_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pair();
+
+        let dot_balance = balance_of(DOT_ASSET_ID, &ALICE);
+        place_order(PriceOrderKind::Limit, 10 * DOT_UNIT, DOT_PRICE, 0, DOT_UNIT / 10, 10);
+        assert_noop!(
+            DexPallet::cancel_price_order(Origin::signed(BOB), 0),
+            Error::<Test>::NotPriceOrderOwner
+        );
+        assert_ok!(DexPallet::cancel_price_order(Origin::signed(ALICE), 0));
+        assert!(has_event(crate::Event::PriceOrderCancelled(ALICE, 0)));
+        assert_eq!(balance_of(DOT_ASSET_ID, &ALICE), dot_balance);
+
+        // Anyone can remove the order after the expiry.
+        place_order(PriceOrderKind::Limit, 10 * DOT_UNIT, DOT_PRICE, 0, DOT_UNIT / 10, 10);
+        System::set_block_number(11);
+        assert_noop!(
+            DexPallet::fill_price_order(Origin::signed(CHARLIE), 1),
+            Error::<Test>::PriceOrderExpired
+        );
+        assert_ok!(DexPallet::cancel_price_order(Origin::signed(BOB), 1));
+        assert!(has_event(crate::Event::PriceOrderExpired(ALICE, 1)));
+        assert_eq!(balance_of(DOT_ASSET_ID, &ALICE), dot_balance);
+
+        assert_noop!(
+            DexPallet::place_price_order(
+                Origin::signed(ALICE),
+                PriceOrderKind::Limit,
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                10 * DOT_UNIT,
+                DOT_PRICE,
+                0,
+                0,
+                10
+            ),
+            Error::<Test>::InvalidPriceOrder
+        );
+
+        // The hook removes the expired order of a swapped pair.
+        place_order(PriceOrderKind::StopLoss, 10 * DOT_UNIT, DOT_PRICE / 2, 0, 0, 12);
+        assert_ok!(DexPallet::inner_swap_exact_tokens_for_tokens(
+            &BOB,
+            DOT_UNIT,
+            0,
+            &[DOT_ASSET_ID, BTC_ASSET_ID],
+            &BOB
+        ));
+        System::set_block_number(13);
+        DexPallet::on_initialize(13);
+        assert!(has_event(crate::Event::PriceOrderExpired(ALICE, 2)));
+        assert_eq!(DexPallet::price_order(2), None);
+        assert_eq!(balance_of(DOT_ASSET_ID, &ALICE), dot_balance);
+    });
+}
+
+#[test]
+fn price_order_checks_should_be_bounded_per_block() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pair();
+
+        for _ in 0..5 {
+            place_order(PriceOrderKind::Limit, DOT_UNIT, DOT_PRICE * 11 / 10, 0, 0, 100);
+        }
+        assert_ok!(DexPallet::inner_swap_exact_tokens_for_tokens(
+            &BOB,
+            100 * BTC_UNIT,
+            0,
+            &[BTC_ASSET_ID, DOT_ASSET_ID],
+            &BOB
+        ));
+
+        System::set_block_number(2);
+        assert_eq!(DexPallet::on_initialize(2), 4 * PRICE_ORDER_CHECK_WEIGHT);
+        for order_id in 0..4 {
+            assert_eq!(DexPallet::price_order(order_id), None);
+        }
+        assert!(DexPallet::price_order(4).is_some());
+        assert_eq!(DexPallet::swapped_pairs(), vec![(DOT_ASSET_ID, BTC_ASSET_ID)]);
+
+        System::set_block_number(3);
+        assert_eq!(DexPallet::on_initialize(3), PRICE_ORDER_CHECK_WEIGHT);
+        assert_eq!(DexPallet::price_order(4), None);
+    });
+}
+
+#[test]
+fn price_orders_should_be_bounded_per_pair() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pair();
+
+        let max_expiry = 1 + MAX_PRICE_ORDER_LIFETIME as u64;
+        assert_noop!(
+            DexPallet::place_price_order(
+                Origin::signed(ALICE),
+                PriceOrderKind::Limit,
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                DOT_UNIT,
+                DOT_PRICE,
+                0,
+                0,
+                max_expiry + 1
+            ),
+            Error::<Test>::InvalidPriceOrder
+        );
+
+        for _ in 0..MAX_PRICE_ORDERS_OF_OWNER {
+            place_order(PriceOrderKind::Limit, DOT_UNIT, DOT_PRICE * 2, 0, 0, max_expiry);
+        }
+        assert_noop!(
+            DexPallet::place_price_order(
+                Origin::signed(ALICE),
+                PriceOrderKind::Limit,
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                DOT_UNIT,
+                DOT_PRICE * 2,
+                0,
+                0,
+                max_expiry
+            ),
+            Error::<Test>::TooManyPriceOrders
+        );
+
+        // The other owners fill up the orders of the pair.
+        let owners = MAX_PRICE_ORDERS_OF_PAIR as u128 / MAX_PRICE_ORDERS_OF_OWNER as u128;
+        for owner in 2..=owners {
+            assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &owner, 100 * DOT_UNIT));
+            assert_ok!(Balances::transfer(Origin::signed(ALICE), owner, 100));
+            for _ in 0..MAX_PRICE_ORDERS_OF_OWNER {
+                assert_ok!(DexPallet::place_price_order(
+                    Origin::signed(owner),
+                    PriceOrderKind::Limit,
+                    DOT_ASSET_ID,
+                    BTC_ASSET_ID,
+                    DOT_UNIT,
+                    DOT_PRICE * 2,
+                    0,
+                    0,
+                    max_expiry
+                ));
+            }
+        }
+        assert_noop!(
+            DexPallet::place_price_order(
+                Origin::signed(ALICE),
+                PriceOrderKind::StopLoss,
+                BTC_ASSET_ID,
+                DOT_ASSET_ID,
+                BTC_UNIT,
+                DOT_PRICE,
+                0,
+                0,
+                max_expiry
+            ),
+            Error::<Test>::TooManyPriceOrders
+        );
+
+        // An order of the pair can be placed again after one is cancelled.
+        assert_ok!(DexPallet::cancel_price_order(Origin::signed(ALICE), 0));
+        assert_eq!(DexPallet::price_order_count(ALICE), MAX_PRICE_ORDERS_OF_OWNER - 1);
+        place_order(PriceOrderKind::Limit, DOT_UNIT, DOT_PRICE * 2, 0, 0, max_expiry);
+        assert_eq!(
+            DexPallet::price_orders_of_pair((DOT_ASSET_ID, BTC_ASSET_ID)).len(),
+            MAX_PRICE_ORDERS_OF_PAIR
+        );
+    });
+}
+
+#[test]
+fn limit_order_should_not_be_filled_below_trigger_price() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pair();
+
+        // The spot price is at the trigger price, but selling 100 DOT only gets about 90.66 BTC.
+        place_order(PriceOrderKind::Limit, 100 * DOT_UNIT, DOT_PRICE, 0, 0, 100);
+        assert!(DexPallet::is_price_order_triggered(&DexPallet::price_order(0).unwrap()));
+        assert_noop!(
+            DexPallet::fill_price_order(Origin::signed(CHARLIE), 0),
+            Error::<Test>::InsufficientTargetAmount
+        );
+
+        // Selling 1 DOT gets about 0.997 BTC, above the trigger price of 0.99 BTC.
+        let btc_balance = balance_of(BTC_ASSET_ID, &ALICE);
+        place_order(PriceOrderKind::Limit, DOT_UNIT, DOT_PRICE * 99 / 100, 0, 0, 100);
+        assert_ok!(DexPallet::fill_price_order(Origin::signed(CHARLIE), 1));
+        assert!(balance_of(BTC_ASSET_ID, &ALICE) - btc_balance >= BTC_UNIT * 99 / 100);
+        assert!(DexPallet::price_order(0).is_some());
+    });
+}
+
+#[test]
+fn price_order_deposit_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pair();
+
+        place_order(PriceOrderKind::Limit, 10 * DOT_UNIT, DOT_PRICE * 2, 0, 0, 10);
+        place_order(PriceOrderKind::StopLoss, 10 * DOT_UNIT, DOT_PRICE / 2, 0, 0, 10);
+        place_order(PriceOrderKind::Limit, DOT_UNIT, DOT_PRICE * 99 / 100, 0, 0, 10);
+        assert_eq!(Balances::reserved_balance(ALICE), 3 * PriceOrderDeposit::get());
+        assert_eq!(DexPallet::price_order_deposit(0), Some(PriceOrderDeposit::get()));
+        assert_eq!(DexPallet::price_order_count(ALICE), 3);
+
+        // The deposit is returned as an order is cancelled, filled or removed after the expiry.
+        assert_ok!(DexPallet::cancel_price_order(Origin::signed(ALICE), 0));
+        assert_eq!(Balances::reserved_balance(ALICE), 2 * PriceOrderDeposit::get());
+        assert_eq!(DexPallet::price_order_deposit(0), None);
+
+        assert_ok!(DexPallet::fill_price_order(Origin::signed(CHARLIE), 2));
+        assert_eq!(Balances::reserved_balance(ALICE), PriceOrderDeposit::get());
+
+        System::set_block_number(11);
+        assert_ok!(DexPallet::cancel_price_order(Origin::signed(BOB), 1));
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(DexPallet::price_order_count(ALICE), 0);
+    });
+}
+
+#[test]
+fn stop_loss_order_triggered_by_a_swap_should_be_bounded() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pair();
+
+        let btc_balance = balance_of(BTC_ASSET_ID, &ALICE);
+        place_order(
+            PriceOrderKind::StopLoss,
+            10 * DOT_UNIT,
+            DOT_PRICE * 9 / 10,
+            9 * BTC_UNIT,
+            0,
+            100,
+        );
+
+        // A swap drops the price to 82667173692 and triggers the order, which would be filled
+        // for about 8.17 BTC, below the minimum amount out.
+        assert_ok!(DexPallet::inner_swap_exact_tokens_for_tokens(
+            &BOB,
+            100 * DOT_UNIT,
+            0,
+            &[DOT_ASSET_ID, BTC_ASSET_ID],
+            &BOB
+        ));
+        assert!(DexPallet::is_price_order_triggered(&DexPallet::price_order(0).unwrap()));
+        assert_noop!(
+            DexPallet::fill_price_order(Origin::signed(CHARLIE), 0),
+            Error::<Test>::InsufficientTargetAmount
+        );
+        System::set_block_number(2);
+        DexPallet::on_initialize(2);
+        assert!(DexPallet::price_order(0).is_some());
+
+        // The swap back restores the price before the order is filled.
+        let bob_btc = balance_of(BTC_ASSET_ID, &BOB) - 10000 * BTC_UNIT;
+        assert_ok!(DexPallet::inner_swap_exact_tokens_for_tokens(
+            &BOB,
+            bob_btc,
+            0,
+            &[BTC_ASSET_ID, DOT_ASSET_ID],
+            &BOB
+        ));
+        assert!(!DexPallet::is_price_order_triggered(&DexPallet::price_order(0).unwrap()));
+        assert_eq!(balance_of(BTC_ASSET_ID, &ALICE), btc_balance);
+    });
+}
+
+#[test]
+fn price_order_should_be_filled_through_pair() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pair();
+        assert_ok!(DexPallet::create_stable_pool(
+            Origin::root(),
+            vec![DOT_ASSET_ID, BTC_ASSET_ID],
+            vec![1, 1],
+            100,
+            4,
+            10000
+        ));
+        assert_ok!(DexPallet::add_stable_liquidity(
+            Origin::signed(BOB),
+            0,
+            vec![BTC_UNIT, BTC_UNIT],
+            0,
+            Deadline::BlockNumber(100)
+        ));
+
+        place_order(PriceOrderKind::Limit, DOT_UNIT, DOT_PRICE * 99 / 100, 0, 0, 100);
+        assert_ok!(DexPallet::fill_price_order(Origin::signed(CHARLIE), 0));
+        assert_eq!(
+            DexPallet::get_reserves(DOT_ASSET_ID, BTC_ASSET_ID).0,
+            1000 * DOT_UNIT + DOT_UNIT
+        );
+        assert_eq!(
+            balance_of(DOT_ASSET_ID, &DexPallet::stable_pool(0).unwrap().pool_account),
+            BTC_UNIT
+        );
+    });
+}
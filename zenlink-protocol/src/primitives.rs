@@ -36,3 +36,262 @@ impl AssetId {
         self.chain_id != self_chain_id
     }
 }
+
+/// The status of a trading pair, which is changed by `ControlOrigin`.
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug)]
+pub enum PairStatus {
+    /// The pair is waiting for its initial liquidity, no swaps or adds.
+    Bootstrap,
+    /// The pair is open for swaps and liquidity.
+    Enabled,
+    /// The pair is paused, the liquidity can only be removed.
+    Disabled,
+    /// The pair is removed for good, the liquidity can only be removed.
+    Delisted,
+}
+
+impl Default for PairStatus {
+    fn default() -> Self {
+        PairStatus::Enabled
+    }
+}
+
+/// The cutoff of a transaction, either a block height or a timestamp of `pallet_timestamp`.
+/// The transaction fails once the current block reaches it.
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug)]
+pub enum Deadline<BlockNumber, Moment> {
+    BlockNumber(BlockNumber),
+    Timestamp(Moment),
+}
+
+/// The amount of an asset which an action of the router batch takes.
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug)]
+pub enum RouterAmount {
+    /// The exact amount, taken from the balance of the caller.
+    Amount(AssetBalance),
+    /// All the amount of the asset which the earlier actions of the batch output.
+    AllOutput,
+}
+
+/// An action of the router batch, the outputs of the actions go to the caller.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, RuntimeDebug)]
+pub enum RouterAction<AccountId> {
+    /// Sell `amount_in` of the first asset of the path for the last one.
+    SwapExactIn { path: Vec<AssetId>, amount_in: RouterAmount },
+    /// Buy `amount_out` of the last asset of the path with at most `amount_in_max` of the
+    /// first one.
+    SwapExactOut { path: Vec<AssetId>, amount_out: AssetBalance, amount_in_max: RouterAmount },
+    /// Add liquidity to the trading pair.
+    AddLiquidity {
+        asset_0: AssetId,
+        asset_1: AssetId,
+        amount_0_desired: RouterAmount,
+        amount_1_desired: RouterAmount,
+    },
+    /// Remove liquidity from the trading pair.
+    RemoveLiquidity { asset_0: AssetId, asset_1: AssetId, liquidity: RouterAmount },
+    /// Transfer an asset to the recipient.
+    Transfer { asset_id: AssetId, amount: RouterAmount, recipient: AccountId },
+}
+
+/// A swap order of `owner` signed off-chain, which any relayer can execute on behalf of the
+/// owner for a fee in the first asset of the path.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, RuntimeDebug)]
+pub struct SwapOrder<AccountId, BlockNumber, Moment> {
+    pub owner: AccountId,
+    pub path: Vec<AssetId>,
+    pub amount_in: AssetBalance,
+    pub amount_out_min: AssetBalance,
+    pub recipient: AccountId,
+    pub deadline: Deadline<BlockNumber, Moment>,
+    pub nonce: u64,
+    pub max_relayer_fee: AssetBalance,
+}
+
+/// A dollar-cost-averaging order of `owner`, which swaps `amount_per_execution` of the locked
+/// amount along the path every `period` blocks until no execution is left.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, RuntimeDebug)]
+pub struct DcaOrder<AccountId, BlockNumber> {
+    pub owner: AccountId,
+    pub path: Vec<AssetId>,
+    pub amount_per_execution: AssetBalance,
+    /// The minimum amount out of each execution.
+    pub amount_out_min: AssetBalance,
+    pub period: BlockNumber,
+    pub next_execution: BlockNumber,
+    pub executions_left: u32,
+    /// The amount locked for the executions left, the remainder of the failed executions is
+    /// refunded at the end.
+    pub locked: AssetBalance,
+}
+
+/// Whether a price order is filled when the price rises to the trigger, or falls to it.
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug)]
+pub enum PriceOrderKind {
+    /// Sell when the price is at or above the trigger price.
+    Limit,
+    /// Sell when the price is at or below the trigger price.
+    StopLoss,
+}
+
+/// An order of `owner` to sell `amount_in` of `asset_in` for `asset_out` through their trading
+/// pair when the spot price crosses the trigger price. The amount in and the keeper reward are
+/// escrowed until the order is filled, cancelled or expired.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, RuntimeDebug)]
+pub struct PriceOrder<AccountId, BlockNumber> {
+    pub owner: AccountId,
+    pub kind: PriceOrderKind,
+    pub asset_in: AssetId,
+    pub asset_out: AssetId,
+    pub amount_in: AssetBalance,
+    /// The spot price of `asset_in` quoted in `asset_out` which triggers the order, in `BONE`.
+    pub trigger_price: u128,
+    pub amount_out_min: AssetBalance,
+    /// The reward of `asset_in` to the keeper which fills the order.
+    pub keeper_reward: AssetBalance,
+    /// The last block which the order can be filled in.
+    pub expiry: BlockNumber,
+}
+
+/// A liquidity mining farm, which emits `reward_per_block` of the reward asset to the stakers
+/// of the liquidity of the pair from the start block to the end block.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, Default, RuntimeDebug)]
+pub struct Farm<BlockNumber> {
+    pub pair: (AssetId, AssetId),
+    pub reward_asset: AssetId,
+    pub reward_per_block: AssetBalance,
+    pub start_block: BlockNumber,
+    pub end_block: BlockNumber,
+    pub total_staked: AssetBalance,
+    /// The rewards accrued per staked liquidity, in `FARM_REWARD_PRECISION`.
+    pub acc_reward_per_share: U256,
+    /// The block which the rewards are accrued until.
+    pub last_reward_block: BlockNumber,
+}
+
+/// The liquidity staked by an account in a farm.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, Copy, Default, RuntimeDebug)]
+pub struct FarmStake {
+    pub amount: AssetBalance,
+    /// The rewards accrued to `amount` before it's staked or last claimed.
+    pub reward_debt: U256,
+    /// The rewards claimed but not paid, as the reward balance of the farm account ran short.
+    pub unpaid_rewards: AssetBalance,
+}
+
+/// The metadata of a trading pair.
+///
+/// The reserves are tracked in the order of the sorted pair, the assets held by the pair
+/// account beyond the reserves are not part of the liquidity until `sync`.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, Default, RuntimeDebug)]
+pub struct PairMetadata<AccountId> {
+    pub pair_account: AccountId,
+    pub total_supply: AssetBalance,
+    pub reserve_0: AssetBalance,
+    pub reserve_1: AssetBalance,
+    pub status: PairStatus,
+}
+
+/// The parameters of the bootstrap of a trading pair, the amounts are in the order of the
+/// sorted pair.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, Default, RuntimeDebug)]
+pub struct BootstrapParameter<BlockNumber> {
+    /// The amounts which the contributions must reach for the pair to open.
+    pub target_supply: (AssetBalance, AssetBalance),
+    /// The amounts contributed so far.
+    pub accumulated_supply: (AssetBalance, AssetBalance),
+    /// The contributions are accepted before this block.
+    pub end_block_number: BlockNumber,
+}
+
+/// The state of a stable pool.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, Default, RuntimeDebug)]
+pub struct StablePool<AccountId, BlockNumber> {
+    pub pool_account: AccountId,
+    pub assets: Vec<AssetId>,
+    /// The multipliers which normalize the balances of the assets to the same decimals.
+    pub precisions: Vec<AssetBalance>,
+    pub reserves: Vec<AssetBalance>,
+    pub total_supply: AssetBalance,
+    /// (FeeNumerator, FeeDenominator)
+    pub fee: (u32, u32),
+    /// The amplification coefficient moves from `initial_a` at `initial_a_block` to
+    /// `future_a` at `future_a_block`, both multiplied by `A_PRECISION`.
+    pub initial_a: u128,
+    pub future_a: u128,
+    pub initial_a_block: BlockNumber,
+    pub future_a_block: BlockNumber,
+}
+
+/// The state of a weighted pool.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, Default, RuntimeDebug)]
+pub struct WeightedPool<AccountId> {
+    pub pool_account: AccountId,
+    pub assets: Vec<AssetId>,
+    /// The weights of the assets normalized to sum up to `BONE`.
+    pub weights: Vec<u128>,
+    pub reserves: Vec<AssetBalance>,
+    pub total_supply: AssetBalance,
+    /// (FeeNumerator, FeeDenominator)
+    pub fee: (u32, u32),
+}
+
+/// The state of a liquidity bootstrapping pool of two assets, whose weights move linearly
+/// from the start weights to the end weights over the blocks of the sale. The reserves and
+/// weights are in the order of the sorted pair.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, Default, RuntimeDebug)]
+pub struct LbpPool<AccountId, BlockNumber> {
+    pub owner: AccountId,
+    pub pool_account: AccountId,
+    pub reserves: (AssetBalance, AssetBalance),
+    /// The normalized weight of `asset_0` at the start block, in `BONE`.
+    pub start_weight: u128,
+    /// The normalized weight of `asset_0` at the end block, in `BONE`.
+    pub end_weight: u128,
+    pub start_block: BlockNumber,
+    pub end_block: BlockNumber,
+    /// (FeeNumerator, FeeDenominator)
+    pub fee: (u32, u32),
+}
+
+/// The state of a concentrated liquidity pool, the price is of `asset_1` quoted in `asset_0`.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, Default, RuntimeDebug)]
+pub struct ConcentratedPool<AccountId> {
+    pub pool_account: AccountId,
+    /// (FeeNumerator, FeeDenominator)
+    pub fee: (u32, u32),
+    /// The ticks of the positions must be multiples of the tick spacing.
+    pub tick_spacing: u32,
+    /// The square root of the price in Q64.96.
+    pub sqrt_price: U256,
+    /// The greatest tick whose price is no more than the price.
+    pub tick: i32,
+    /// The liquidity of the positions in range of the price.
+    pub liquidity: u128,
+    /// The fees earned per unit of liquidity over the life of the pool in Q128.128.
+    pub fee_growth_global_0: U256,
+    pub fee_growth_global_1: U256,
+}
+
+/// The liquidity referencing a tick of a concentrated liquidity pool.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, Default, RuntimeDebug)]
+pub struct ConcentratedTick {
+    /// The total liquidity of the positions which use the tick as a bound.
+    pub liquidity_gross: u128,
+    /// The liquidity added to the pool when the price crosses the tick upwards.
+    pub liquidity_net: i128,
+    /// The fee growth on the other side of the tick from the price, relative to it.
+    pub fee_growth_outside_0: U256,
+    pub fee_growth_outside_1: U256,
+}
+
+/// A position of liquidity between two ticks of a concentrated liquidity pool.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, Default, RuntimeDebug)]
+pub struct ConcentratedPosition {
+    pub liquidity: u128,
+    /// The fee growth between the ticks when the fees owed were last updated.
+    pub fee_growth_inside_0_last: U256,
+    pub fee_growth_inside_1_last: U256,
+    pub fees_owed_0: AssetBalance,
+    pub fees_owed_1: AssetBalance,
+}
@@ -0,0 +1,273 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+//! # Route Module
+//!
+//! ## Overview
+//!
+//! Searches the trading pairs for the swap path which gives the best price, so the swaps
+//! only need the assets sold and bought.
+
+use super::*;
+
+/// The number of parts which the amount is split into by the best split of the routes.
+pub const SPLIT_ROUTE_PARTS: u32 = 100;
+/// The max number of the swap paths compared by the best-route swaps.
+pub const MAX_SWAP_PATHS: usize = 16;
+/// The weight of a step of the search for the swap paths, which checks a trading pair.
+pub const SWAP_PATH_SEARCH_STEP_WEIGHT: Weight = 10_000;
+
+#[cfg(test)]
+mod tests;
+
+impl<T: Config> Pallet<T> {
+    /// The swap paths from `asset_in` to `asset_out` through the enabled and unpaused trading
+    /// pairs, none of them goes through more than `T::MaxSwapHops` pairs or through an asset
+    /// twice. At most `MAX_SWAP_PATHS` paths are found.
+    pub fn get_swap_paths(asset_in: AssetId, asset_out: AssetId) -> Vec<Vec<AssetId>> {
+        let max_hops = T::MaxSwapHops::get() as usize;
+        let mut paths = Vec::new();
+        if asset_in == asset_out || max_hops == 0 {
+            return paths;
+        }
+
+        let pairs = Self::lp_pairs()
+            .into_iter()
+            .filter(|&(asset_0, asset_1)| {
+                Self::is_pair_enabled(asset_0, asset_1) && !Self::is_pair_paused(asset_0, asset_1)
+            })
+            .collect::<Vec<_>>();
+        Self::search_swap_paths(&pairs, asset_out, max_hops, &mut vec![asset_in], &mut paths);
+
+        paths
+    }
+
+    /// The swap path from `asset_in` to `asset_out` which gives the most of `asset_out` for
+    /// `amount_in`, with the amounts of the assets along it.
+    pub fn get_best_path_for_exact_in(
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: AssetBalance,
+    ) -> Option<(Vec<AssetId>, Vec<AssetBalance>)> {
+        Self::get_swap_paths(asset_in, asset_out)
+            .into_iter()
+            .filter_map(|path| {
+                Self::get_amount_out_by_path(amount_in, &path).ok().map(|amounts| (path, amounts))
+            })
+            // The shorter path wins a tie.
+            .max_by(|(path_a, amounts_a), (path_b, amounts_b)| {
+                amounts_a[amounts_a.len() - 1]
+                    .cmp(&amounts_b[amounts_b.len() - 1])
+                    .then(path_b.len().cmp(&path_a.len()))
+            })
+    }
+
+    /// The swap path from `asset_in` to `asset_out` which takes the least of `asset_in` for
+    /// `amount_out`, with the amounts of the assets along it.
+    pub fn get_best_path_for_exact_out(
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_out: AssetBalance,
+    ) -> Option<(Vec<AssetId>, Vec<AssetBalance>)> {
+        Self::get_swap_paths(asset_in, asset_out)
+            .into_iter()
+            .filter_map(|path| {
+                Self::get_amount_in_by_path(amount_out, &path).ok().map(|amounts| (path, amounts))
+            })
+            // The shorter path wins a tie.
+            .min_by(|(path_a, amounts_a), (path_b, amounts_b)| {
+                amounts_a[0].cmp(&amounts_b[0]).then(path_a.len().cmp(&path_b.len()))
+            })
+    }
+
+    /// The weight of the best-route swaps, the search checks every trading pair for every
+    /// path of less than `T::MaxSwapHops` hops, and at most `MAX_SWAP_PATHS` paths are quoted.
+    pub fn best_route_swap_weight() -> Weight {
+        let pairs = LiquidityPairs::<T>::decode_len().unwrap_or(0) as Weight;
+        let max_hops = T::MaxSwapHops::get();
+        let steps = (1..=max_hops)
+            .fold(0 as Weight, |steps, hops| steps.saturating_add(pairs.saturating_pow(hops)));
+        let quotes = (MAX_SWAP_PATHS as Weight).saturating_mul(max_hops as Weight);
+
+        T::DbWeight::get()
+            .reads(pairs.saturating_mul(2).saturating_add(quotes).saturating_add(1))
+            .saturating_add(SWAP_PATH_SEARCH_STEP_WEIGHT.saturating_mul(steps))
+            .saturating_add(1_000_000)
+    }
+
+    pub fn inner_swap_exact_assets_for_assets_through_best_route(
+        who: &T::AccountId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: AssetBalance,
+        amount_out_min: AssetBalance,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        let (path, _) = Self::get_best_path_for_exact_in(asset_in, asset_out, amount_in)
+            .ok_or(Error::<T>::InvalidPath)?;
+
+        Self::inner_swap_exact_tokens_for_tokens(who, amount_in, amount_out_min, &path, recipient)
+    }
+
+    pub fn inner_swap_assets_for_exact_assets_through_best_route(
+        who: &T::AccountId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_out: AssetBalance,
+        amount_in_max: AssetBalance,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        let (path, _) = Self::get_best_path_for_exact_out(asset_in, asset_out, amount_out)
+            .ok_or(Error::<T>::InvalidPath)?;
+
+        Self::inner_swap_tokens_for_exact_tokens(who, amount_out, amount_in_max, &path, recipient)
+    }
+
+    /// The best split of `amount_in` across `paths`, as the proportions of the paths in
+    /// `SPLIT_ROUTE_PARTS` parts with the total amount out. Each part goes to the path which
+    /// gives the most for it, the paths are taken as not sharing the pools.
+    pub fn get_best_split(
+        amount_in: AssetBalance,
+        paths: &[Vec<AssetId>],
+    ) -> (Vec<u32>, AssetBalance) {
+        let mut proportions = vec![0u32; paths.len()];
+        let mut amounts_out: Vec<AssetBalance> = vec![Zero::zero(); paths.len()];
+
+        for _ in 0..SPLIT_ROUTE_PARTS {
+            let best = paths
+                .iter()
+                .enumerate()
+                .filter_map(|(i, path)| {
+                    let amount = U256::from(amount_in)
+                        .saturating_mul(U256::from(proportions[i] + 1))
+                        .checked_div(U256::from(SPLIT_ROUTE_PARTS))
+                        .and_then(|n| TryInto::<AssetBalance>::try_into(n).ok())?;
+
+                    Self::get_amount_out_by_path(amount, path)
+                        .ok()
+                        .map(|amounts| (i, amounts[amounts.len() - 1]))
+                })
+                .max_by_key(|&(i, amount_out)| amount_out.saturating_sub(amounts_out[i]));
+
+            match best {
+                Some((i, amount_out)) => {
+                    proportions[i] += 1;
+                    amounts_out[i] = amount_out;
+                }
+                None => return (vec![0u32; paths.len()], Zero::zero()),
+            }
+        }
+
+        (
+            proportions,
+            amounts_out.iter().fold(Zero::zero(), |acc: AssetBalance, x| acc.saturating_add(*x)),
+        )
+    }
+
+    /// Sell `amount_in` split across the paths of `routes` by their proportions, every path
+    /// starts with the same asset and ends with the same asset.
+    pub fn inner_swap_exact_assets_for_assets_through_split_routes(
+        who: &T::AccountId,
+        amount_in: AssetBalance,
+        amount_out_min: AssetBalance,
+        routes: &[(Vec<AssetId>, u32)],
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        let (asset_in, asset_out) = match routes.first() {
+            Some((path, _)) if path.len() > 1 => (path[0], path[path.len() - 1]),
+            _ => return Err(Error::<T>::InvalidPath.into()),
+        };
+        ensure!(
+            routes.iter().all(|(path, _)| {
+                path.len() > 1 && path[0] == asset_in && path[path.len() - 1] == asset_out
+            }),
+            Error::<T>::InvalidPath
+        );
+
+        let proportions = routes.iter().map(|(_, proportion)| *proportion).collect::<Vec<_>>();
+        let amounts = Self::split_amount(amount_in, &proportions).ok_or(Error::<T>::InvalidPath)?;
+
+        let mut total_amount_out: AssetBalance = Zero::zero();
+        for ((path, _), amount) in routes.iter().zip(amounts) {
+            if amount.is_zero() {
+                continue;
+            }
+
+            let amounts_out = Self::get_amount_out_by_path(amount, path)?;
+            let amount_out = amounts_out[amounts_out.len() - 1];
+            Self::inner_swap_exact_tokens_for_tokens(who, amount, amount_out, path, recipient)?;
+
+            total_amount_out =
+                total_amount_out.checked_add(amount_out).ok_or(Error::<T>::Overflow)?;
+        }
+        ensure!(total_amount_out >= amount_out_min, Error::<T>::InsufficientTargetAmount);
+
+        Self::deposit_event(Event::AssetSplitSwap(
+            who.clone(),
+            recipient.clone(),
+            asset_in,
+            asset_out,
+            amount_in,
+            total_amount_out,
+        ));
+
+        Ok(())
+    }
+
+    /// `amount` split by `proportions`, the remainder of the division goes to the last
+    /// nonzero proportion.
+    fn split_amount(amount: AssetBalance, proportions: &[u32]) -> Option<Vec<AssetBalance>> {
+        let total = proportions.iter().try_fold(0u32, |acc, x| acc.checked_add(*x))?;
+        let last = proportions.iter().rposition(|x| *x > 0)?;
+
+        let mut amounts = proportions
+            .iter()
+            .map(|proportion| {
+                U256::from(amount)
+                    .saturating_mul(U256::from(*proportion))
+                    .checked_div(U256::from(total))
+                    .and_then(|n| TryInto::<AssetBalance>::try_into(n).ok())
+            })
+            .collect::<Option<Vec<AssetBalance>>>()?;
+
+        let split =
+            amounts.iter().fold(Zero::zero(), |acc: AssetBalance, x| acc.saturating_add(*x));
+        amounts[last] = amounts[last].checked_add(amount.checked_sub(split)?)?;
+
+        Some(amounts)
+    }
+
+    /// Depth-first search of the paths which extend `path` to `asset_out`.
+    fn search_swap_paths(
+        pairs: &[(AssetId, AssetId)],
+        asset_out: AssetId,
+        max_hops: usize,
+        path: &mut Vec<AssetId>,
+        paths: &mut Vec<Vec<AssetId>>,
+    ) {
+        let last = path[path.len() - 1];
+        for &(asset_0, asset_1) in pairs {
+            if paths.len() >= MAX_SWAP_PATHS {
+                return;
+            }
+
+            let next = if asset_0 == last {
+                asset_1
+            } else if asset_1 == last {
+                asset_0
+            } else {
+                continue;
+            };
+
+            if next == asset_out {
+                let mut found = path.clone();
+                found.push(next);
+                paths.push(found);
+            } else if path.len() < max_hops && !path.contains(&next) {
+                path.push(next);
+                Self::search_swap_paths(pairs, asset_out, max_hops, path, paths);
+                path.pop();
+            }
+        }
+    }
+}
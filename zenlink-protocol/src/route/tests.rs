@@ -0,0 +1,373 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+use frame_support::{assert_noop, assert_ok};
+
+use super::{AssetId, Error, MultiAssetsHandler, MAX_SWAP_PATHS, SWAP_PATH_SEARCH_STEP_WEIGHT};
+use crate::mock::*;
+
+const DOT_ASSET_ID: AssetId = AssetId { chain_id: 200, asset_type: LOCAL, asset_index: 2 };
+
+const BTC_ASSET_ID: AssetId = AssetId { chain_id: 300, asset_type: RESERVED, asset_index: 3 };
+
+const ETH_ASSET_ID: AssetId = AssetId { chain_id: 400, asset_type: NATIVE, asset_index: 0 };
+
+const KSM_ASSET_ID: AssetId = AssetId { chain_id: 500, asset_type: LOCAL, asset_index: 4 };
+
+const ALICE: u128 = 1;
+const BOB: u128 = 2;
+
+const DOT_UNIT: u128 = 1000_000_000_000_000;
+const BTC_UNIT: u128 = 1000_000_00;
+const ETH_UNIT: u128 = 1000_000_000_000;
+
+/// A shallow DOT-BTC pair, deep DOT-ETH and ETH-BTC pairs, and the KSM pairs which make up
+/// a path of three hops from DOT to BTC.
+fn create_pairs() {
+    for asset_id in [DOT_ASSET_ID, BTC_ASSET_ID, ETH_ASSET_ID, KSM_ASSET_ID].iter() {
+        assert_ok!(DexPallet::foreign_mint(*asset_id, &ALICE, u128::MAX / 2));
+    }
+    for (asset_0, asset_1) in [
+        (DOT_ASSET_ID, BTC_ASSET_ID),
+        (DOT_ASSET_ID, ETH_ASSET_ID),
+        (ETH_ASSET_ID, BTC_ASSET_ID),
+        (DOT_ASSET_ID, KSM_ASSET_ID),
+        (KSM_ASSET_ID, ETH_ASSET_ID),
+    ]
+    .iter()
+    {
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), *asset_0, *asset_1));
+    }
+
+    assert_ok!(DexPallet::inner_add_liquidity(
+        &ALICE,
+        DOT_ASSET_ID,
+        BTC_ASSET_ID,
+        1000 * DOT_UNIT,
+        1000 * BTC_UNIT,
+        0,
+        0
+    ));
+    assert_ok!(DexPallet::inner_add_liquidity(
+        &ALICE,
+        DOT_ASSET_ID,
+        ETH_ASSET_ID,
+        100000 * DOT_UNIT,
+        100000 * ETH_UNIT,
+        0,
+        0
+    ));
+    assert_ok!(DexPallet::inner_add_liquidity(
+        &ALICE,
+        ETH_ASSET_ID,
+        BTC_ASSET_ID,
+        100000 * ETH_UNIT,
+        100000 * BTC_UNIT,
+        0,
+        0
+    ));
+}
+
+#[test]
+fn get_swap_paths_should_work() {
+    new_test_ext().execute_with(|| {
+        create_pairs();
+
+        // DOT-KSM-ETH-BTC is beyond `MaxSwapHops`.
+        assert_eq!(
+            DexPallet::get_swap_paths(DOT_ASSET_ID, BTC_ASSET_ID),
+            vec![vec![DOT_ASSET_ID, BTC_ASSET_ID], vec![DOT_ASSET_ID, ETH_ASSET_ID, BTC_ASSET_ID]]
+        );
+        assert_eq!(
+            DexPallet::get_swap_paths(BTC_ASSET_ID, DOT_ASSET_ID),
+            vec![vec![BTC_ASSET_ID, DOT_ASSET_ID], vec![BTC_ASSET_ID, ETH_ASSET_ID, DOT_ASSET_ID]]
+        );
+        assert_eq!(
+            DexPallet::get_swap_paths(KSM_ASSET_ID, BTC_ASSET_ID),
+            vec![
+                vec![KSM_ASSET_ID, DOT_ASSET_ID, BTC_ASSET_ID],
+                vec![KSM_ASSET_ID, ETH_ASSET_ID, BTC_ASSET_ID]
+            ]
+        );
+        assert!(DexPallet::get_swap_paths(DOT_ASSET_ID, DOT_ASSET_ID).is_empty());
+
+        // The paths through the KSM pairs without liquidity give nothing.
+        assert_eq!(
+            DexPallet::get_best_path_for_exact_in(KSM_ASSET_ID, BTC_ASSET_ID, DOT_UNIT),
+            None
+        );
+    });
+}
+
+#[test]
+fn swap_exact_assets_for_assets_through_best_route_should_work() {
+    new_test_ext().execute_with(|| {
+        create_pairs();
+        System::set_block_number(1);
+
+        // The fee of one more hop outweighs the price impact of small amounts.
+        assert_eq!(
+            DexPallet::get_best_path_for_exact_in(DOT_ASSET_ID, BTC_ASSET_ID, DOT_UNIT / 100),
+            Some((vec![DOT_ASSET_ID, BTC_ASSET_ID], vec![DOT_UNIT / 100, 996990]))
+        );
+        assert_ok!(DexPallet::swap_exact_assets_for_assets_through_best_route(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            DOT_UNIT / 100,
+            996990,
+            BOB,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(<Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &BOB), 996990);
+
+        // Direct through DOT-BTC it is 9066108938.
+        assert_noop!(
+            DexPallet::swap_exact_assets_for_assets_through_best_route(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                100 * DOT_UNIT,
+                9920338517,
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientTargetAmount
+        );
+        assert_ok!(DexPallet::swap_exact_assets_for_assets_through_best_route(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            100 * DOT_UNIT,
+            9920338516,
+            BOB,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &BOB),
+            996990 + 9920338516
+        );
+        assert!(System::events().iter().any(|record| record.event
+            == Event::Zenlink(crate::Event::AssetSwap(
+                ALICE,
+                BOB,
+                vec![DOT_ASSET_ID, ETH_ASSET_ID, BTC_ASSET_ID],
+                100 * DOT_UNIT,
+                9920338516
+            ))));
+
+        assert_noop!(
+            DexPallet::swap_exact_assets_for_assets_through_best_route(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                KSM_ASSET_ID,
+                DOT_UNIT,
+                0,
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InvalidPath
+        );
+        assert_noop!(
+            DexPallet::swap_exact_assets_for_assets_through_best_route(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                DOT_UNIT,
+                0,
+                BOB,
+                Deadline::BlockNumber(0)
+            ),
+            Error::<Test>::Deadline
+        );
+    });
+}
+
+#[test]
+fn swap_assets_for_exact_assets_through_best_route_should_work() {
+    new_test_ext().execute_with(|| {
+        create_pairs();
+        System::set_block_number(1);
+
+        // Direct through DOT-BTC it is 10131404313951957.
+        assert_noop!(
+            DexPallet::swap_assets_for_exact_assets_through_best_route(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                10 * BTC_UNIT,
+                10062286569148288,
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::ExcessiveSoldAmount
+        );
+
+        let dot_balance = <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &ALICE);
+        assert_ok!(DexPallet::swap_assets_for_exact_assets_through_best_route(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            10 * BTC_UNIT,
+            10062286569148289,
+            BOB,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &BOB),
+            10 * BTC_UNIT
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &ALICE),
+            dot_balance - 10062286569148289
+        );
+        assert!(System::events().iter().any(|record| record.event
+            == Event::Zenlink(crate::Event::AssetSwap(
+                ALICE,
+                BOB,
+                vec![DOT_ASSET_ID, ETH_ASSET_ID, BTC_ASSET_ID],
+                10062286569148289,
+                10 * BTC_UNIT
+            ))));
+    });
+}
+
+#[test]
+fn get_best_split_should_work() {
+    new_test_ext().execute_with(|| {
+        create_pairs();
+
+        let paths =
+            vec![vec![DOT_ASSET_ID, BTC_ASSET_ID], vec![DOT_ASSET_ID, ETH_ASSET_ID, BTC_ASSET_ID]];
+        // All through DOT-ETH-BTC it is 97460453597.
+        assert_eq!(DexPallet::get_best_split(1000 * DOT_UNIT, &paths), (vec![2, 98], 97503566347));
+
+        assert_eq!(
+            DexPallet::get_best_split(1000 * DOT_UNIT, &[vec![DOT_ASSET_ID, KSM_ASSET_ID]]),
+            (vec![0], 0)
+        );
+    });
+}
+
+#[test]
+fn swap_exact_assets_for_assets_through_split_routes_should_work() {
+    new_test_ext().execute_with(|| {
+        create_pairs();
+        System::set_block_number(1);
+
+        let routes = vec![
+            (vec![DOT_ASSET_ID, BTC_ASSET_ID], 2),
+            (vec![DOT_ASSET_ID, ETH_ASSET_ID, BTC_ASSET_ID], 98),
+        ];
+        assert_noop!(
+            DexPallet::swap_exact_assets_for_assets_through_split_routes(
+                Origin::signed(ALICE),
+                1000 * DOT_UNIT,
+                97503566348,
+                routes.clone(),
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientTargetAmount
+        );
+        assert_ok!(DexPallet::swap_exact_assets_for_assets_through_split_routes(
+            Origin::signed(ALICE),
+            1000 * DOT_UNIT,
+            97503566347,
+            routes,
+            BOB,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &BOB),
+            97503566347
+        );
+
+        let events = System::events().into_iter().map(|record| record.event).collect::<Vec<_>>();
+        assert!(events.contains(&Event::Zenlink(crate::Event::AssetSwap(
+            ALICE,
+            BOB,
+            vec![DOT_ASSET_ID, BTC_ASSET_ID],
+            20 * DOT_UNIT,
+            1955016961
+        ))));
+        assert!(events.contains(&Event::Zenlink(crate::Event::AssetSwap(
+            ALICE,
+            BOB,
+            vec![DOT_ASSET_ID, ETH_ASSET_ID, BTC_ASSET_ID],
+            980 * DOT_UNIT,
+            95548549386
+        ))));
+        assert!(events.contains(&Event::Zenlink(crate::Event::AssetSplitSwap(
+            ALICE,
+            BOB,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            1000 * DOT_UNIT,
+            97503566347
+        ))));
+    });
+}
+
+#[test]
+fn swap_exact_assets_for_assets_through_split_routes_should_not_work() {
+    new_test_ext().execute_with(|| {
+        create_pairs();
+
+        assert_noop!(
+            DexPallet::swap_exact_assets_for_assets_through_split_routes(
+                Origin::signed(ALICE),
+                DOT_UNIT,
+                0,
+                vec![],
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InvalidPath
+        );
+        // The routes end with different assets.
+        assert_noop!(
+            DexPallet::swap_exact_assets_for_assets_through_split_routes(
+                Origin::signed(ALICE),
+                DOT_UNIT,
+                0,
+                vec![(vec![DOT_ASSET_ID, BTC_ASSET_ID], 1), (vec![DOT_ASSET_ID, ETH_ASSET_ID], 1)],
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InvalidPath
+        );
+        assert_noop!(
+            DexPallet::swap_exact_assets_for_assets_through_split_routes(
+                Origin::signed(ALICE),
+                DOT_UNIT,
+                0,
+                vec![(vec![DOT_ASSET_ID, BTC_ASSET_ID], 0)],
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InvalidPath
+        );
+    });
+}
+
+#[test]
+fn swap_path_search_should_be_bounded() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(DexPallet::best_route_swap_weight(), 1_000_000);
+        create_pairs();
+        // Five pairs for the paths of one hop, and 25 for the paths of two hops.
+        assert_eq!(
+            DexPallet::best_route_swap_weight(),
+            1_000_000 + 30 * SWAP_PATH_SEARCH_STEP_WEIGHT
+        );
+
+        for asset_index in 10..30 {
+            let asset_id = AssetId { chain_id: 600, asset_type: LOCAL, asset_index };
+            assert_ok!(DexPallet::foreign_mint(asset_id, &ALICE, DOT_UNIT));
+            assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, asset_id));
+            assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), asset_id, BTC_ASSET_ID));
+        }
+        assert_eq!(DexPallet::get_swap_paths(DOT_ASSET_ID, BTC_ASSET_ID).len(), MAX_SWAP_PATHS);
+    });
+}
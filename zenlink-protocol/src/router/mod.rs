@@ -0,0 +1,195 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+//! # Router Module
+//!
+//! ## Overview
+//!
+//! Batches of swaps, liquidity changes and transfers which run in order in one transaction.
+//! The outputs of the actions are settled to the caller and tracked by the batch, so a later
+//! action can take all the output of an earlier one, and the minimum outputs are only
+//! checked once the whole batch is done.
+
+use super::*;
+use sp_std::collections::btree_map::BTreeMap;
+
+/// The maximum number of actions of a router batch.
+pub const MAX_ROUTER_ACTIONS: usize = 16;
+
+/// The outputs of the actions of a router batch which are not taken by the later actions.
+#[derive(Default)]
+struct RouterOutputs {
+    assets: BTreeMap<AssetId, AssetBalance>,
+    liquidity: BTreeMap<(AssetId, AssetId), AssetBalance>,
+}
+
+#[cfg(test)]
+mod tests;
+
+impl<T: Config> Pallet<T> {
+    /// Run the actions of `who` in order, then check the outputs left by the batch against the
+    /// minimums. The non-zero outputs left are returned.
+    pub fn inner_router_batch(
+        who: &T::AccountId,
+        actions: Vec<RouterAction<T::AccountId>>,
+        amounts_out_min: &[(AssetId, AssetBalance)],
+    ) -> Result<Vec<(AssetId, AssetBalance)>, DispatchError> {
+        ensure!(
+            !actions.is_empty() && actions.len() <= MAX_ROUTER_ACTIONS,
+            Error::<T>::InvalidRouterActions
+        );
+
+        let mut outputs = RouterOutputs::default();
+        for action in actions {
+            Self::router_action(who, action, &mut outputs)?;
+        }
+
+        for (asset_id, amount_min) in amounts_out_min.iter() {
+            ensure!(
+                outputs.assets.get(asset_id).copied().unwrap_or_default() >= *amount_min,
+                Error::<T>::InsufficientTargetAmount
+            );
+        }
+
+        Ok(outputs.assets.into_iter().filter(|(_, amount)| !amount.is_zero()).collect())
+    }
+
+    fn router_action(
+        who: &T::AccountId,
+        action: RouterAction<T::AccountId>,
+        outputs: &mut RouterOutputs,
+    ) -> DispatchResult {
+        match action {
+            RouterAction::SwapExactIn { path, amount_in } => {
+                ensure!(path.len() >= 2, Error::<T>::InvalidPath);
+                let (asset_in, asset_out) = (path[0], path[path.len() - 1]);
+                let amount_in = Self::router_amount(amount_in, outputs.assets.get(&asset_in))?;
+
+                Self::settle_router_outputs(who, &[asset_in, asset_out], None, outputs, || {
+                    Self::inner_swap_exact_tokens_for_tokens(
+                        who,
+                        amount_in,
+                        Zero::zero(),
+                        &path,
+                        who,
+                    )
+                })
+            }
+            RouterAction::SwapExactOut { path, amount_out, amount_in_max } => {
+                ensure!(path.len() >= 2, Error::<T>::InvalidPath);
+                let (asset_in, asset_out) = (path[0], path[path.len() - 1]);
+                let amount_in_max =
+                    Self::router_amount(amount_in_max, outputs.assets.get(&asset_in))?;
+
+                Self::settle_router_outputs(who, &[asset_in, asset_out], None, outputs, || {
+                    Self::inner_swap_tokens_for_exact_tokens(
+                        who,
+                        amount_out,
+                        amount_in_max,
+                        &path,
+                        who,
+                    )
+                })
+            }
+            RouterAction::AddLiquidity { asset_0, asset_1, amount_0_desired, amount_1_desired } => {
+                let amount_0_desired =
+                    Self::router_amount(amount_0_desired, outputs.assets.get(&asset_0))?;
+                let amount_1_desired =
+                    Self::router_amount(amount_1_desired, outputs.assets.get(&asset_1))?;
+                let pair = Self::sort_asset_id(asset_0, asset_1);
+
+                Self::settle_router_outputs(who, &[asset_0, asset_1], Some(pair), outputs, || {
+                    Self::inner_add_liquidity(
+                        who,
+                        asset_0,
+                        asset_1,
+                        amount_0_desired,
+                        amount_1_desired,
+                        Zero::zero(),
+                        Zero::zero(),
+                    )
+                })
+            }
+            RouterAction::RemoveLiquidity { asset_0, asset_1, liquidity } => {
+                let pair = Self::sort_asset_id(asset_0, asset_1);
+                let liquidity = Self::router_amount(liquidity, outputs.liquidity.get(&pair))?;
+
+                Self::settle_router_outputs(who, &[asset_0, asset_1], Some(pair), outputs, || {
+                    Self::inner_remove_liquidity(
+                        who,
+                        asset_0,
+                        asset_1,
+                        liquidity,
+                        Zero::zero(),
+                        Zero::zero(),
+                        who,
+                    )
+                })
+            }
+            RouterAction::Transfer { asset_id, amount, recipient } => {
+                let amount = Self::router_amount(amount, outputs.assets.get(&asset_id))?;
+
+                Self::settle_router_outputs(who, &[asset_id], None, outputs, || {
+                    T::MultiAssetsHandler::transfer(asset_id, who, &recipient, amount)
+                })
+            }
+        }
+    }
+
+    fn router_amount(
+        amount: RouterAmount,
+        output: Option<&AssetBalance>,
+    ) -> Result<AssetBalance, DispatchError> {
+        match amount {
+            RouterAmount::Amount(amount) => Ok(amount),
+            RouterAmount::AllOutput => output
+                .copied()
+                .filter(|output| !output.is_zero())
+                .ok_or_else(|| Error::<T>::NoRouterOutput.into()),
+        }
+    }
+
+    /// Run the action, then add the balance increases of `who` in the assets and the
+    /// liquidity of the pair to the outputs, and take the decreases from them.
+    fn settle_router_outputs<F: FnOnce() -> DispatchResult>(
+        who: &T::AccountId,
+        assets: &[AssetId],
+        pair: Option<(AssetId, AssetId)>,
+        outputs: &mut RouterOutputs,
+        action: F,
+    ) -> DispatchResult {
+        let mut assets = assets.to_vec();
+        assets.dedup();
+
+        let balances = assets
+            .iter()
+            .map(|asset_id| T::MultiAssetsHandler::balance_of(*asset_id, who))
+            .collect::<Vec<_>>();
+        let liquidity = pair.map(|pair| Self::lp_ledger((pair, who)));
+
+        action()?;
+
+        for (asset_id, balance) in assets.iter().zip(balances) {
+            let output = outputs.assets.entry(*asset_id).or_default();
+            Self::settle_router_output(
+                output,
+                balance,
+                T::MultiAssetsHandler::balance_of(*asset_id, who),
+            );
+        }
+        if let (Some(pair), Some(liquidity)) = (pair, liquidity) {
+            let output = outputs.liquidity.entry(pair).or_default();
+            Self::settle_router_output(output, liquidity, Self::lp_ledger((pair, who)));
+        }
+
+        Ok(())
+    }
+
+    fn settle_router_output(output: &mut AssetBalance, before: AssetBalance, after: AssetBalance) {
+        if after >= before {
+            *output = output.saturating_add(after - before);
+        } else {
+            *output = output.saturating_sub(before - after);
+        }
+    }
+}
@@ -0,0 +1,241 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+use frame_support::{assert_noop, assert_ok};
+
+use super::{AssetId, Error, MultiAssetsHandler, RouterAction, RouterAmount, MAX_ROUTER_ACTIONS};
+use crate::mock::*;
+
+const DOT_ASSET_ID: AssetId = AssetId { chain_id: 200, asset_type: LOCAL, asset_index: 2 };
+
+const BTC_ASSET_ID: AssetId = AssetId { chain_id: 300, asset_type: RESERVED, asset_index: 3 };
+
+const ETH_ASSET_ID: AssetId = AssetId { chain_id: 400, asset_type: NATIVE, asset_index: 0 };
+
+const ALICE: u128 = 1;
+const BOB: u128 = 2;
+
+const DOT_UNIT: u128 = 1000_000_000_000_000;
+const BTC_UNIT: u128 = 1000_000_00;
+const ETH_UNIT: u128 = 1000_000_000_000;
+
+/// The DOT-BTC and BTC-ETH pairs with liquidity of ALICE.
+fn create_pairs() {
+    for asset_id in [DOT_ASSET_ID, BTC_ASSET_ID, ETH_ASSET_ID].iter() {
+        assert_ok!(DexPallet::foreign_mint(*asset_id, &ALICE, u128::MAX / 2));
+    }
+    assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+    assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), BTC_ASSET_ID, ETH_ASSET_ID));
+
+    assert_ok!(DexPallet::inner_add_liquidity(
+        &ALICE,
+        DOT_ASSET_ID,
+        BTC_ASSET_ID,
+        1000 * DOT_UNIT,
+        1000 * BTC_UNIT,
+        0,
+        0
+    ));
+    assert_ok!(DexPallet::inner_add_liquidity(
+        &ALICE,
+        BTC_ASSET_ID,
+        ETH_ASSET_ID,
+        1000 * BTC_UNIT,
+        1000 * ETH_UNIT,
+        0,
+        0
+    ));
+}
+
+#[test]
+fn router_batch_swaps_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pairs();
+
+        let amounts = DexPallet::get_amount_out_by_path(
+            DOT_UNIT,
+            &[DOT_ASSET_ID, BTC_ASSET_ID, ETH_ASSET_ID],
+        )
+        .unwrap();
+        let actions = vec![
+            RouterAction::SwapExactIn {
+                path: vec![DOT_ASSET_ID, BTC_ASSET_ID],
+                amount_in: RouterAmount::Amount(DOT_UNIT),
+            },
+            RouterAction::SwapExactIn {
+                path: vec![BTC_ASSET_ID, ETH_ASSET_ID],
+                amount_in: RouterAmount::AllOutput,
+            },
+            RouterAction::Transfer {
+                asset_id: ETH_ASSET_ID,
+                amount: RouterAmount::AllOutput,
+                recipient: BOB,
+            },
+        ];
+
+        // The output of ETH is transferred away before the end of the batch.
+        assert_noop!(
+            DexPallet::router_batch(
+                Origin::signed(ALICE),
+                actions.clone(),
+                vec![(ETH_ASSET_ID, 1)],
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientTargetAmount
+        );
+        assert_ok!(DexPallet::router_batch(
+            Origin::signed(ALICE),
+            actions,
+            vec![],
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(ETH_ASSET_ID, &BOB),
+            amounts[2]
+        );
+        assert!(System::events().iter().any(|record| record.event
+            == Event::Zenlink(crate::Event::RouterBatchExecuted(ALICE, vec![]))));
+
+        let amounts =
+            DexPallet::get_amount_in_by_path(ETH_UNIT, &[DOT_ASSET_ID, BTC_ASSET_ID, ETH_ASSET_ID])
+                .unwrap();
+        assert_ok!(DexPallet::router_batch(
+            Origin::signed(ALICE),
+            vec![
+                RouterAction::SwapExactOut {
+                    path: vec![DOT_ASSET_ID, BTC_ASSET_ID],
+                    amount_out: amounts[1],
+                    amount_in_max: RouterAmount::Amount(amounts[0]),
+                },
+                RouterAction::SwapExactOut {
+                    path: vec![BTC_ASSET_ID, ETH_ASSET_ID],
+                    amount_out: ETH_UNIT,
+                    amount_in_max: RouterAmount::AllOutput,
+                },
+            ],
+            vec![(ETH_ASSET_ID, ETH_UNIT)],
+            Deadline::BlockNumber(100)
+        ));
+        assert!(System::events().iter().any(|record| record.event
+            == Event::Zenlink(crate::Event::RouterBatchExecuted(
+                ALICE,
+                vec![(ETH_ASSET_ID, ETH_UNIT)]
+            ))));
+    });
+}
+
+#[test]
+fn router_batch_liquidity_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pairs();
+
+        let liquidity = DexPallet::lp_ledger(((DOT_ASSET_ID, BTC_ASSET_ID), ALICE));
+        let actions = vec![
+            RouterAction::SwapExactOut {
+                path: vec![DOT_ASSET_ID, BTC_ASSET_ID],
+                amount_out: BTC_UNIT,
+                amount_in_max: RouterAmount::Amount(2 * DOT_UNIT),
+            },
+            RouterAction::AddLiquidity {
+                asset_0: DOT_ASSET_ID,
+                asset_1: BTC_ASSET_ID,
+                amount_0_desired: RouterAmount::Amount(10 * DOT_UNIT),
+                amount_1_desired: RouterAmount::AllOutput,
+            },
+            RouterAction::RemoveLiquidity {
+                asset_0: DOT_ASSET_ID,
+                asset_1: BTC_ASSET_ID,
+                liquidity: RouterAmount::AllOutput,
+            },
+        ];
+
+        assert_noop!(
+            DexPallet::router_batch(
+                Origin::signed(ALICE),
+                actions.clone(),
+                vec![(BTC_ASSET_ID, BTC_UNIT + 1)],
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientTargetAmount
+        );
+        assert_ok!(DexPallet::router_batch(
+            Origin::signed(ALICE),
+            actions,
+            vec![(BTC_ASSET_ID, BTC_UNIT - 1)],
+            Deadline::BlockNumber(100)
+        ));
+        // All the liquidity added by the batch is removed by it.
+        assert_eq!(DexPallet::lp_ledger(((DOT_ASSET_ID, BTC_ASSET_ID), ALICE)), liquidity);
+    });
+}
+
+#[test]
+fn router_batch_should_not_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pairs();
+
+        let transfer = RouterAction::Transfer {
+            asset_id: DOT_ASSET_ID,
+            amount: RouterAmount::Amount(1),
+            recipient: BOB,
+        };
+        assert_noop!(
+            DexPallet::router_batch(
+                Origin::signed(ALICE),
+                vec![],
+                vec![],
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InvalidRouterActions
+        );
+        assert_noop!(
+            DexPallet::router_batch(
+                Origin::signed(ALICE),
+                vec![transfer.clone(); MAX_ROUTER_ACTIONS + 1],
+                vec![],
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InvalidRouterActions
+        );
+        assert_noop!(
+            DexPallet::router_batch(
+                Origin::signed(ALICE),
+                vec![transfer.clone()],
+                vec![],
+                Deadline::BlockNumber(1)
+            ),
+            Error::<Test>::Deadline
+        );
+        assert_noop!(
+            DexPallet::router_batch(
+                Origin::signed(ALICE),
+                vec![
+                    transfer,
+                    RouterAction::Transfer {
+                        asset_id: BTC_ASSET_ID,
+                        amount: RouterAmount::AllOutput,
+                        recipient: BOB
+                    }
+                ],
+                vec![],
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::NoRouterOutput
+        );
+        assert_noop!(
+            DexPallet::router_batch(
+                Origin::signed(ALICE),
+                vec![RouterAction::SwapExactIn {
+                    path: vec![DOT_ASSET_ID],
+                    amount_in: RouterAmount::Amount(DOT_UNIT)
+                }],
+                vec![],
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InvalidPath
+        );
+    });
+}
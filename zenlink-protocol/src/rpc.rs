@@ -24,6 +24,45 @@ pub struct PairInfo<AccountId, AssetBalance> {
     pub lp_asset_id: AssetId,
 }
 
+#[derive(Encode, Decode, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ConcentratedPositionInfo<AssetBalance> {
+    pub asset_0: AssetId,
+    pub asset_1: AssetId,
+
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: AssetBalance,
+    /// The amounts of the assets of the liquidity at the current price.
+    pub amount_0: AssetBalance,
+    pub amount_1: AssetBalance,
+    /// The fees earned and not collected yet.
+    pub fees_0: AssetBalance,
+    pub fees_1: AssetBalance,
+}
+
+/// Calculate the output amount of `amount_in` by the time-weighted average price between
+/// two cumulative price observations which are `elapsed` blocks apart.
+pub fn calculate_twap_amount(
+    price_cumulative_start: U256,
+    price_cumulative_end: U256,
+    elapsed: u32,
+    amount_in: AssetBalance,
+) -> AssetBalance {
+    if elapsed == 0 {
+        return Zero::zero();
+    }
+
+    let price_average =
+        price_cumulative_end.overflowing_sub(price_cumulative_start).0 / U256::from(elapsed);
+
+    TryInto::<AssetBalance>::try_into(
+        price_average.full_mul(U256::from(amount_in)) >> swap::PRICE_RESOLUTION,
+    )
+    .unwrap_or_else(|_| Zero::zero())
+}
+
 impl<T: Config> Pallet<T> {
     pub fn get_assets() -> Vec<AssetId> {
         let mut all_assets = Self::foreign_list();
@@ -46,16 +85,16 @@ impl<T: Config> Pallet<T> {
             .enumerate()
             .map(|(i, pair)| {
                 let lp_id = AssetId { chain_id, asset_type: LIQUIDITY, asset_index: i as u32 };
-                let (para_account, total) = Self::lp_metadata(pair).unwrap_or_default();
+                let meta = Self::lp_metadata(pair).unwrap_or_default();
 
                 PairInfo {
                     asset_0: pair.0,
                     asset_1: pair.1,
-                    account: para_account.clone(),
-                    total_liquidity: total,
+                    account: meta.pair_account,
+                    total_liquidity: meta.total_supply,
                     holding_liquidity: Zero::zero(),
-                    reserve_0: T::MultiAssetsHandler::balance_of(pair.0, &para_account),
-                    reserve_1: T::MultiAssetsHandler::balance_of(pair.1, &para_account),
+                    reserve_0: meta.reserve_0,
+                    reserve_1: meta.reserve_1,
                     lp_asset_id: lp_id,
                 }
             })
@@ -71,16 +110,17 @@ impl<T: Config> Pallet<T> {
 
         if let Some(index) = Self::lp_pairs().iter().position(|pair| *pair == sorted_pair) {
             let lp_id = AssetId { chain_id, asset_type: LIQUIDITY, asset_index: index as u32 };
-            let (para_account, total) = Self::lp_metadata(sorted_pair).unwrap_or_default();
+            let meta = Self::lp_metadata(sorted_pair).unwrap_or_default();
+            let (reserve_0, reserve_1) = Self::pair_reserves(&meta, asset_0, asset_1);
 
             Some(PairInfo {
                 asset_0,
                 asset_1,
-                account: para_account.clone(),
-                total_liquidity: total,
+                account: meta.pair_account,
+                total_liquidity: meta.total_supply,
                 holding_liquidity: Zero::zero(),
-                reserve_0: T::MultiAssetsHandler::balance_of(asset_0, &para_account),
-                reserve_1: T::MultiAssetsHandler::balance_of(asset_1, &para_account),
+                reserve_0,
+                reserve_1,
                 lp_asset_id: lp_id,
             })
         } else {
@@ -147,9 +187,8 @@ impl<T: Config> Pallet<T> {
         amount_1_min: AssetBalance,
     ) -> AssetBalance {
         let sorted_pair = Self::sort_asset_id(asset_0, asset_1);
-        Self::lp_metadata(sorted_pair).map_or(Zero::zero(), |(pair_account, total)| {
-            let reserve_0 = T::MultiAssetsHandler::balance_of(asset_0, &pair_account);
-            let reserve_1 = T::MultiAssetsHandler::balance_of(asset_1, &pair_account);
+        Self::lp_metadata(sorted_pair).map_or(Zero::zero(), |meta| {
+            let (reserve_0, reserve_1) = Self::pair_reserves(&meta, asset_0, asset_1);
 
             Self::calculate_added_amount(
                 amount_0_desired,
@@ -160,8 +199,104 @@ impl<T: Config> Pallet<T> {
                 reserve_1,
             )
             .map_or(Zero::zero(), |(amount_0, amount_1)| {
-                Self::calculate_liquidity(amount_0, amount_1, reserve_0, reserve_1, total)
+                Self::calculate_liquidity(
+                    amount_0,
+                    amount_1,
+                    reserve_0,
+                    reserve_1,
+                    meta.total_supply,
+                )
             })
         })
     }
+
+    /// The liquidity minted by `add_liquidity_single_asset` with `amount_0` of `asset_0`.
+    pub fn get_estimate_lptoken_single_asset(
+        asset_0: AssetId,
+        asset_1: AssetId,
+        amount_0: AssetBalance,
+    ) -> AssetBalance {
+        let sorted_pair = Self::sort_asset_id(asset_0, asset_1);
+        Self::lp_metadata(sorted_pair).map_or(Zero::zero(), |meta| {
+            let (reserve_0, reserve_1) = Self::pair_reserves(&meta, asset_0, asset_1);
+            if reserve_0.is_zero() || reserve_1.is_zero() {
+                return Zero::zero();
+            }
+
+            let fee = Self::get_exchange_fee(asset_0, asset_1);
+            let swap_amount = Self::calculate_single_asset_swap_amount(amount_0, reserve_0, fee);
+            let amount_1 = Self::get_amount_out(swap_amount, reserve_0, reserve_1, fee);
+            let reserve_0 = reserve_0.saturating_add(swap_amount);
+            let reserve_1 = reserve_1.saturating_sub(amount_1);
+
+            Self::calculate_added_amount(
+                amount_0.saturating_sub(swap_amount),
+                amount_1,
+                Zero::zero(),
+                Zero::zero(),
+                reserve_0,
+                reserve_1,
+            )
+            .map_or(Zero::zero(), |(amount_0, amount_1)| {
+                Self::calculate_liquidity(
+                    amount_0,
+                    amount_1,
+                    reserve_0,
+                    reserve_1,
+                    meta.total_supply,
+                )
+            })
+        })
+    }
+
+    /// The amount of `asset_0` received by `remove_liquidity_single_asset` with `liquidity`.
+    pub fn get_estimate_remove_liquidity_single_asset(
+        asset_0: AssetId,
+        asset_1: AssetId,
+        liquidity: AssetBalance,
+    ) -> AssetBalance {
+        let sorted_pair = Self::sort_asset_id(asset_0, asset_1);
+        Self::lp_metadata(sorted_pair).map_or(Zero::zero(), |meta| {
+            let (reserve_0, reserve_1) = Self::pair_reserves(&meta, asset_0, asset_1);
+            if liquidity > meta.total_supply {
+                return Zero::zero();
+            }
+
+            let amount_0 = Self::calculate_share_amount(liquidity, meta.total_supply, reserve_0);
+            let amount_1 = Self::calculate_share_amount(liquidity, meta.total_supply, reserve_1);
+
+            let amount_out = Self::get_amount_out(
+                amount_1,
+                reserve_1.saturating_sub(amount_1),
+                reserve_0.saturating_sub(amount_0),
+                Self::get_exchange_fee(asset_0, asset_1),
+            );
+            if !amount_1.is_zero() && amount_out.is_zero() {
+                return Zero::zero();
+            }
+
+            amount_0.saturating_add(amount_out)
+        })
+    }
+
+    /// The cumulative price of `asset_in` quoted in `asset_out` as of the current block.
+    pub fn get_price_cumulative(asset_in: AssetId, asset_out: AssetId) -> Option<U256> {
+        let (asset_0, asset_1) = Self::sort_asset_id(asset_in, asset_out);
+        let meta = Self::lp_metadata((asset_0, asset_1))?;
+        let (price_0, price_1, last) = Self::lp_price_cumulative((asset_0, asset_1));
+
+        let (price_0, price_1) = Self::accumulate_price(
+            price_0,
+            price_1,
+            meta.reserve_0,
+            meta.reserve_1,
+            frame_system::Pallet::<T>::block_number().saturating_sub(last),
+        );
+
+        if asset_in == asset_0 {
+            Some(price_0)
+        } else {
+            Some(price_1)
+        }
+    }
 }
@@ -0,0 +1,81 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+//! # Signed Order Module
+//!
+//! ## Overview
+//!
+//! Swap orders which are signed off-chain by their owners and submitted by any relayer, so
+//! the owners without the native currency for the transaction fees can swap too. Every order
+//! carries the next nonce of its owner and is executed at most once. The owner cancels the
+//! outstanding orders by moving the next nonce past them.
+
+use super::*;
+
+/// The context which the swap orders are signed in, apart from the other signed messages.
+pub const SWAP_ORDER_CONTEXT: &[u8] = b"zenlink/swap-order";
+
+#[cfg(test)]
+mod tests;
+
+impl<T: Config> Pallet<T> {
+    /// The message signed by the owner of the swap order, made of the context, the genesis
+    /// hash of the chain and the order, so it can't be replayed on another chain.
+    pub fn swap_order_message(order: &SwapOrderOf<T>) -> Vec<u8> {
+        let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+
+        (SWAP_ORDER_CONTEXT, genesis_hash, order).encode()
+    }
+
+    pub fn inner_execute_swap_order(
+        relayer: &T::AccountId,
+        order: &SwapOrderOf<T>,
+        signature: &OrderSignatureOf<T>,
+        relayer_fee: AssetBalance,
+    ) -> DispatchResult {
+        ensure!(relayer_fee <= order.max_relayer_fee, Error::<T>::ExcessiveRelayerFee);
+        ensure!(order.path.len() >= 2, Error::<T>::InvalidPath);
+        ensure!(order.nonce == Self::order_nonce(&order.owner), Error::<T>::InvalidOrderNonce);
+        ensure!(
+            T::OrderSignatureVerifier::verify(
+                signature,
+                &Self::swap_order_message(order),
+                &order.owner
+            ),
+            Error::<T>::InvalidOrderSignature
+        );
+
+        let next_nonce = order.nonce.checked_add(1).ok_or(Error::<T>::Overflow)?;
+        OrderNonces::<T>::insert(&order.owner, next_nonce);
+
+        if !relayer_fee.is_zero() {
+            T::MultiAssetsHandler::transfer(order.path[0], &order.owner, relayer, relayer_fee)?;
+        }
+        Self::inner_swap_exact_tokens_for_tokens(
+            &order.owner,
+            order.amount_in,
+            order.amount_out_min,
+            &order.path,
+            &order.recipient,
+        )?;
+
+        Self::deposit_event(Event::SwapOrderExecuted(
+            order.owner.clone(),
+            relayer.clone(),
+            order.nonce,
+            relayer_fee,
+        ));
+
+        Ok(())
+    }
+
+    /// Cancel the swap orders of `who` below `nonce`, which becomes the next nonce.
+    pub fn inner_cancel_swap_orders(who: &T::AccountId, nonce: u64) -> DispatchResult {
+        ensure!(nonce > Self::order_nonce(who), Error::<T>::InvalidOrderNonce);
+        OrderNonces::<T>::insert(who, nonce);
+
+        Self::deposit_event(Event::SwapOrdersCancelled(who.clone(), nonce));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+use frame_support::{assert_noop, assert_ok};
+
+use super::{AssetId, Error, MultiAssetsHandler, SwapOrder};
+use crate::mock::*;
+
+const DOT_ASSET_ID: AssetId = AssetId { chain_id: 200, asset_type: LOCAL, asset_index: 2 };
+
+const BTC_ASSET_ID: AssetId = AssetId { chain_id: 300, asset_type: RESERVED, asset_index: 3 };
+
+const ALICE: u128 = 1;
+const BOB: u128 = 2;
+const CHARLIE: u128 = 3;
+
+const DOT_UNIT: u128 = 1000_000_000_000_000;
+const BTC_UNIT: u128 = 1000_000_00;
+
+fn create_pair() {
+    assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, 10000 * DOT_UNIT));
+    assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, 10000 * BTC_UNIT));
+    assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+    assert_ok!(DexPallet::inner_add_liquidity(
+        &ALICE,
+        DOT_ASSET_ID,
+        BTC_ASSET_ID,
+        1000 * DOT_UNIT,
+        1000 * BTC_UNIT,
+        0,
+        0
+    ));
+}
+
+fn swap_order(nonce: u64, deadline: u64) -> SwapOrder<u128, u64, u64> {
+    SwapOrder {
+        owner: ALICE,
+        path: vec![DOT_ASSET_ID, BTC_ASSET_ID],
+        amount_in: DOT_UNIT,
+        amount_out_min: 0,
+        recipient: CHARLIE,
+        deadline: Deadline::BlockNumber(deadline),
+        nonce,
+        max_relayer_fee: DOT_UNIT / 100,
+    }
+}
+
+fn sign(owner: u128, order: &SwapOrder<u128, u64, u64>) -> TestSignature {
+    TestSignature(owner, DexPallet::swap_order_message(order))
+}
+
+#[test]
+fn execute_swap_order_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pair();
+
+        let order = swap_order(0, 100);
+        let alice_dot = <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &ALICE);
+
+        assert_ok!(DexPallet::execute_swap_order(
+            Origin::signed(BOB),
+            order.clone(),
+            sign(ALICE, &order),
+            DOT_UNIT / 1000
+        ));
+
+        assert_eq!(DexPallet::order_nonce(ALICE), 1);
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &BOB),
+            DOT_UNIT / 1000
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &ALICE),
+            alice_dot - DOT_UNIT - DOT_UNIT / 1000
+        );
+        assert!(<Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &CHARLIE) > 0);
+        assert!(System::events().iter().any(|record| record.event
+            == Event::Zenlink(crate::Event::SwapOrderExecuted(ALICE, BOB, 0, DOT_UNIT / 1000))));
+
+        // The order can't be replayed.
+        assert_noop!(
+            DexPallet::execute_swap_order(
+                Origin::signed(BOB),
+                order.clone(),
+                sign(ALICE, &order),
+                DOT_UNIT / 1000
+            ),
+            Error::<Test>::InvalidOrderNonce
+        );
+
+        let order = swap_order(1, 100);
+        assert_ok!(DexPallet::execute_swap_order(
+            Origin::signed(CHARLIE),
+            order.clone(),
+            sign(ALICE, &order),
+            0
+        ));
+        assert_eq!(DexPallet::order_nonce(ALICE), 2);
+    });
+}
+
+#[test]
+fn execute_swap_order_with_invalid_order_should_not_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(10);
+        create_pair();
+
+        let order = swap_order(0, 100);
+
+        assert_noop!(
+            DexPallet::execute_swap_order(Origin::signed(BOB), order.clone(), sign(BOB, &order), 0),
+            Error::<Test>::InvalidOrderSignature
+        );
+        assert_noop!(
+            DexPallet::execute_swap_order(
+                Origin::signed(BOB),
+                order.clone(),
+                sign(ALICE, &swap_order(0, 200)),
+                0
+            ),
+            Error::<Test>::InvalidOrderSignature
+        );
+        assert_noop!(
+            DexPallet::execute_swap_order(
+                Origin::signed(BOB),
+                order.clone(),
+                sign(ALICE, &order),
+                DOT_UNIT / 100 + 1
+            ),
+            Error::<Test>::ExcessiveRelayerFee
+        );
+        assert_noop!(
+            DexPallet::execute_swap_order(
+                Origin::signed(BOB),
+                swap_order(1, 100),
+                sign(ALICE, &swap_order(1, 100)),
+                0
+            ),
+            Error::<Test>::InvalidOrderNonce
+        );
+
+        let order = swap_order(0, 9);
+        assert_noop!(
+            DexPallet::execute_swap_order(
+                Origin::signed(BOB),
+                order.clone(),
+                sign(ALICE, &order),
+                0
+            ),
+            Error::<Test>::Deadline
+        );
+        assert_eq!(DexPallet::order_nonce(ALICE), 0);
+    });
+}
+
+#[test]
+fn cancel_swap_orders_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_pair();
+
+        assert_noop!(
+            DexPallet::cancel_swap_orders(Origin::signed(ALICE), 0),
+            Error::<Test>::InvalidOrderNonce
+        );
+        assert_ok!(DexPallet::cancel_swap_orders(Origin::signed(ALICE), 2));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event
+                == Event::Zenlink(crate::Event::SwapOrdersCancelled(ALICE, 2))));
+        assert_eq!(DexPallet::order_nonce(ALICE), 2);
+
+        // The orders below the next nonce are cancelled.
+        for nonce in 0..2 {
+            let order = swap_order(nonce, 100);
+            assert_noop!(
+                DexPallet::execute_swap_order(
+                    Origin::signed(BOB),
+                    order.clone(),
+                    sign(ALICE, &order),
+                    0
+                ),
+                Error::<Test>::InvalidOrderNonce
+            );
+        }
+        assert_noop!(
+            DexPallet::cancel_swap_orders(Origin::signed(ALICE), 1),
+            Error::<Test>::InvalidOrderNonce
+        );
+
+        let order = swap_order(2, 100);
+        assert_ok!(DexPallet::execute_swap_order(
+            Origin::signed(BOB),
+            order.clone(),
+            sign(ALICE, &order),
+            0
+        ));
+        assert_eq!(DexPallet::order_nonce(ALICE), 3);
+    });
+}
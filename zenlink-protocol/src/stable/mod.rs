@@ -0,0 +1,620 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+//! # Stable Swap Module
+//!
+//! ## Overview
+//!
+//! Built-in pools of pegged assets in Zenlink Protocol, the swap mechanism
+//! refers to the design of Curve StableSwap.
+
+use super::*;
+
+/// The precision of the amplification coefficient.
+pub const A_PRECISION: u128 = 100;
+/// The amplification coefficient must be less than it.
+pub const MAX_A: u128 = 1_000_000;
+/// The max multiple which the amplification coefficient can change by in a ramping.
+pub const MAX_A_CHANGE: u128 = 10;
+/// The max number of assets in a stable pool.
+pub const MAX_STABLE_ASSETS: usize = 8;
+/// The min number of blocks a ramping of the amplification coefficient lasts, a day of 12
+/// second blocks.
+pub const MIN_RAMP_BLOCKS: u32 = 7_200;
+/// The liquidity asset of stable pool `n` is the `LIQUIDITY` asset of index
+/// `STABLE_LP_INDEX_OFFSET + n`.
+pub const STABLE_LP_INDEX_OFFSET: u32 = 1 << 30;
+
+const MAX_ITERATIONS: u32 = 255;
+
+#[cfg(test)]
+mod tests;
+
+impl<T: Config> Pallet<T> {
+    /// The account ID of a stable pool account
+    pub fn stable_pool_account_id(pool_id: u32) -> T::AccountId {
+        T::PalletId::get().into_sub_account((b"stable", pool_id))
+    }
+
+    /// The liquidity asset ID of a stable pool
+    pub fn stable_lp_asset_id(pool_id: u32) -> AssetId {
+        AssetId {
+            chain_id: T::SelfParaId::get(),
+            asset_type: LIQUIDITY,
+            asset_index: STABLE_LP_INDEX_OFFSET + pool_id,
+        }
+    }
+
+    /// The stable pool of the liquidity asset index, if it is in the range of stable pools.
+    pub(crate) fn stable_pool_id_of_lp(asset_index: u32) -> Option<u32> {
+        asset_index.checked_sub(STABLE_LP_INDEX_OFFSET).filter(|id| *id < STABLE_LP_INDEX_OFFSET)
+    }
+
+    pub fn inner_create_stable_pool(
+        assets: Vec<AssetId>,
+        precisions: Vec<AssetBalance>,
+        a: u128,
+        fee_numerator: u32,
+        fee_denominator: u32,
+    ) -> DispatchResult {
+        ensure!(
+            assets.len() >= 2
+                && assets.len() <= MAX_STABLE_ASSETS
+                && precisions.len() == assets.len(),
+            Error::<T>::InvalidPoolAssets
+        );
+        ensure!(assets.iter().all(|id| id.is_support()), Error::<T>::UnsupportedAssetType);
+        ensure!(
+            assets.iter().all(|id| T::MultiAssetsHandler::is_exists(*id)),
+            Error::<T>::AssetNotExists
+        );
+        let mut sorted_assets = assets.clone();
+        sorted_assets.sort();
+        sorted_assets.dedup();
+        ensure!(
+            sorted_assets.len() == assets.len() && precisions.iter().all(|p| !p.is_zero()),
+            Error::<T>::InvalidPoolAssets
+        );
+        ensure!(a > 0 && a < MAX_A, Error::<T>::InvalidAmplification);
+        ensure!(fee_numerator < fee_denominator, Error::<T>::InvalidExchangeFee);
+
+        let pool_id = Self::next_stable_pool_id();
+        ensure!(pool_id < STABLE_LP_INDEX_OFFSET, Error::<T>::Overflow);
+
+        let now = frame_system::Pallet::<T>::block_number();
+        StablePools::<T>::insert(
+            pool_id,
+            StablePool {
+                pool_account: Self::stable_pool_account_id(pool_id),
+                assets: assets.clone(),
+                precisions,
+                reserves: vec![Zero::zero(); assets.len()],
+                total_supply: Zero::zero(),
+                fee: (fee_numerator, fee_denominator),
+                initial_a: a * A_PRECISION,
+                future_a: a * A_PRECISION,
+                initial_a_block: now,
+                future_a_block: now,
+            },
+        );
+        NextStablePoolId::<T>::put(pool_id + 1);
+
+        Self::note_stable_pool_of_assets(pool_id, &assets);
+
+        Self::deposit_event(Event::StablePoolCreated(pool_id, assets));
+
+        Ok(())
+    }
+
+    /// Whether the stable pool has liquidity to swap through.
+    pub(crate) fn is_stable_pool_liquid(pool_id: u32) -> bool {
+        Self::stable_pool(pool_id).map_or(false, |pool| !pool.total_supply.is_zero())
+    }
+
+    /// Record the stable pool for the swaps between its assets, unless the pool recorded for
+    /// two of them has liquidity.
+    fn note_stable_pool_of_assets(pool_id: u32, assets: &[AssetId]) {
+        for (i, asset_0) in assets.iter().enumerate() {
+            for asset_1 in assets.iter().skip(i + 1) {
+                let pair = Self::sort_asset_id(*asset_0, *asset_1);
+                StablePoolOfAssets::<T>::mutate(pair, |recorded| {
+                    if !recorded.map_or(false, Self::is_stable_pool_liquid) {
+                        *recorded = Some(pool_id);
+                    }
+                });
+            }
+        }
+    }
+
+    pub fn inner_add_stable_liquidity(
+        who: &T::AccountId,
+        pool_id: u32,
+        amounts: Vec<AssetBalance>,
+        min_mint_amount: AssetBalance,
+    ) -> DispatchResult {
+        StablePools::<T>::try_mutate(pool_id, |pool| {
+            let pool = pool.as_mut().ok_or(Error::<T>::PoolNotExists)?;
+            ensure!(amounts.len() == pool.assets.len(), Error::<T>::InvalidPoolAssets);
+
+            let amp = U256::from(Self::stable_a(pool));
+            let n = pool.assets.len();
+
+            let d_0 = if pool.total_supply.is_zero() {
+                U256::zero()
+            } else {
+                Self::get_d(&Self::stable_xp(&pool.reserves, &pool.precisions), amp)
+                    .ok_or(Error::<T>::Overflow)?
+            };
+
+            let mut new_reserves = Vec::with_capacity(n);
+            for (i, amount) in amounts.iter().enumerate() {
+                if pool.total_supply.is_zero() {
+                    ensure!(!amount.is_zero(), Error::<T>::InsufficientLiquidity);
+                }
+                new_reserves
+                    .push(pool.reserves[i].checked_add(*amount).ok_or(Error::<T>::Overflow)?);
+            }
+
+            let d_1 = Self::get_d(&Self::stable_xp(&new_reserves, &pool.precisions), amp)
+                .ok_or(Error::<T>::Overflow)?;
+            ensure!(d_1 > d_0, Error::<T>::InsufficientLiquidity);
+
+            let mint_liquidity = if pool.total_supply.is_zero() {
+                d_1
+            } else {
+                // The imbalanced part of the deposit is charged the exchange fee.
+                let (fee_numerator, fee_denominator) = pool.fee;
+                let fee_numerator = U256::from(fee_numerator) * U256::from(n);
+                let fee_denominator = U256::from(fee_denominator) * U256::from(4 * (n - 1));
+
+                let mut adjusted_reserves = Vec::with_capacity(n);
+                for (i, new_reserve) in new_reserves.iter().enumerate() {
+                    let ideal_reserve = d_1 * U256::from(pool.reserves[i]) / d_0;
+                    let new_reserve = U256::from(*new_reserve);
+                    let difference = if ideal_reserve > new_reserve {
+                        ideal_reserve - new_reserve
+                    } else {
+                        new_reserve - ideal_reserve
+                    };
+                    let fee = fee_numerator * difference / fee_denominator;
+                    adjusted_reserves.push(
+                        TryInto::<AssetBalance>::try_into(new_reserve.saturating_sub(fee))
+                            .map_err(|_| Error::<T>::Overflow)?,
+                    );
+                }
+
+                let d_2 = Self::get_d(&Self::stable_xp(&adjusted_reserves, &pool.precisions), amp)
+                    .ok_or(Error::<T>::Overflow)?;
+
+                U256::from(pool.total_supply).saturating_mul(d_2.saturating_sub(d_0)) / d_0
+            };
+            let mint_liquidity = TryInto::<AssetBalance>::try_into(mint_liquidity)
+                .map_err(|_| Error::<T>::Overflow)?;
+
+            ensure!(mint_liquidity > Zero::zero(), Error::<T>::InsufficientLiquidity);
+            ensure!(mint_liquidity >= min_mint_amount, Error::<T>::InsufficientTargetAmount);
+
+            for (i, amount) in amounts.iter().enumerate() {
+                if !amount.is_zero() {
+                    T::MultiAssetsHandler::transfer(
+                        pool.assets[i],
+                        who,
+                        &pool.pool_account,
+                        *amount,
+                    )?;
+                }
+            }
+
+            // The first liquidity of the pool takes over the swaps from the recorded pools
+            // without liquidity.
+            if pool.total_supply.is_zero() {
+                Self::note_stable_pool_of_assets(pool_id, &pool.assets);
+            }
+
+            pool.reserves = new_reserves;
+            pool.total_supply =
+                pool.total_supply.checked_add(mint_liquidity).ok_or(Error::<T>::Overflow)?;
+            StableLedger::<T>::try_mutate((pool_id, who), |liquidity| -> DispatchResult {
+                *liquidity = liquidity.checked_add(mint_liquidity).ok_or(Error::<T>::Overflow)?;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::StableLiquidityAdded(
+                who.clone(),
+                pool_id,
+                amounts,
+                mint_liquidity,
+            ));
+
+            Ok(())
+        })
+    }
+
+    pub fn inner_remove_stable_liquidity(
+        who: &T::AccountId,
+        pool_id: u32,
+        remove_liquidity: AssetBalance,
+        min_amounts: Vec<AssetBalance>,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        ensure!(
+            Self::stable_ledger((pool_id, who)) >= remove_liquidity,
+            Error::<T>::InsufficientLiquidity
+        );
+
+        StablePools::<T>::try_mutate(pool_id, |pool| {
+            let pool = pool.as_mut().ok_or(Error::<T>::PoolNotExists)?;
+            ensure!(min_amounts.len() == pool.assets.len(), Error::<T>::InvalidPoolAssets);
+
+            let mut amounts = Vec::with_capacity(pool.assets.len());
+            for (i, reserve) in pool.reserves.iter().enumerate() {
+                let amount =
+                    Self::calculate_share_amount(remove_liquidity, pool.total_supply, *reserve);
+                ensure!(amount >= min_amounts[i], Error::<T>::InsufficientTargetAmount);
+                amounts.push(amount);
+            }
+
+            pool.total_supply = pool
+                .total_supply
+                .checked_sub(remove_liquidity)
+                .ok_or(Error::<T>::InsufficientLiquidity)?;
+            StableLedger::<T>::mutate((pool_id, who), |liquidity| {
+                *liquidity = liquidity.saturating_sub(remove_liquidity)
+            });
+
+            for (i, amount) in amounts.iter().enumerate() {
+                if !amount.is_zero() {
+                    T::MultiAssetsHandler::transfer(
+                        pool.assets[i],
+                        &pool.pool_account,
+                        recipient,
+                        *amount,
+                    )?;
+                }
+                pool.reserves[i] = pool.reserves[i].saturating_sub(*amount);
+            }
+
+            Self::deposit_event(Event::StableLiquidityRemoved(
+                who.clone(),
+                recipient.clone(),
+                pool_id,
+                amounts,
+                remove_liquidity,
+            ));
+
+            Ok(())
+        })
+    }
+
+    pub fn inner_ramp_a(
+        pool_id: u32,
+        future_a: u128,
+        future_block: T::BlockNumber,
+    ) -> DispatchResult {
+        StablePools::<T>::try_mutate(pool_id, |pool| {
+            let pool = pool.as_mut().ok_or(Error::<T>::PoolNotExists)?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                future_block >= now.saturating_add(MIN_RAMP_BLOCKS.into()),
+                Error::<T>::InvalidAmplification
+            );
+            ensure!(future_a > 0 && future_a < MAX_A, Error::<T>::InvalidAmplification);
+
+            let initial_a = Self::stable_a(pool);
+            let future_a = future_a * A_PRECISION;
+            ensure!(
+                future_a <= initial_a.saturating_mul(MAX_A_CHANGE)
+                    && future_a.saturating_mul(MAX_A_CHANGE) >= initial_a,
+                Error::<T>::InvalidAmplification
+            );
+
+            pool.initial_a = initial_a;
+            pool.future_a = future_a;
+            pool.initial_a_block = now;
+            pool.future_a_block = future_block;
+
+            Self::deposit_event(Event::RampA(pool_id, initial_a, future_a, now, future_block));
+
+            Ok(())
+        })
+    }
+
+    pub fn inner_stop_ramp_a(pool_id: u32) -> DispatchResult {
+        StablePools::<T>::try_mutate(pool_id, |pool| {
+            let pool = pool.as_mut().ok_or(Error::<T>::PoolNotExists)?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let current_a = Self::stable_a(pool);
+
+            pool.initial_a = current_a;
+            pool.future_a = current_a;
+            pool.initial_a_block = now;
+            pool.future_a_block = now;
+
+            Self::deposit_event(Event::StopRampA(pool_id, current_a));
+
+            Ok(())
+        })
+    }
+
+    /// The amplification coefficient of the pool multiplied by `A_PRECISION`, which moves
+    /// linearly from `initial_a` to `future_a` during the ramping.
+    pub fn stable_a(pool: &StablePool<T::AccountId, T::BlockNumber>) -> u128 {
+        let now = frame_system::Pallet::<T>::block_number();
+        if now >= pool.future_a_block {
+            return pool.future_a;
+        }
+
+        let elapsed = now.saturating_sub(pool.initial_a_block).saturated_into::<u128>();
+        let duration =
+            pool.future_a_block.saturating_sub(pool.initial_a_block).saturated_into::<u128>();
+
+        if pool.future_a > pool.initial_a {
+            pool.initial_a + (pool.future_a - pool.initial_a) * elapsed / duration
+        } else {
+            pool.initial_a - (pool.initial_a - pool.future_a) * elapsed / duration
+        }
+    }
+
+    /// The amount of `asset_out` got by swapping `amount_in` of `asset_in` in the pool.
+    pub fn stable_amount_out(
+        pool_id: u32,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: AssetBalance,
+    ) -> AssetBalance {
+        Self::stable_pool(pool_id)
+            .and_then(|pool| {
+                let i = pool.assets.iter().position(|id| *id == asset_in)?;
+                let j = pool.assets.iter().position(|id| *id == asset_out)?;
+                let amp = U256::from(Self::stable_a(&pool));
+                let xp = Self::stable_xp(&pool.reserves, &pool.precisions);
+
+                let d = Self::get_d(&xp, amp)?;
+                let x = xp[i].checked_add(
+                    U256::from(amount_in).checked_mul(U256::from(pool.precisions[i]))?,
+                )?;
+                let y = Self::get_y(i, j, x, &xp, amp, d)?;
+                let dy = xp[j].checked_sub(y)?.checked_sub(U256::one())?;
+
+                let (fee_numerator, fee_denominator) = pool.fee;
+                let fee = dy * U256::from(fee_numerator) / U256::from(fee_denominator);
+
+                TryInto::<AssetBalance>::try_into((dy - fee) / U256::from(pool.precisions[j])).ok()
+            })
+            .unwrap_or_else(Zero::zero)
+    }
+
+    /// The amount of `asset_in` needed to get `amount_out` of `asset_out` from the pool.
+    pub fn stable_amount_in(
+        pool_id: u32,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_out: AssetBalance,
+    ) -> AssetBalance {
+        Self::stable_pool(pool_id)
+            .and_then(|pool| {
+                let i = pool.assets.iter().position(|id| *id == asset_in)?;
+                let j = pool.assets.iter().position(|id| *id == asset_out)?;
+                let amp = U256::from(Self::stable_a(&pool));
+                let xp = Self::stable_xp(&pool.reserves, &pool.precisions);
+
+                let (fee_numerator, fee_denominator) = pool.fee;
+                let dy = U256::from(amount_out)
+                    .checked_mul(U256::from(pool.precisions[j]))?
+                    .checked_mul(U256::from(fee_denominator))?
+                    / U256::from(fee_denominator - fee_numerator);
+
+                let d = Self::get_d(&xp, amp)?;
+                let y = xp[j].checked_sub(dy)?.checked_sub(U256::one())?;
+                let x = Self::get_y(j, i, y, &xp, amp, d)?;
+                let dx = x.checked_sub(xp[i])? / U256::from(pool.precisions[i]) + U256::one();
+
+                TryInto::<AssetBalance>::try_into(dx).ok()
+            })
+            .unwrap_or_else(Zero::zero)
+    }
+
+    /// Send `amount_out` of `asset_out` to the recipient, `amount_in` of `asset_in` must have
+    /// been transferred to the pool account.
+    pub(crate) fn stable_swap(
+        pool_id: u32,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: AssetBalance,
+        amount_out: AssetBalance,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        StablePools::<T>::try_mutate(pool_id, |pool| {
+            let pool = pool.as_mut().ok_or(Error::<T>::PoolNotExists)?;
+            let i =
+                pool.assets.iter().position(|id| *id == asset_in).ok_or(Error::<T>::InvalidPath)?;
+            let j = pool
+                .assets
+                .iter()
+                .position(|id| *id == asset_out)
+                .ok_or(Error::<T>::InvalidPath)?;
+
+            pool.reserves[i] =
+                pool.reserves[i].checked_add(amount_in).ok_or(Error::<T>::Overflow)?;
+            pool.reserves[j] = pool.reserves[j]
+                .checked_sub(amount_out)
+                .ok_or(Error::<T>::InsufficientPairReserve)?;
+
+            T::MultiAssetsHandler::transfer(asset_out, &pool.pool_account, recipient, amount_out)
+        })
+    }
+
+    fn stable_xp(reserves: &[AssetBalance], precisions: &[AssetBalance]) -> Vec<U256> {
+        reserves
+            .iter()
+            .zip(precisions.iter())
+            .map(|(reserve, precision)| U256::from(*reserve).saturating_mul(U256::from(*precision)))
+            .collect()
+    }
+
+    /// The StableSwap invariant D of the normalized balances, by Newton's method.
+    pub(crate) fn get_d(xp: &[U256], amp: U256) -> Option<U256> {
+        let n = U256::from(xp.len());
+        let sum = xp.iter().try_fold(U256::zero(), |sum, x| sum.checked_add(*x))?;
+        if sum.is_zero() {
+            return Some(U256::zero());
+        }
+
+        let a_precision = U256::from(A_PRECISION);
+        let ann = amp.checked_mul(n)?;
+        let mut d = sum;
+        for _ in 0..MAX_ITERATIONS {
+            let mut d_p = d;
+            for x in xp.iter() {
+                d_p = d_p.checked_mul(d)?.checked_div(x.checked_mul(n)?)?;
+            }
+            let d_prev = d;
+
+            let numerator = ann
+                .checked_mul(sum)?
+                .checked_div(a_precision)?
+                .checked_add(d_p.checked_mul(n)?)?
+                .checked_mul(d)?;
+            let denominator = ann
+                .checked_sub(a_precision)?
+                .checked_mul(d)?
+                .checked_div(a_precision)?
+                .checked_add(n.checked_add(U256::one())?.checked_mul(d_p)?)?;
+            d = numerator.checked_div(denominator)?;
+
+            if d > d_prev {
+                if d - d_prev <= U256::one() {
+                    return Some(d);
+                }
+            } else if d_prev - d <= U256::one() {
+                return Some(d);
+            }
+        }
+
+        None
+    }
+
+    /// The normalized balance of asset `j` which keeps the invariant `d` when the balance of
+    /// asset `i` is `x`.
+    pub(crate) fn get_y(
+        i: usize,
+        j: usize,
+        x: U256,
+        xp: &[U256],
+        amp: U256,
+        d: U256,
+    ) -> Option<U256> {
+        if i == j || i >= xp.len() || j >= xp.len() {
+            return None;
+        }
+
+        let n = U256::from(xp.len());
+        let a_precision = U256::from(A_PRECISION);
+        let ann = amp.checked_mul(n)?;
+
+        let mut c = d;
+        let mut sum = U256::zero();
+        for (k, x_k) in xp.iter().enumerate() {
+            let x_k = if k == i {
+                x
+            } else if k != j {
+                *x_k
+            } else {
+                continue;
+            };
+            sum = sum.checked_add(x_k)?;
+            c = c.checked_mul(d)?.checked_div(x_k.checked_mul(n)?)?;
+        }
+        c = c.checked_mul(d)?.checked_mul(a_precision)?.checked_div(ann.checked_mul(n)?)?;
+        let b = sum.checked_add(d.checked_mul(a_precision)?.checked_div(ann)?)?;
+
+        let mut y = d;
+        for _ in 0..MAX_ITERATIONS {
+            let y_prev = y;
+            y = y
+                .checked_mul(y)?
+                .checked_add(c)?
+                .checked_div(y.checked_mul(U256::from(2))?.checked_add(b)?.checked_sub(d)?)?;
+
+            if y > y_prev {
+                if y - y_prev <= U256::one() {
+                    return Some(y);
+                }
+            } else if y_prev - y <= U256::one() {
+                return Some(y);
+            }
+        }
+
+        None
+    }
+
+    // Liquidity asset of the stable pools
+
+    pub(crate) fn stable_lp_transfer(
+        id: AssetId,
+        pool_id: u32,
+        owner: &T::AccountId,
+        target: &T::AccountId,
+        amount: AssetBalance,
+    ) -> DispatchResult {
+        ensure!(StablePools::<T>::contains_key(pool_id), Error::<T>::AssetNotExists);
+
+        let owner_balance = Self::stable_ledger((pool_id, owner));
+        ensure!(owner_balance >= amount, Error::<T>::InsufficientAssetBalance);
+
+        StableLedger::<T>::insert((pool_id, owner), owner_balance.saturating_sub(amount));
+        StableLedger::<T>::mutate((pool_id, target), |balance| {
+            *balance = balance.saturating_add(amount)
+        });
+
+        Self::deposit_event(Event::Transferred(id, owner.clone(), target.clone(), amount));
+
+        Ok(())
+    }
+
+    pub(crate) fn stable_lp_mint(
+        id: AssetId,
+        pool_id: u32,
+        owner: &T::AccountId,
+        amount: AssetBalance,
+    ) -> DispatchResult {
+        StablePools::<T>::try_mutate(pool_id, |pool| -> DispatchResult {
+            let pool = pool.as_mut().ok_or(Error::<T>::AssetNotExists)?;
+
+            pool.total_supply =
+                pool.total_supply.checked_add(amount).ok_or(Error::<T>::Overflow)?;
+            StableLedger::<T>::try_mutate((pool_id, owner), |balance| -> DispatchResult {
+                *balance = balance.checked_add(amount).ok_or(Error::<T>::Overflow)?;
+                Ok(())
+            })
+        })?;
+
+        Self::deposit_event(Event::Minted(id, owner.clone(), amount));
+
+        Ok(())
+    }
+
+    pub(crate) fn stable_lp_burn(
+        id: AssetId,
+        pool_id: u32,
+        owner: &T::AccountId,
+        amount: AssetBalance,
+    ) -> DispatchResult {
+        StablePools::<T>::try_mutate(pool_id, |pool| -> DispatchResult {
+            let pool = pool.as_mut().ok_or(Error::<T>::AssetNotExists)?;
+
+            pool.total_supply =
+                pool.total_supply.checked_sub(amount).ok_or(Error::<T>::InsufficientLiquidity)?;
+            StableLedger::<T>::try_mutate((pool_id, owner), |balance| -> DispatchResult {
+                *balance = balance.checked_sub(amount).ok_or(Error::<T>::InsufficientLiquidity)?;
+                Ok(())
+            })
+        })?;
+
+        Self::deposit_event(Event::Burned(id, owner.clone(), amount));
+
+        Ok(())
+    }
+}
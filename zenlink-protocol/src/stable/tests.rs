@@ -0,0 +1,476 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::DispatchError;
+
+use super::{AssetId, Error, Hop, MultiAssetsHandler, A_PRECISION, MIN_RAMP_BLOCKS};
+use crate::mock::*;
+
+const DOT_ASSET_ID: AssetId = AssetId { chain_id: 200, asset_type: LOCAL, asset_index: 2 };
+
+const VDOT_ASSET_ID: AssetId = AssetId { chain_id: 200, asset_type: LOCAL, asset_index: 3 };
+
+const BTC_ASSET_ID: AssetId = AssetId { chain_id: 300, asset_type: RESERVED, asset_index: 3 };
+
+const ALICE: u128 = 1;
+const BOB: u128 = 2;
+
+const DOT_UNIT: u128 = 1000_000_000_000_000;
+const BTC_UNIT: u128 = 1000_000_00;
+
+fn create_dot_vdot_pool() {
+    assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX / 2));
+    assert_ok!(DexPallet::foreign_mint(VDOT_ASSET_ID, &ALICE, u128::MAX / 2));
+    assert_ok!(DexPallet::create_stable_pool(
+        Origin::root(),
+        vec![DOT_ASSET_ID, VDOT_ASSET_ID],
+        vec![1, 1],
+        100,
+        4,
+        10000
+    ));
+    assert_ok!(DexPallet::add_stable_liquidity(
+        Origin::signed(ALICE),
+        0,
+        vec![1000 * DOT_UNIT, 1000 * DOT_UNIT],
+        0,
+        Deadline::BlockNumber(100)
+    ));
+}
+
+#[test]
+fn create_stable_pool_should_work() {
+    new_test_ext().execute_with(|| {
+        create_dot_vdot_pool();
+
+        let pool = DexPallet::stable_pool(0).unwrap();
+        assert_eq!(pool.assets, vec![DOT_ASSET_ID, VDOT_ASSET_ID]);
+        assert_eq!(pool.reserves, vec![1000 * DOT_UNIT, 1000 * DOT_UNIT]);
+        assert_eq!(pool.total_supply, 2000 * DOT_UNIT);
+        assert_eq!(DexPallet::stable_a(&pool), 100 * A_PRECISION);
+        assert_eq!(DexPallet::next_stable_pool_id(), 1);
+        assert_eq!(DexPallet::stable_pool_of_assets((DOT_ASSET_ID, VDOT_ASSET_ID)), Some(0));
+
+        let lp_asset_id = DexPallet::stable_lp_asset_id(0);
+        assert!(<Test as Config>::MultiAssetsHandler::is_exists(lp_asset_id));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::total_supply(lp_asset_id),
+            2000 * DOT_UNIT
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(lp_asset_id, &ALICE),
+            2000 * DOT_UNIT
+        );
+
+        assert_ok!(<Test as Config>::MultiAssetsHandler::transfer(
+            lp_asset_id,
+            &ALICE,
+            &BOB,
+            DOT_UNIT
+        ));
+        assert_eq!(DexPallet::stable_ledger((0, BOB)), DOT_UNIT);
+    });
+}
+
+#[test]
+fn create_stable_pool_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, DOT_UNIT));
+        assert_ok!(DexPallet::foreign_mint(VDOT_ASSET_ID, &ALICE, DOT_UNIT));
+
+        assert_noop!(
+            DexPallet::create_stable_pool(
+                Origin::signed(ALICE),
+                vec![DOT_ASSET_ID, VDOT_ASSET_ID],
+                vec![1, 1],
+                100,
+                4,
+                10000
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            DexPallet::create_stable_pool(
+                Origin::root(),
+                vec![DOT_ASSET_ID],
+                vec![1],
+                100,
+                4,
+                10000
+            ),
+            Error::<Test>::InvalidPoolAssets
+        );
+        assert_noop!(
+            DexPallet::create_stable_pool(
+                Origin::root(),
+                vec![DOT_ASSET_ID, DOT_ASSET_ID],
+                vec![1, 1],
+                100,
+                4,
+                10000
+            ),
+            Error::<Test>::InvalidPoolAssets
+        );
+        assert_noop!(
+            DexPallet::create_stable_pool(
+                Origin::root(),
+                vec![DOT_ASSET_ID, BTC_ASSET_ID],
+                vec![1, 1],
+                100,
+                4,
+                10000
+            ),
+            Error::<Test>::AssetNotExists
+        );
+        assert_noop!(
+            DexPallet::create_stable_pool(
+                Origin::root(),
+                vec![DOT_ASSET_ID, VDOT_ASSET_ID],
+                vec![1, 1],
+                0,
+                4,
+                10000
+            ),
+            Error::<Test>::InvalidAmplification
+        );
+        assert_noop!(
+            DexPallet::create_stable_pool(
+                Origin::root(),
+                vec![DOT_ASSET_ID, VDOT_ASSET_ID],
+                vec![1, 1],
+                100,
+                10000,
+                10000
+            ),
+            Error::<Test>::InvalidExchangeFee
+        );
+    });
+}
+
+#[test]
+fn stable_liquidity_should_work() {
+    new_test_ext().execute_with(|| {
+        create_dot_vdot_pool();
+
+        // The imbalanced part of the deposit is charged the exchange fee.
+        assert_ok!(DexPallet::add_stable_liquidity(
+            Origin::signed(ALICE),
+            0,
+            vec![100 * DOT_UNIT, 0],
+            0,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(DexPallet::stable_ledger((0, ALICE)), 2000 * DOT_UNIT + 99956373206996579);
+
+        assert_noop!(
+            DexPallet::add_stable_liquidity(
+                Origin::signed(ALICE),
+                0,
+                vec![100 * DOT_UNIT, 100 * DOT_UNIT],
+                200 * DOT_UNIT + 1,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientTargetAmount
+        );
+
+        assert_ok!(DexPallet::remove_stable_liquidity(
+            Origin::signed(ALICE),
+            0,
+            1000 * DOT_UNIT,
+            vec![0, 0],
+            BOB,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &BOB),
+            523820406002106486
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(VDOT_ASSET_ID, &BOB),
+            476200369092824078
+        );
+        let pool = DexPallet::stable_pool(0).unwrap();
+        assert_eq!(
+            pool.reserves,
+            vec![1100 * DOT_UNIT - 523820406002106486, 1000 * DOT_UNIT - 476200369092824078]
+        );
+        assert_eq!(pool.total_supply, 1000 * DOT_UNIT + 99956373206996579);
+
+        assert_noop!(
+            DexPallet::remove_stable_liquidity(
+                Origin::signed(BOB),
+                0,
+                1,
+                vec![0, 0],
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientLiquidity
+        );
+    });
+}
+
+#[test]
+fn stable_swap_should_work() {
+    new_test_ext().execute_with(|| {
+        create_dot_vdot_pool();
+
+        let path = vec![DOT_ASSET_ID, VDOT_ASSET_ID];
+        assert_eq!(DexPallet::supply_out_amount(10 * DOT_UNIT, path.clone()), 9995010298009605);
+        assert_eq!(DexPallet::desired_in_amount(10 * DOT_UNIT, path.clone()), 10004992687614731);
+
+        assert_ok!(DexPallet::inner_swap_exact_assets_for_assets(
+            &ALICE,
+            10 * DOT_UNIT,
+            0,
+            &path,
+            &BOB
+        ));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(VDOT_ASSET_ID, &BOB),
+            9995010298009605
+        );
+        assert_eq!(
+            DexPallet::stable_pool(0).unwrap().reserves,
+            vec![1010 * DOT_UNIT, 1000 * DOT_UNIT - 9995010298009605]
+        );
+
+        assert_eq!(
+            DexPallet::supply_out_amount(10 * DOT_UNIT, vec![VDOT_ASSET_ID, DOT_ASSET_ID]),
+            9996989304062536
+        );
+    });
+}
+
+#[test]
+fn swap_through_stable_pool_and_pair_should_work() {
+    new_test_ext().execute_with(|| {
+        create_dot_vdot_pool();
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), VDOT_ASSET_ID, BTC_ASSET_ID));
+        assert_ok!(DexPallet::add_liquidity(
+            Origin::signed(ALICE),
+            VDOT_ASSET_ID,
+            BTC_ASSET_ID,
+            1000 * DOT_UNIT,
+            1000 * BTC_UNIT,
+            0,
+            0,
+            Deadline::BlockNumber(100)
+        ));
+
+        let path = vec![DOT_ASSET_ID, VDOT_ASSET_ID, BTC_ASSET_ID];
+        assert_eq!(DexPallet::desired_in_amount(BTC_UNIT, path.clone()), 1004424794757974);
+
+        assert_ok!(DexPallet::inner_swap_exact_assets_for_assets(
+            &ALICE,
+            10 * DOT_UNIT,
+            0,
+            &path,
+            &BOB
+        ));
+        assert_eq!(<Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &BOB), 986670331);
+        assert_eq!(
+            DexPallet::get_reserves(VDOT_ASSET_ID, BTC_ASSET_ID),
+            (1000 * DOT_UNIT + 9995010298009605, 1000 * BTC_UNIT - 986670331)
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(
+                VDOT_ASSET_ID,
+                &DexPallet::stable_pool_account_id(0)
+            ),
+            1000 * DOT_UNIT - 9995010298009605
+        );
+    });
+}
+
+#[test]
+fn ramp_a_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_dot_vdot_pool();
+        let future_block = 1 + MIN_RAMP_BLOCKS as u64;
+
+        assert_noop!(
+            DexPallet::ramp_a(Origin::signed(ALICE), 0, 200, future_block),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            DexPallet::ramp_a(Origin::root(), 0, 1001, future_block),
+            Error::<Test>::InvalidAmplification
+        );
+        assert_noop!(
+            DexPallet::ramp_a(Origin::root(), 0, 200, 1),
+            Error::<Test>::InvalidAmplification
+        );
+        // The ramping lasts at least `MIN_RAMP_BLOCKS` blocks.
+        assert_noop!(
+            DexPallet::ramp_a(Origin::root(), 0, 200, future_block - 1),
+            Error::<Test>::InvalidAmplification
+        );
+        assert_noop!(
+            DexPallet::ramp_a(Origin::root(), 1, 200, future_block),
+            Error::<Test>::PoolNotExists
+        );
+
+        assert_ok!(DexPallet::ramp_a(Origin::root(), 0, 200, future_block));
+
+        System::set_block_number(1 + MIN_RAMP_BLOCKS as u64 / 2);
+        assert_eq!(DexPallet::stable_a(&DexPallet::stable_pool(0).unwrap()), 150 * A_PRECISION);
+
+        assert_ok!(DexPallet::stop_ramp_a(Origin::root(), 0));
+
+        System::set_block_number(1 + 2 * MIN_RAMP_BLOCKS as u64);
+        assert_eq!(DexPallet::stable_a(&DexPallet::stable_pool(0).unwrap()), 150 * A_PRECISION);
+    });
+}
+
+#[test]
+fn stable_pool_should_not_reroute_pair_path() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::foreign_mint(VDOT_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, VDOT_ASSET_ID));
+        assert_ok!(DexPallet::inner_add_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            VDOT_ASSET_ID,
+            1000 * DOT_UNIT,
+            1000 * DOT_UNIT,
+            0,
+            0
+        ));
+
+        for _ in 0..2 {
+            assert_ok!(DexPallet::create_stable_pool(
+                Origin::root(),
+                vec![DOT_ASSET_ID, VDOT_ASSET_ID],
+                vec![1, 1],
+                100,
+                4,
+                10000
+            ));
+        }
+        assert_eq!(DexPallet::get_hop(DOT_ASSET_ID, VDOT_ASSET_ID), Some(Hop::Pair));
+
+        // The first pool with liquidity is recorded for the assets.
+        assert_ok!(DexPallet::add_stable_liquidity(
+            Origin::signed(ALICE),
+            1,
+            vec![1000 * DOT_UNIT, 1000 * DOT_UNIT],
+            0,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(DexPallet::stable_pool_of_assets((DOT_ASSET_ID, VDOT_ASSET_ID)), Some(1));
+        assert_ok!(DexPallet::add_stable_liquidity(
+            Origin::signed(ALICE),
+            0,
+            vec![1000 * DOT_UNIT, 1000 * DOT_UNIT],
+            0,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(DexPallet::stable_pool_of_assets((DOT_ASSET_ID, VDOT_ASSET_ID)), Some(1));
+
+        // The liquid pool doesn't take over the path of the trading pair.
+        assert_eq!(DexPallet::get_hop(VDOT_ASSET_ID, DOT_ASSET_ID), Some(Hop::Pair));
+        assert_ok!(DexPallet::inner_swap_exact_assets_for_assets(
+            &ALICE,
+            DOT_UNIT,
+            0,
+            &[DOT_ASSET_ID, VDOT_ASSET_ID],
+            &BOB
+        ));
+        assert_eq!(DexPallet::get_reserves(DOT_ASSET_ID, VDOT_ASSET_ID).0, 1001 * DOT_UNIT);
+
+        // The path keeps the protections of the pair.
+        assert_ok!(DexPallet::set_pair_paused(Origin::root(), DOT_ASSET_ID, VDOT_ASSET_ID, true));
+        assert_noop!(
+            DexPallet::inner_swap_exact_assets_for_assets(
+                &ALICE,
+                DOT_UNIT,
+                0,
+                &[DOT_ASSET_ID, VDOT_ASSET_ID],
+                &BOB
+            ),
+            Error::<Test>::Paused
+        );
+
+        // The pool is reached by naming the hop.
+        let pool_reserve = DexPallet::stable_pool(1).unwrap().reserves[0];
+        assert_ok!(DexPallet::swap_exact_assets_for_assets_through_hops(
+            Origin::signed(ALICE),
+            DOT_UNIT,
+            0,
+            vec![DOT_ASSET_ID, VDOT_ASSET_ID],
+            vec![Hop::Stable(1)],
+            BOB,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(DexPallet::get_reserves(DOT_ASSET_ID, VDOT_ASSET_ID).0, 1001 * DOT_UNIT);
+        assert!(DexPallet::stable_pool(1).unwrap().reserves[0] > pool_reserve);
+    });
+}
+
+#[test]
+fn swap_through_hops_should_check_hops() {
+    new_test_ext().execute_with(|| {
+        create_dot_vdot_pool();
+        let path = vec![DOT_ASSET_ID, VDOT_ASSET_ID];
+
+        assert_noop!(
+            DexPallet::inner_swap_exact_assets_for_assets_through_hops(
+                &ALICE,
+                DOT_UNIT,
+                0,
+                &path,
+                &[Hop::Stable(0), Hop::Stable(0)],
+                &BOB
+            ),
+            Error::<Test>::InvalidPath
+        );
+        assert_noop!(
+            DexPallet::inner_swap_exact_assets_for_assets_through_hops(
+                &ALICE,
+                DOT_UNIT,
+                0,
+                &path,
+                &[Hop::Stable(1)],
+                &BOB
+            ),
+            Error::<Test>::PoolNotExists
+        );
+        assert_noop!(
+            DexPallet::inner_swap_exact_assets_for_assets_through_hops(
+                &ALICE,
+                DOT_UNIT,
+                0,
+                &path,
+                &[Hop::Pair],
+                &BOB
+            ),
+            Error::<Test>::PairNotExists
+        );
+        assert_noop!(
+            DexPallet::inner_swap_exact_assets_for_assets_through_hops(
+                &ALICE,
+                DOT_UNIT,
+                0,
+                &[DOT_ASSET_ID, BTC_ASSET_ID],
+                &[Hop::Stable(0)],
+                &BOB
+            ),
+            Error::<Test>::InvalidPath
+        );
+
+        assert_ok!(DexPallet::inner_swap_exact_assets_for_assets_through_hops(
+            &ALICE,
+            DOT_UNIT,
+            0,
+            &path,
+            &[Hop::Stable(0)],
+            &BOB
+        ));
+        assert!(<Test as Config>::MultiAssetsHandler::balance_of(VDOT_ASSET_ID, &BOB) > 0);
+    });
+}
@@ -10,8 +10,20 @@
 
 use super::*;
 
-#[cfg(test)]
-mod mock;
+/// The number of fractional bits of the cumulative price, same as UQ112x112 in Uniswap V2.
+pub(crate) const PRICE_RESOLUTION: u32 = 112;
+
+/// The liquidity permanently locked on the first deposit of a pair.
+pub const MINIMUM_LIQUIDITY: AssetBalance = 1000;
+
+/// The pool which a hop of the swap path goes through.
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug)]
+pub enum Hop {
+    Pair,
+    Stable(u32),
+    Weighted(u32),
+}
+
 #[cfg(test)]
 mod tests;
 
@@ -37,7 +49,256 @@ impl<T: Config> Pallet<T> {
     pub fn get_pair_account_id(asset_0: AssetId, asset_1: AssetId) -> Option<T::AccountId> {
         let (asset_0, asset_1) = Self::sort_asset_id(asset_0, asset_1);
 
-        Self::lp_metadata((asset_0, asset_1)).map(|(pair_account, _)| pair_account)
+        Self::lp_metadata((asset_0, asset_1)).map(|meta| meta.pair_account)
+    }
+
+    /// Whether the pair is open for swaps and liquidity adds.
+    pub fn is_pair_enabled(asset_0: AssetId, asset_1: AssetId) -> bool {
+        Self::lp_metadata(Self::sort_asset_id(asset_0, asset_1))
+            .map_or(false, |meta| meta.status == PairStatus::Enabled)
+    }
+
+    /// Ensure every trading pair which the path goes through is enabled and not paused.
+    fn ensure_path_enabled(path: &[AssetId], hops: &[Hop]) -> DispatchResult {
+        for i in 0..path.len().saturating_sub(1) {
+            if hops[i] == Hop::Pair {
+                ensure!(Self::is_pair_enabled(path[i], path[i + 1]), Error::<T>::PairNotEnabled);
+                Self::ensure_pair_not_paused(path[i], path[i + 1])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ensure the current block is before the deadline of the transaction.
+    pub(crate) fn ensure_deadline(
+        deadline: Deadline<T::BlockNumber, MomentOf<T>>,
+    ) -> DispatchResult {
+        let before_deadline = match deadline {
+            Deadline::BlockNumber(block_number) => {
+                block_number > frame_system::Pallet::<T>::block_number()
+            }
+            Deadline::Timestamp(moment) => moment > T::Time::now(),
+        };
+        ensure!(before_deadline, Error::<T>::Deadline);
+
+        Ok(())
+    }
+
+    /// The maximum price impact of the hops through the trading pair, the one of the pair
+    /// takes precedence over the global one.
+    pub fn get_max_price_impact(asset_0: AssetId, asset_1: AssetId) -> Option<(u32, u32)> {
+        Self::pair_max_price_impact(Self::sort_asset_id(asset_0, asset_1))
+            .or_else(Self::global_max_price_impact)
+    }
+
+    /// The fall of the spot price of `input` quoted in `output` caused by swapping `amount_in`
+    /// for `amount_out` through the trading pair, in `BONE`.
+    pub fn calculate_price_impact(
+        amount_in: AssetBalance,
+        amount_out: AssetBalance,
+        reserve_in: AssetBalance,
+        reserve_out: AssetBalance,
+    ) -> AssetBalance {
+        if reserve_in.is_zero() || reserve_out.is_zero() {
+            return Zero::zero();
+        }
+        if amount_out >= reserve_out {
+            return BONE;
+        }
+
+        // 1 - ((reserve_out - amount_out) / (reserve_in + amount_in)) / (reserve_out / reserve_in)
+        let numerator = U512::from(reserve_out - amount_out) * U512::from(reserve_in);
+        let denominator =
+            U512::from(reserve_out) * (U512::from(reserve_in) + U512::from(amount_in));
+
+        denominator
+            .saturating_sub(numerator)
+            .saturating_mul(U512::from(BONE))
+            .checked_div(denominator)
+            .and_then(|n| TryInto::<AssetBalance>::try_into(n).ok())
+            .unwrap_or(BONE)
+    }
+
+    /// The price impacts of every hop of selling `amount_in` through the path, in `BONE`. The
+    /// impacts are only of the hops through the trading pairs, zero for the other hops.
+    pub fn get_price_impact_by_path(
+        amount_in: AssetBalance,
+        path: &[AssetId],
+    ) -> Result<Vec<AssetBalance>, DispatchError> {
+        let hops = Self::get_hops(path)?;
+        let amounts = Self::get_amount_out_by_hops(amount_in, path, &hops)?;
+
+        Ok(Self::price_impacts(&amounts, path, &hops))
+    }
+
+    fn price_impacts(
+        amounts: &[AssetBalance],
+        path: &[AssetId],
+        hops: &[Hop],
+    ) -> Vec<AssetBalance> {
+        (0..path.len().saturating_sub(1))
+            .map(|i| {
+                if hops[i] == Hop::Pair {
+                    let (reserve_in, reserve_out) = Self::get_reserves(path[i], path[i + 1]);
+                    Self::calculate_price_impact(
+                        amounts[i],
+                        amounts[i + 1],
+                        reserve_in,
+                        reserve_out,
+                    )
+                } else {
+                    Zero::zero()
+                }
+            })
+            .collect()
+    }
+
+    /// Ensure no hop through a trading pair of the path moves the spot price more than the
+    /// maximum price impact of the pair.
+    fn ensure_price_impact(
+        amounts: &[AssetBalance],
+        path: &[AssetId],
+        hops: &[Hop],
+    ) -> DispatchResult {
+        for i in 0..path.len().saturating_sub(1) {
+            if hops[i] == Hop::Pair {
+                Self::ensure_pair_price_impact(path[i], path[i + 1], amounts[i], amounts[i + 1])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ensure the swap of `amount_in` of `input` for `amount_out` of `output` through the
+    /// trading pair doesn't move the spot price more than the maximum price impact of the pair.
+    fn ensure_pair_price_impact(
+        input: AssetId,
+        output: AssetId,
+        amount_in: AssetBalance,
+        amount_out: AssetBalance,
+    ) -> DispatchResult {
+        if let Some((impact_numerator, impact_denominator)) =
+            Self::get_max_price_impact(input, output)
+        {
+            let (reserve_in, reserve_out) = Self::get_reserves(input, output);
+            let impact =
+                Self::calculate_price_impact(amount_in, amount_out, reserve_in, reserve_out);
+            ensure!(
+                U256::from(impact) * U256::from(impact_denominator)
+                    <= U256::from(impact_numerator) * U256::from(BONE),
+                Error::<T>::ExcessivePriceImpact
+            );
+        }
+
+        Ok(())
+    }
+
+    /// The pool which the swap from `asset_in` to `asset_out` goes through when the path
+    /// doesn't name its hops. The trading pair of the two assets always takes precedence, so
+    /// a pool never takes over the swaps of an existing pair, the swaps through such a pool
+    /// name the hops explicitly. Without a pair, the stable pool takes precedence over the
+    /// weighted pool, unless only the weighted pool has liquidity.
+    pub fn get_hop(asset_in: AssetId, asset_out: AssetId) -> Option<Hop> {
+        let pair = Self::sort_asset_id(asset_in, asset_out);
+        if LiquidityMeta::<T>::contains_key(pair) {
+            return Some(Hop::Pair);
+        }
+
+        match (Self::stable_pool_of_assets(pair), Self::weighted_pool_of_assets(pair)) {
+            (Some(stable_pool), Some(weighted_pool))
+                if !Self::is_stable_pool_liquid(stable_pool)
+                    && Self::is_weighted_pool_liquid(weighted_pool) =>
+            {
+                Some(Hop::Weighted(weighted_pool))
+            }
+            (Some(stable_pool), _) => Some(Hop::Stable(stable_pool)),
+            (None, weighted_pool) => weighted_pool.map(Hop::Weighted),
+        }
+    }
+
+    /// The hops of every pair of adjacent assets of the path which doesn't name its hops.
+    pub fn get_hops(path: &[AssetId]) -> Result<Vec<Hop>, DispatchError> {
+        ensure!(path.len() > 1, Error::<T>::InvalidPath);
+
+        path.windows(2)
+            .map(|assets| {
+                Self::get_hop(assets[0], assets[1]).ok_or_else(|| Error::<T>::InvalidPath.into())
+            })
+            .collect()
+    }
+
+    /// Ensure there is a hop for every pair of adjacent assets of the path, and every hop goes
+    /// through an existing trading pair or a pool of both of the assets.
+    pub(crate) fn ensure_hops(path: &[AssetId], hops: &[Hop]) -> DispatchResult {
+        ensure!(path.len() > 1 && hops.len() + 1 == path.len(), Error::<T>::InvalidPath);
+
+        for (assets, hop) in path.windows(2).zip(hops) {
+            let pool_assets = match *hop {
+                Hop::Pair => {
+                    ensure!(
+                        LiquidityMeta::<T>::contains_key(Self::sort_asset_id(assets[0], assets[1])),
+                        Error::<T>::PairNotExists
+                    );
+                    continue;
+                }
+                Hop::Stable(pool_id) => {
+                    Self::stable_pool(pool_id).ok_or(Error::<T>::PoolNotExists)?.assets
+                }
+                Hop::Weighted(pool_id) => {
+                    Self::weighted_pool(pool_id).ok_or(Error::<T>::PoolNotExists)?.assets
+                }
+            };
+            ensure!(
+                assets[0] != assets[1]
+                    && pool_assets.contains(&assets[0])
+                    && pool_assets.contains(&assets[1]),
+                Error::<T>::InvalidPath
+            );
+        }
+
+        Ok(())
+    }
+
+    /// The account which holds the assets of the hop from `asset_in` to `asset_out`
+    pub fn get_hop_account_id(asset_in: AssetId, asset_out: AssetId) -> Option<T::AccountId> {
+        Self::hop_account_id(Self::get_hop(asset_in, asset_out)?, asset_in, asset_out)
+    }
+
+    fn hop_account_id(hop: Hop, asset_in: AssetId, asset_out: AssetId) -> Option<T::AccountId> {
+        match hop {
+            Hop::Pair => Self::get_pair_account_id(asset_in, asset_out),
+            Hop::Stable(pool_id) => Self::stable_pool(pool_id).map(|pool| pool.pool_account),
+            Hop::Weighted(pool_id) => Self::weighted_pool(pool_id).map(|pool| pool.pool_account),
+        }
+    }
+
+    /// The account which the `MINIMUM_LIQUIDITY` of every pair is locked to, nobody holds
+    /// the key of it.
+    pub fn lock_account_id() -> T::AccountId {
+        T::PalletId::get().into_sub_account(b"lock")
+    }
+
+    /// Create the sorted pair with the status.
+    pub(crate) fn inner_create_pair(
+        asset_0: AssetId,
+        asset_1: AssetId,
+        status: PairStatus,
+    ) -> DispatchResult {
+        LiquidityMeta::<T>::try_mutate((asset_0, asset_1), |meta| {
+            ensure!(meta.is_none(), Error::<T>::PairAlreadyExists);
+
+            let pair_account = Self::pair_account_id(asset_0, asset_1);
+            *meta = Some(PairMetadata { pair_account, status, ..Default::default() });
+
+            Self::mutate_lp_pairs(asset_0, asset_1);
+            LiquidityPriceCumulative::<T>::insert(
+                (asset_0, asset_1),
+                (U256::zero(), U256::zero(), frame_system::Pallet::<T>::block_number()),
+            );
+
+            Ok(())
+        })
     }
 
     pub fn mutate_lp_pairs(asset_0: AssetId, asset_1: AssetId) {
@@ -76,9 +337,10 @@ impl<T: Config> Pallet<T> {
         amount_1_min: AssetBalance,
     ) -> DispatchResult {
         LiquidityMeta::<T>::try_mutate(Self::sort_asset_id(asset_0, asset_1), |meta| {
-            if let Some((pair_account, total_liquidity)) = meta {
-                let reserve_0 = T::MultiAssetsHandler::balance_of(asset_0, pair_account);
-                let reserve_1 = T::MultiAssetsHandler::balance_of(asset_1, pair_account);
+            if let Some(meta) = meta {
+                ensure!(meta.status == PairStatus::Enabled, Error::<T>::PairNotEnabled);
+                Self::ensure_pair_not_paused(asset_0, asset_1)?;
+                let (reserve_0, reserve_1) = Self::pair_reserves(meta, asset_0, asset_1);
 
                 let (amount_0, amount_1) = Self::calculate_added_amount(
                     amount_0_desired,
@@ -96,21 +358,49 @@ impl<T: Config> Pallet<T> {
                     Error::<T>::InsufficientAssetBalance
                 );
 
+                let fee_on = Self::mint_protocol_fee(
+                    asset_0,
+                    asset_1,
+                    reserve_0,
+                    reserve_1,
+                    &mut meta.total_supply,
+                )?;
+
                 let mint_liquidity = Self::calculate_liquidity(
                     amount_0,
                     amount_1,
                     reserve_0,
                     reserve_1,
-                    *total_liquidity,
+                    meta.total_supply,
                 );
+                if meta.total_supply.is_zero() {
+                    ensure!(mint_liquidity > Zero::zero(), Error::<T>::InsufficientLiquidity);
+
+                    meta.total_supply = MINIMUM_LIQUIDITY;
+                    Self::mutate_liquidity(
+                        asset_0,
+                        asset_1,
+                        &Self::lock_account_id(),
+                        MINIMUM_LIQUIDITY,
+                        true,
+                    )?;
+                }
                 ensure!(mint_liquidity > Zero::zero(), Error::<T>::Overflow);
 
-                *total_liquidity =
-                    total_liquidity.checked_add(mint_liquidity).ok_or(Error::<T>::Overflow)?;
+                meta.total_supply =
+                    meta.total_supply.checked_add(mint_liquidity).ok_or(Error::<T>::Overflow)?;
                 Self::mutate_liquidity(asset_0, asset_1, who, mint_liquidity, true)?;
 
-                T::MultiAssetsHandler::transfer(asset_0, who, &pair_account, amount_0)?;
-                T::MultiAssetsHandler::transfer(asset_1, who, &pair_account, amount_1)?;
+                T::MultiAssetsHandler::transfer(asset_0, who, &meta.pair_account, amount_0)?;
+                T::MultiAssetsHandler::transfer(asset_1, who, &meta.pair_account, amount_1)?;
+
+                let reserve_0 = reserve_0.checked_add(amount_0).ok_or(Error::<T>::Overflow)?;
+                let reserve_1 = reserve_1.checked_add(amount_1).ok_or(Error::<T>::Overflow)?;
+                Self::update_reserves(meta, asset_0, asset_1, reserve_0, reserve_1)?;
+
+                if fee_on {
+                    Self::update_k_last(asset_0, asset_1, reserve_0, reserve_1);
+                }
 
                 Self::deposit_event(Event::LiquidityAdded(
                     who.clone(),
@@ -138,33 +428,73 @@ impl<T: Config> Pallet<T> {
         amount_1_min: AssetBalance,
         recipient: &T::AccountId,
     ) -> DispatchResult {
+        Self::remove_liquidity_of(
+            who,
+            asset_0,
+            asset_1,
+            remove_liquidity,
+            amount_0_min,
+            amount_1_min,
+            recipient,
+        )
+        .map(|_| ())
+    }
+
+    /// Remove liquidity from the pair into the amounts of the assets which are returned.
+    #[allow(clippy::too_many_arguments)]
+    fn remove_liquidity_of(
+        who: &T::AccountId,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        remove_liquidity: AssetBalance,
+        amount_0_min: AssetBalance,
+        amount_1_min: AssetBalance,
+        recipient: &T::AccountId,
+    ) -> Result<(AssetBalance, AssetBalance), DispatchError> {
+        Self::ensure_pair_not_paused(asset_0, asset_1)?;
         ensure!(
-            Self::lp_ledger(((asset_0, asset_1), who)) >= remove_liquidity,
+            Self::lp_ledger((Self::sort_asset_id(asset_0, asset_1), who)) >= remove_liquidity,
             Error::<T>::InsufficientLiquidity
         );
 
         LiquidityMeta::<T>::try_mutate(Self::sort_asset_id(asset_0, asset_1), |meta| {
-            if let Some((pair_account, total_liquidity)) = meta {
-                let reserve_0 = T::MultiAssetsHandler::balance_of(asset_0, &pair_account);
-                let reserve_1 = T::MultiAssetsHandler::balance_of(asset_1, &pair_account);
+            if let Some(meta) = meta {
+                let (reserve_0, reserve_1) = Self::pair_reserves(meta, asset_0, asset_1);
+
+                let fee_on = Self::mint_protocol_fee(
+                    asset_0,
+                    asset_1,
+                    reserve_0,
+                    reserve_1,
+                    &mut meta.total_supply,
+                )?;
 
                 let amount_0 =
-                    Self::calculate_share_amount(remove_liquidity, *total_liquidity, reserve_0);
+                    Self::calculate_share_amount(remove_liquidity, meta.total_supply, reserve_0);
                 let amount_1 =
-                    Self::calculate_share_amount(remove_liquidity, *total_liquidity, reserve_1);
+                    Self::calculate_share_amount(remove_liquidity, meta.total_supply, reserve_1);
 
                 ensure!(
                     amount_0 >= amount_0_min && amount_1 >= amount_1_min,
                     Error::<T>::InsufficientTargetAmount
                 );
 
-                *total_liquidity = total_liquidity
+                meta.total_supply = meta
+                    .total_supply
                     .checked_sub(remove_liquidity)
                     .ok_or(Error::<T>::InsufficientLiquidity)?;
                 Self::mutate_liquidity(asset_0, asset_1, who, remove_liquidity, false)?;
 
-                T::MultiAssetsHandler::transfer(asset_0, &pair_account, recipient, amount_0)?;
-                T::MultiAssetsHandler::transfer(asset_1, &pair_account, recipient, amount_1)?;
+                T::MultiAssetsHandler::transfer(asset_0, &meta.pair_account, recipient, amount_0)?;
+                T::MultiAssetsHandler::transfer(asset_1, &meta.pair_account, recipient, amount_1)?;
+
+                let reserve_0 = reserve_0.saturating_sub(amount_0);
+                let reserve_1 = reserve_1.saturating_sub(amount_1);
+                Self::update_reserves(meta, asset_0, asset_1, reserve_0, reserve_1)?;
+
+                if fee_on {
+                    Self::update_k_last(asset_0, asset_1, reserve_0, reserve_1);
+                }
 
                 Self::deposit_event(Event::LiquidityRemoved(
                     who.clone(),
@@ -176,13 +506,105 @@ impl<T: Config> Pallet<T> {
                     remove_liquidity,
                 ));
 
-                Ok(())
+                Ok((amount_0, amount_1))
             } else {
                 Err(Error::<T>::PairNotExists.into())
             }
         })
     }
 
+    /// Remove liquidity from the pair into `asset_0` only, the `asset_1` removed is swapped
+    /// for `asset_0` through the pair.
+    pub fn inner_remove_liquidity_single_asset(
+        who: &T::AccountId,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        remove_liquidity: AssetBalance,
+        amount_0_min: AssetBalance,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        let (amount_0, amount_1) = Self::remove_liquidity_of(
+            who,
+            asset_0,
+            asset_1,
+            remove_liquidity,
+            Zero::zero(),
+            Zero::zero(),
+            who,
+        )?;
+
+        let (reserve_0, reserve_1) = Self::get_reserves(asset_0, asset_1);
+        let amount_out = Self::get_amount_out(
+            amount_1,
+            reserve_1,
+            reserve_0,
+            Self::get_exchange_fee(asset_0, asset_1),
+        );
+        // The `asset_1` removed must be swapped, it is never left to `who`.
+        ensure!(amount_1.is_zero() || !amount_out.is_zero(), Error::<T>::InsufficientTargetAmount);
+        ensure!(
+            amount_0.checked_add(amount_out).ok_or(Error::<T>::Overflow)? >= amount_0_min,
+            Error::<T>::InsufficientTargetAmount
+        );
+
+        if !amount_out.is_zero() {
+            let pair_account =
+                Self::get_pair_account_id(asset_0, asset_1).ok_or(Error::<T>::PairNotExists)?;
+            T::MultiAssetsHandler::transfer(asset_1, who, &pair_account, amount_1)?;
+            Self::swap_through_pair(asset_1, asset_0, amount_1, amount_out, recipient)?;
+        }
+        if who != recipient {
+            T::MultiAssetsHandler::transfer(asset_0, who, recipient, amount_0)?;
+        }
+
+        Ok(())
+    }
+
+    /// Add liquidity to the pair with `amount_0` of `asset_0` only, the part of it which
+    /// makes up the ratio of the reserves is swapped for `asset_1` through the pair first.
+    /// The dust of the adding is left to `who`.
+    pub fn inner_add_liquidity_single_asset(
+        who: &T::AccountId,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        amount_0: AssetBalance,
+        liquidity_min: AssetBalance,
+    ) -> DispatchResult {
+        let pair = Self::sort_asset_id(asset_0, asset_1);
+        let pair_account =
+            Self::get_pair_account_id(asset_0, asset_1).ok_or(Error::<T>::PairNotExists)?;
+        let (reserve_0, reserve_1) = Self::get_reserves(asset_0, asset_1);
+        ensure!(
+            reserve_0 > Zero::zero() && reserve_1 > Zero::zero(),
+            Error::<T>::InsufficientPairReserve
+        );
+
+        let fee = Self::get_exchange_fee(asset_0, asset_1);
+        let swap_amount = Self::calculate_single_asset_swap_amount(amount_0, reserve_0, fee);
+        let amount_1 = Self::get_amount_out(swap_amount, reserve_0, reserve_1, fee);
+        ensure!(amount_1 > Zero::zero(), Error::<T>::InsufficientInputAmount);
+
+        T::MultiAssetsHandler::transfer(asset_0, who, &pair_account, swap_amount)?;
+        Self::swap_through_pair(asset_0, asset_1, swap_amount, amount_1, who)?;
+
+        let liquidity = Self::lp_ledger((pair, who));
+        Self::inner_add_liquidity(
+            who,
+            asset_0,
+            asset_1,
+            amount_0.saturating_sub(swap_amount),
+            amount_1,
+            Zero::zero(),
+            Zero::zero(),
+        )?;
+        ensure!(
+            Self::lp_ledger((pair, who)).saturating_sub(liquidity) >= liquidity_min,
+            Error::<T>::InsufficientTargetAmount
+        );
+
+        Ok(())
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn inner_swap_exact_tokens_for_tokens(
         who: &T::AccountId,
@@ -191,14 +613,40 @@ impl<T: Config> Pallet<T> {
         path: &[AssetId],
         recipient: &T::AccountId,
     ) -> DispatchResult {
-        let amounts = Self::get_amount_out_by_path(amount_in, &path)?;
+        let hops = Self::get_hops(path)?;
+
+        Self::inner_swap_exact_assets_for_assets_through_hops(
+            who,
+            amount_in,
+            amount_out_min,
+            path,
+            &hops,
+            recipient,
+        )
+    }
+
+    /// Sell `amount_in` through the path whose hops are named explicitly, which reaches the
+    /// pools of the assets which have a trading pair.
+    #[allow(clippy::too_many_arguments)]
+    pub fn inner_swap_exact_assets_for_assets_through_hops(
+        who: &T::AccountId,
+        amount_in: AssetBalance,
+        amount_out_min: AssetBalance,
+        path: &[AssetId],
+        hops: &[Hop],
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        Self::ensure_hops(path, hops)?;
+        let amounts = Self::get_amount_out_by_hops(amount_in, path, hops)?;
+        Self::ensure_path_enabled(path, hops)?;
+        Self::ensure_price_impact(&amounts, path, hops)?;
         ensure!(amounts[amounts.len() - 1] >= amount_out_min, Error::<T>::InsufficientTargetAmount);
 
-        let pair_account =
-            Self::get_pair_account_id(path[0], path[1]).ok_or(Error::<T>::PairNotExists)?;
+        let hop_account =
+            Self::hop_account_id(hops[0], path[0], path[1]).ok_or(Error::<T>::PairNotExists)?;
 
-        T::MultiAssetsHandler::transfer(path[0], who, &pair_account, amount_in)?;
-        Self::swap(&amounts, &path, &recipient)?;
+        T::MultiAssetsHandler::transfer(path[0], who, &hop_account, amount_in)?;
+        Self::swap(&amounts, path, hops, recipient)?;
 
         Self::deposit_event(Event::AssetSwap(
             who.clone(),
@@ -219,15 +667,18 @@ impl<T: Config> Pallet<T> {
         path: &[AssetId],
         recipient: &T::AccountId,
     ) -> DispatchResult {
-        let amounts = Self::get_amount_in_by_path(amount_out, &path)?;
+        let hops = Self::get_hops(path)?;
+        let amounts = Self::get_amount_in_by_hops(amount_out, path, &hops)?;
+        Self::ensure_path_enabled(path, &hops)?;
+        Self::ensure_price_impact(&amounts, path, &hops)?;
 
         ensure!(amounts[0] <= amount_in_max, Error::<T>::ExcessiveSoldAmount);
 
-        let pair_account =
-            Self::get_pair_account_id(path[0], path[1]).ok_or(Error::<T>::PairNotExists)?;
+        let hop_account =
+            Self::hop_account_id(hops[0], path[0], path[1]).ok_or(Error::<T>::PairNotExists)?;
 
-        T::MultiAssetsHandler::transfer(path[0], who, &pair_account, amounts[0])?;
-        Self::swap(&amounts, &path, recipient)?;
+        T::MultiAssetsHandler::transfer(path[0], who, &hop_account, amounts[0])?;
+        Self::swap(&amounts, path, &hops, recipient)?;
 
         Self::deposit_event(Event::AssetSwap(
             who.clone(),
@@ -240,7 +691,399 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
-    fn calculate_share_amount(
+    #[allow(clippy::too_many_arguments)]
+    pub fn inner_flash_swap(
+        who: &T::AccountId,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        amount_0_out: AssetBalance,
+        amount_1_out: AssetBalance,
+        recipient: &T::AccountId,
+        data: &[u8],
+    ) -> DispatchResult {
+        ensure!(
+            amount_0_out > Zero::zero() || amount_1_out > Zero::zero(),
+            Error::<T>::InsufficientTargetAmount
+        );
+
+        let pair = Self::sort_asset_id(asset_0, asset_1);
+        let pair_account =
+            Self::get_pair_account_id(asset_0, asset_1).ok_or(Error::<T>::PairNotExists)?;
+        ensure!(Self::is_pair_enabled(asset_0, asset_1), Error::<T>::PairNotEnabled);
+        Self::ensure_pair_not_paused(asset_0, asset_1)?;
+        ensure!(!Self::flash_swap_locked(pair), Error::<T>::PairLocked);
+
+        let (reserve_0, reserve_1) = Self::get_reserves(asset_0, asset_1);
+        ensure!(
+            amount_0_out < reserve_0 && amount_1_out < reserve_1,
+            Error::<T>::InsufficientPairReserve
+        );
+
+        // The reserves of the pair can't be changed by the borrower until it is paid back.
+        FlashSwapLocked::<T>::insert(pair, true);
+        Self::pair_swap(asset_0, asset_1, &pair_account, amount_0_out, amount_1_out, recipient)?;
+        T::FlashSwapHandler::on_flash_swap(
+            who,
+            recipient,
+            asset_0,
+            asset_1,
+            amount_0_out,
+            amount_1_out,
+            data,
+        )?;
+        FlashSwapLocked::<T>::remove(pair);
+
+        let balance_0 = T::MultiAssetsHandler::balance_of(asset_0, &pair_account);
+        let balance_1 = T::MultiAssetsHandler::balance_of(asset_1, &pair_account);
+        let amount_0_in = balance_0.saturating_sub(reserve_0 - amount_0_out);
+        let amount_1_in = balance_1.saturating_sub(reserve_1 - amount_1_out);
+        ensure!(
+            amount_0_in > Zero::zero() || amount_1_in > Zero::zero(),
+            Error::<T>::InsufficientInputAmount
+        );
+
+        let (fee_numerator, fee_denominator) = Self::get_exchange_fee(asset_0, asset_1);
+        let balance_0_adjusted = U256::from(balance_0)
+            .saturating_mul(U256::from(fee_denominator))
+            .saturating_sub(U256::from(amount_0_in).saturating_mul(U256::from(fee_numerator)));
+        let balance_1_adjusted = U256::from(balance_1)
+            .saturating_mul(U256::from(fee_denominator))
+            .saturating_sub(U256::from(amount_1_in).saturating_mul(U256::from(fee_numerator)));
+        ensure!(
+            balance_0_adjusted.full_mul(balance_1_adjusted)
+                >= U256::from(reserve_0)
+                    .full_mul(U256::from(reserve_1))
+                    .saturating_mul(U512::from(fee_denominator) * U512::from(fee_denominator)),
+            Error::<T>::InvalidInvariant
+        );
+
+        Self::swap_reserves(
+            asset_0,
+            asset_1,
+            amount_0_in,
+            amount_1_in,
+            amount_0_out,
+            amount_1_out,
+        )?;
+
+        Self::deposit_event(Event::FlashSwap(
+            who.clone(),
+            recipient.clone(),
+            asset_0,
+            asset_1,
+            amount_0_out,
+            amount_1_out,
+            amount_0_in,
+            amount_1_in,
+        ));
+
+        Ok(())
+    }
+
+    pub fn inner_skim(
+        asset_0: AssetId,
+        asset_1: AssetId,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        let (asset_0, asset_1) = Self::sort_asset_id(asset_0, asset_1);
+        let meta = Self::lp_metadata((asset_0, asset_1)).ok_or(Error::<T>::PairNotExists)?;
+        // The contributions to the bootstrap are held by the pair account.
+        ensure!(meta.status != PairStatus::Bootstrap, Error::<T>::PairNotEnabled);
+        Self::ensure_pair_not_paused(asset_0, asset_1)?;
+        ensure!(!Self::flash_swap_locked((asset_0, asset_1)), Error::<T>::PairLocked);
+
+        let excess_0 = T::MultiAssetsHandler::balance_of(asset_0, &meta.pair_account)
+            .saturating_sub(meta.reserve_0);
+        let excess_1 = T::MultiAssetsHandler::balance_of(asset_1, &meta.pair_account)
+            .saturating_sub(meta.reserve_1);
+
+        if excess_0 > Zero::zero() {
+            T::MultiAssetsHandler::transfer(asset_0, &meta.pair_account, recipient, excess_0)?;
+        }
+
+        if excess_1 > Zero::zero() {
+            T::MultiAssetsHandler::transfer(asset_1, &meta.pair_account, recipient, excess_1)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn inner_sync(asset_0: AssetId, asset_1: AssetId) -> DispatchResult {
+        let (asset_0, asset_1) = Self::sort_asset_id(asset_0, asset_1);
+
+        LiquidityMeta::<T>::try_mutate((asset_0, asset_1), |meta| {
+            let meta = meta.as_mut().ok_or(Error::<T>::PairNotExists)?;
+            ensure!(meta.status != PairStatus::Bootstrap, Error::<T>::PairNotEnabled);
+            Self::ensure_pair_not_paused(asset_0, asset_1)?;
+
+            let balance_0 = T::MultiAssetsHandler::balance_of(asset_0, &meta.pair_account);
+            let balance_1 = T::MultiAssetsHandler::balance_of(asset_1, &meta.pair_account);
+            Self::update_reserves(meta, asset_0, asset_1, balance_0, balance_1)
+        })
+    }
+
+    /// The tracked reserves of the pair, in the order of `asset_0` and `asset_1`.
+    pub fn get_reserves(asset_0: AssetId, asset_1: AssetId) -> (AssetBalance, AssetBalance) {
+        Self::lp_metadata(Self::sort_asset_id(asset_0, asset_1))
+            .map_or((Zero::zero(), Zero::zero()), |meta| {
+                Self::pair_reserves(&meta, asset_0, asset_1)
+            })
+    }
+
+    pub(crate) fn pair_reserves(
+        meta: &PairMetadata<T::AccountId>,
+        asset_0: AssetId,
+        asset_1: AssetId,
+    ) -> (AssetBalance, AssetBalance) {
+        if asset_0 < asset_1 {
+            (meta.reserve_0, meta.reserve_1)
+        } else {
+            (meta.reserve_1, meta.reserve_0)
+        }
+    }
+
+    /// Update the tracked reserves of the pair, in the order of `asset_0` and `asset_1`.
+    ///
+    /// The prices are accumulated by the reserves before updating.
+    pub(crate) fn update_reserves(
+        meta: &mut PairMetadata<T::AccountId>,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        reserve_0: AssetBalance,
+        reserve_1: AssetBalance,
+    ) -> DispatchResult {
+        let (asset_0, asset_1, reserve_0, reserve_1) = if asset_0 < asset_1 {
+            (asset_0, asset_1, reserve_0, reserve_1)
+        } else {
+            (asset_1, asset_0, reserve_1, reserve_0)
+        };
+        ensure!(!Self::flash_swap_locked((asset_0, asset_1)), Error::<T>::PairLocked);
+
+        Self::update_price_cumulative(asset_0, asset_1, meta.reserve_0, meta.reserve_1);
+        meta.reserve_0 = reserve_0;
+        meta.reserve_1 = reserve_1;
+
+        Self::deposit_event(Event::Sync(asset_0, asset_1, reserve_0, reserve_1));
+
+        Ok(())
+    }
+
+    /// Update the tracked reserves of the pair by the amounts swapped in and out, in the
+    /// order of `asset_0` and `asset_1`.
+    fn swap_reserves(
+        asset_0: AssetId,
+        asset_1: AssetId,
+        amount_0_in: AssetBalance,
+        amount_1_in: AssetBalance,
+        amount_0_out: AssetBalance,
+        amount_1_out: AssetBalance,
+    ) -> DispatchResult {
+        LiquidityMeta::<T>::try_mutate(Self::sort_asset_id(asset_0, asset_1), |meta| {
+            let meta = meta.as_mut().ok_or(Error::<T>::PairNotExists)?;
+            let (reserve_0, reserve_1) = Self::pair_reserves(meta, asset_0, asset_1);
+
+            let reserve_0 = reserve_0
+                .checked_add(amount_0_in)
+                .and_then(|r| r.checked_sub(amount_0_out))
+                .ok_or(Error::<T>::InsufficientPairReserve)?;
+            let reserve_1 = reserve_1
+                .checked_add(amount_1_in)
+                .and_then(|r| r.checked_sub(amount_1_out))
+                .ok_or(Error::<T>::InsufficientPairReserve)?;
+            Self::update_reserves(meta, asset_0, asset_1, reserve_0, reserve_1)
+        })
+    }
+
+    /// Accumulate the prices of the sorted pair by the reserves before the first change in
+    /// this block.
+    ///
+    /// Must be called with the reserves before they are changed.
+    fn update_price_cumulative(
+        asset_0: AssetId,
+        asset_1: AssetId,
+        reserve_0: AssetBalance,
+        reserve_1: AssetBalance,
+    ) {
+        let now = frame_system::Pallet::<T>::block_number();
+
+        LiquidityPriceCumulative::<T>::mutate((asset_0, asset_1), |(price_0, price_1, last)| {
+            let elapsed = now.saturating_sub(*last);
+            if elapsed.is_zero() {
+                return;
+            }
+
+            let (cumulative_0, cumulative_1) =
+                Self::accumulate_price(*price_0, *price_1, reserve_0, reserve_1, elapsed);
+            *price_0 = cumulative_0;
+            *price_1 = cumulative_1;
+            *last = now;
+        })
+    }
+
+    /// The cumulative prices are allowed to overflow, only the difference between
+    /// two observations is meaningful.
+    pub(crate) fn accumulate_price(
+        price_0_cumulative: U256,
+        price_1_cumulative: U256,
+        reserve_0: AssetBalance,
+        reserve_1: AssetBalance,
+        elapsed: T::BlockNumber,
+    ) -> (U256, U256) {
+        if reserve_0.is_zero() || reserve_1.is_zero() {
+            return (price_0_cumulative, price_1_cumulative);
+        }
+
+        let elapsed = U256::from(elapsed.saturated_into::<u128>());
+        let price_0 = (U256::from(reserve_1) << PRICE_RESOLUTION) / U256::from(reserve_0);
+        let price_1 = (U256::from(reserve_0) << PRICE_RESOLUTION) / U256::from(reserve_1);
+
+        (
+            price_0_cumulative.overflowing_add(price_0.overflowing_mul(elapsed).0).0,
+            price_1_cumulative.overflowing_add(price_1.overflowing_mul(elapsed).0).0,
+        )
+    }
+
+    /// Mint the protocol fee, which is the configured fraction of the growth in sqrt(k),
+    /// as liquidity to the fee receiver.
+    ///
+    /// Return whether the protocol fee is switched on.
+    pub(crate) fn mint_protocol_fee(
+        asset_0: AssetId,
+        asset_1: AssetId,
+        reserve_0: AssetBalance,
+        reserve_1: AssetBalance,
+        total_liquidity: &mut AssetBalance,
+    ) -> Result<bool, DispatchError> {
+        let pair = Self::sort_asset_id(asset_0, asset_1);
+        let k_last = Self::k_last(pair);
+
+        match Self::fee_meta() {
+            (Some(fee_receiver), (fee_numerator, fee_denominator)) if fee_numerator > 0 => {
+                if !k_last.is_zero() {
+                    let root_k = Self::u256_sqrt(U256::from(reserve_0) * U256::from(reserve_1));
+                    let root_k_last = Self::u256_sqrt(k_last);
+
+                    if root_k > root_k_last {
+                        let numerator = U256::from(*total_liquidity)
+                            .saturating_mul(root_k - root_k_last)
+                            .saturating_mul(U256::from(fee_numerator));
+                        let denominator = root_k
+                            .saturating_mul(U256::from(fee_denominator - fee_numerator))
+                            .saturating_add(root_k_last.saturating_mul(U256::from(fee_numerator)));
+
+                        let fee_liquidity = numerator
+                            .checked_div(denominator)
+                            .and_then(|n| TryInto::<AssetBalance>::try_into(n).ok())
+                            .unwrap_or_else(Zero::zero);
+
+                        if fee_liquidity > Zero::zero() {
+                            *total_liquidity = total_liquidity
+                                .checked_add(fee_liquidity)
+                                .ok_or(Error::<T>::Overflow)?;
+                            Self::mutate_liquidity(
+                                asset_0,
+                                asset_1,
+                                &fee_receiver,
+                                fee_liquidity,
+                                true,
+                            )?;
+                        }
+                    }
+                }
+
+                Ok(true)
+            }
+            _ => {
+                if !k_last.is_zero() {
+                    KLast::<T>::remove(pair);
+                }
+
+                Ok(false)
+            }
+        }
+    }
+
+    pub(crate) fn update_k_last(
+        asset_0: AssetId,
+        asset_1: AssetId,
+        reserve_0: AssetBalance,
+        reserve_1: AssetBalance,
+    ) {
+        KLast::<T>::insert(
+            Self::sort_asset_id(asset_0, asset_1),
+            U256::from(reserve_0) * U256::from(reserve_1),
+        );
+    }
+
+    /// The amount of the `amount_in` added to the pair by itself which is swapped first, so
+    /// the rest of it and the amount swapped out make up the ratio of the reserves after
+    /// the swap.
+    ///
+    /// s = (sqrt(r * (r * (2 - f)^2 + 4 * (1 - f) * a)) - r * (2 - f)) / (2 * (1 - f))
+    pub(crate) fn calculate_single_asset_swap_amount(
+        amount_in: AssetBalance,
+        reserve_in: AssetBalance,
+        (fee_numerator, fee_denominator): (u32, u32),
+    ) -> AssetBalance {
+        let fee_numerator = U512::from(fee_numerator);
+        let fee_denominator = U512::from(fee_denominator);
+        let reserve_in = U512::from(reserve_in);
+
+        let b = reserve_in.saturating_mul(
+            fee_denominator.saturating_mul(U512::from(2)).saturating_sub(fee_numerator),
+        );
+        let discriminant = b.saturating_mul(b).saturating_add(
+            reserve_in
+                .saturating_mul(U512::from(amount_in))
+                .saturating_mul(fee_denominator.saturating_sub(fee_numerator))
+                .saturating_mul(fee_denominator)
+                .saturating_mul(U512::from(4)),
+        );
+
+        Self::u512_sqrt(discriminant)
+            .saturating_sub(b)
+            .checked_div(
+                fee_denominator.saturating_sub(fee_numerator).saturating_mul(U512::from(2)),
+            )
+            .and_then(|n| TryInto::<AssetBalance>::try_into(n).ok())
+            .unwrap_or_else(Zero::zero)
+    }
+
+    /// Integer square root of U256, the babylonian method.
+    pub(crate) fn u256_sqrt(y: U256) -> U256 {
+        if y > U256::from(3) {
+            let mut z = y;
+            let mut x = y / 2 + 1;
+            while x < z {
+                z = x;
+                x = (y / x + x) / 2;
+            }
+            z
+        } else if !y.is_zero() {
+            U256::one()
+        } else {
+            U256::zero()
+        }
+    }
+
+    /// Integer square root of U512, the babylonian method.
+    pub(crate) fn u512_sqrt(y: U512) -> U512 {
+        if y > U512::from(3) {
+            let mut z = y;
+            let mut x = y / 2 + 1;
+            while x < z {
+                z = x;
+                x = (y / x + x) / 2;
+            }
+            z
+        } else if !y.is_zero() {
+            U512::one()
+        } else {
+            U512::zero()
+        }
+    }
+
+    pub(crate) fn calculate_share_amount(
         amount_0: AssetBalance,
         reserve_0: AssetBalance,
         reserve_1: AssetBalance,
@@ -260,7 +1103,7 @@ impl<T: Config> Pallet<T> {
         total_liquidity: AssetBalance,
     ) -> AssetBalance {
         if total_liquidity == Zero::zero() {
-            amount_0.saturating_mul(amount_1).integer_sqrt()
+            amount_0.saturating_mul(amount_1).integer_sqrt().saturating_sub(MINIMUM_LIQUIDITY)
         } else {
             core::cmp::min(
                 Self::calculate_share_amount(amount_0, reserve_0, total_liquidity),
@@ -293,7 +1136,7 @@ impl<T: Config> Pallet<T> {
         Ok((amount_0_optimal, amount_1_desired))
     }
 
-    fn mutate_liquidity(
+    pub(crate) fn mutate_liquidity(
         asset_0: AssetId,
         asset_1: AssetId,
         who: &T::AccountId,
@@ -315,17 +1158,22 @@ impl<T: Config> Pallet<T> {
         )
     }
 
+    /// The exchange fee rate of the pair, fall back to `GetExchangeFee` if not set.
+    pub fn get_exchange_fee(asset_0: AssetId, asset_1: AssetId) -> (u32, u32) {
+        Self::lp_exchange_fee(Self::sort_asset_id(asset_0, asset_1))
+            .unwrap_or_else(T::GetExchangeFee::get)
+    }
+
     fn get_amount_in(
         output_amount: AssetBalance,
         input_reserve: AssetBalance,
         output_reserve: AssetBalance,
+        (fee_numerator, fee_denominator): (u32, u32),
     ) -> AssetBalance {
         if input_reserve.is_zero() || output_reserve.is_zero() || output_amount.is_zero() {
             return Zero::zero();
         }
 
-        let (fee_numerator, fee_denominator) = T::GetExchangeFee::get();
-
         let numerator = U256::from(input_reserve)
             .saturating_mul(U256::from(output_amount))
             .saturating_mul(U256::from(fee_denominator));
@@ -340,17 +1188,16 @@ impl<T: Config> Pallet<T> {
             .unwrap_or_else(Zero::zero)
     }
 
-    fn get_amount_out(
+    pub(crate) fn get_amount_out(
         input_amount: AssetBalance,
         input_reserve: AssetBalance,
         output_reserve: AssetBalance,
+        (fee_numerator, fee_denominator): (u32, u32),
     ) -> AssetBalance {
         if input_reserve.is_zero() || output_reserve.is_zero() || input_amount.is_zero() {
             return Zero::zero();
         }
 
-        let (fee_numerator, fee_denominator) = T::GetExchangeFee::get();
-
         let input_amount_with_fee =
             U256::from(input_amount.saturating_mul(
                 fee_denominator.saturating_sub(fee_numerator).unique_saturated_into(),
@@ -371,21 +1218,45 @@ impl<T: Config> Pallet<T> {
     pub fn get_amount_in_by_path(
         amount_out: AssetBalance,
         path: &[AssetId],
+    ) -> Result<Vec<AssetBalance>, DispatchError> {
+        Self::get_amount_in_by_hops(amount_out, path, &Self::get_hops(path)?)
+    }
+
+    fn get_amount_in_by_hops(
+        amount_out: AssetBalance,
+        path: &[AssetId],
+        hops: &[Hop],
     ) -> Result<Vec<AssetBalance>, DispatchError> {
         let len = path.len();
-        ensure!(len > 1, Error::<T>::InvalidPath);
+        ensure!(len > 1 && hops.len() + 1 == len, Error::<T>::InvalidPath);
 
         let mut i = len - 1;
         let mut out_vec = vec![amount_out];
 
         while i > 0 {
-            let pair_account = Self::pair_account_id(path[i], path[i - 1]);
-            let reserve_0 = T::MultiAssetsHandler::balance_of(path[i], &pair_account);
-            let reserve_1 = T::MultiAssetsHandler::balance_of(path[i - 1], &pair_account);
-
-            ensure!(reserve_1 > Zero::zero() && reserve_0 > Zero::zero(), Error::<T>::InvalidPath);
-
-            let amount = Self::get_amount_in(out_vec[len - 1 - i], reserve_1, reserve_0);
+            let amount = match hops[i - 1] {
+                Hop::Pair => {
+                    let (reserve_0, reserve_1) = Self::get_reserves(path[i], path[i - 1]);
+
+                    ensure!(
+                        reserve_1 > Zero::zero() && reserve_0 > Zero::zero(),
+                        Error::<T>::InvalidPath
+                    );
+
+                    Self::get_amount_in(
+                        out_vec[len - 1 - i],
+                        reserve_1,
+                        reserve_0,
+                        Self::get_exchange_fee(path[i], path[i - 1]),
+                    )
+                }
+                Hop::Stable(pool_id) => {
+                    Self::stable_amount_in(pool_id, path[i - 1], path[i], out_vec[len - 1 - i])
+                }
+                Hop::Weighted(pool_id) => {
+                    Self::weighted_amount_in(pool_id, path[i - 1], path[i], out_vec[len - 1 - i])
+                }
+            };
             ensure!(amount > One::one(), Error::<T>::InvalidPath);
 
             out_vec.push(amount);
@@ -400,19 +1271,43 @@ impl<T: Config> Pallet<T> {
         amount_in: AssetBalance,
         path: &[AssetId],
     ) -> Result<Vec<AssetBalance>, DispatchError> {
-        ensure!(path.len() > 1, Error::<T>::InvalidPath);
+        Self::get_amount_out_by_hops(amount_in, path, &Self::get_hops(path)?)
+    }
+
+    fn get_amount_out_by_hops(
+        amount_in: AssetBalance,
+        path: &[AssetId],
+        hops: &[Hop],
+    ) -> Result<Vec<AssetBalance>, DispatchError> {
+        ensure!(path.len() > 1 && hops.len() + 1 == path.len(), Error::<T>::InvalidPath);
 
         let len = path.len() - 1;
         let mut out_vec = vec![amount_in];
 
         for i in 0..len {
-            let pair_account = Self::pair_account_id(path[i], path[i + 1]);
-            let reserve_0 = T::MultiAssetsHandler::balance_of(path[i], &pair_account);
-            let reserve_1 = T::MultiAssetsHandler::balance_of(path[i + 1], &pair_account);
-
-            ensure!(reserve_1 > Zero::zero() && reserve_0 > Zero::zero(), Error::<T>::InvalidPath);
-
-            let amount = Self::get_amount_out(out_vec[i], reserve_0, reserve_1);
+            let amount = match hops[i] {
+                Hop::Pair => {
+                    let (reserve_0, reserve_1) = Self::get_reserves(path[i], path[i + 1]);
+
+                    ensure!(
+                        reserve_1 > Zero::zero() && reserve_0 > Zero::zero(),
+                        Error::<T>::InvalidPath
+                    );
+
+                    Self::get_amount_out(
+                        out_vec[i],
+                        reserve_0,
+                        reserve_1,
+                        Self::get_exchange_fee(path[i], path[i + 1]),
+                    )
+                }
+                Hop::Stable(pool_id) => {
+                    Self::stable_amount_out(pool_id, path[i], path[i + 1], out_vec[i])
+                }
+                Hop::Weighted(pool_id) => {
+                    Self::weighted_amount_out(pool_id, path[i], path[i + 1], out_vec[i])
+                }
+            };
             ensure!(amount > Zero::zero(), Error::<T>::InvalidPath);
             out_vec.push(amount);
         }
@@ -423,47 +1318,106 @@ impl<T: Config> Pallet<T> {
     fn swap(
         amounts: &[AssetBalance],
         path: &[AssetId],
+        hops: &[Hop],
         recipient: &T::AccountId,
     ) -> DispatchResult {
         for i in 0..(amounts.len() - 1) {
             let input = path[i];
             let output = path[i + 1];
-            let mut amount0_out: AssetBalance = AssetBalance::default();
-            let mut amount1_out = amounts[i + 1];
-
-            let (asset_0, asset_1) = Self::sort_asset_id(input, output);
-            if input != asset_0 {
-                amount0_out = amounts[i + 1];
-                amount1_out = AssetBalance::default();
-            }
-            let pair_account =
-                Self::get_pair_account_id(asset_0, asset_1).ok_or(Error::<T>::PairNotExists)?;
 
-            if i < (amounts.len() - 2) {
-                let mid_account = Self::get_pair_account_id(output, path[i + 2])
-                    .ok_or(Error::<T>::PairNotExists)?;
-                Self::pair_swap(
-                    asset_0,
-                    asset_1,
-                    &pair_account,
-                    amount0_out,
-                    amount1_out,
-                    &mid_account,
-                )?;
+            let to = if i < (amounts.len() - 2) {
+                Self::hop_account_id(hops[i + 1], output, path[i + 2])
+                    .ok_or(Error::<T>::PairNotExists)?
             } else {
-                Self::pair_swap(
-                    asset_0,
-                    asset_1,
-                    &pair_account,
-                    amount0_out,
-                    amount1_out,
-                    &recipient,
-                )?;
+                recipient.clone()
             };
+
+            match hops[i] {
+                Hop::Stable(pool_id) => {
+                    Self::stable_swap(pool_id, input, output, amounts[i], amounts[i + 1], &to)?
+                }
+                Hop::Weighted(pool_id) => {
+                    Self::weighted_swap(pool_id, input, output, amounts[i], amounts[i + 1], &to)?
+                }
+                Hop::Pair => {
+                    Self::swap_through_pair(input, output, amounts[i], amounts[i + 1], &to)?
+                }
+            }
         }
         Ok(())
     }
 
+    /// Swap `amount_in` of `input` from `who` for `output` through the trading pair of them,
+    /// regardless of the hop of the two assets. Returns the amount of `output` to `recipient`.
+    pub(crate) fn swap_exact_through_pair(
+        who: &T::AccountId,
+        input: AssetId,
+        output: AssetId,
+        amount_in: AssetBalance,
+        amount_out_min: AssetBalance,
+        recipient: &T::AccountId,
+    ) -> Result<AssetBalance, DispatchError> {
+        let pair_account =
+            Self::get_pair_account_id(input, output).ok_or(Error::<T>::PairNotExists)?;
+        let (reserve_in, reserve_out) = Self::get_reserves(input, output);
+        let amount_out = Self::get_amount_out(
+            amount_in,
+            reserve_in,
+            reserve_out,
+            Self::get_exchange_fee(input, output),
+        );
+        ensure!(
+            !amount_out.is_zero() && amount_out >= amount_out_min,
+            Error::<T>::InsufficientTargetAmount
+        );
+
+        T::MultiAssetsHandler::transfer(input, who, &pair_account, amount_in)?;
+        Self::swap_through_pair(input, output, amount_in, amount_out, recipient)?;
+
+        Self::deposit_event(Event::AssetSwap(
+            who.clone(),
+            recipient.clone(),
+            vec![input, output],
+            amount_in,
+            amount_out,
+        ));
+
+        Ok(amount_out)
+    }
+
+    /// Swap `amount_in` of `input` for `amount_out` of `output` through the trading pair of
+    /// them, `amount_in` must have been transferred to the pair account. The swap is checked
+    /// against the maximum price impact of the pair.
+    fn swap_through_pair(
+        input: AssetId,
+        output: AssetId,
+        amount_in: AssetBalance,
+        amount_out: AssetBalance,
+        to: &T::AccountId,
+    ) -> DispatchResult {
+        let mut amount0_in = amount_in;
+        let mut amount1_in: AssetBalance = AssetBalance::default();
+        let mut amount0_out: AssetBalance = AssetBalance::default();
+        let mut amount1_out = amount_out;
+
+        let (asset_0, asset_1) = Self::sort_asset_id(input, output);
+        if input != asset_0 {
+            amount0_in = AssetBalance::default();
+            amount1_in = amount_in;
+            amount0_out = amount_out;
+            amount1_out = AssetBalance::default();
+        }
+        let meta = Self::lp_metadata((asset_0, asset_1)).ok_or(Error::<T>::PairNotExists)?;
+        ensure!(meta.status == PairStatus::Enabled, Error::<T>::PairNotEnabled);
+        Self::ensure_pair_not_paused(asset_0, asset_1)?;
+        Self::ensure_pair_price_impact(input, output, amount_in, amount_out)?;
+
+        Self::pair_swap(asset_0, asset_1, &meta.pair_account, amount0_out, amount1_out, to)?;
+        Self::note_pair_swapped(asset_0, asset_1);
+
+        Self::swap_reserves(asset_0, asset_1, amount0_in, amount1_in, amount0_out, amount1_out)
+    }
+
     fn pair_swap(
         asset_0: AssetId,
         asset_1: AssetId,
@@ -472,8 +1426,7 @@ impl<T: Config> Pallet<T> {
         amount_1: AssetBalance,
         recipient: &T::AccountId,
     ) -> DispatchResult {
-        let reserve_0 = T::MultiAssetsHandler::balance_of(asset_0, &pair_account);
-        let reserve_1 = T::MultiAssetsHandler::balance_of(asset_1, &pair_account);
+        let (reserve_0, reserve_1) = Self::get_reserves(asset_0, asset_1);
 
         ensure!(
             amount_0 <= reserve_0 && amount_1 <= reserve_1,
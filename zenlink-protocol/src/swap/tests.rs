@@ -1,9 +1,19 @@
 // Copyright 2020-2021 Zenlink
 // Licensed under GPL-3.0.
 
-use frame_support::{assert_noop, assert_ok};
-
-use super::{mock::*, AssetId, Error, MultiAssetsHandler};
+use codec::Encode;
+use frame_support::{
+    assert_noop, assert_ok,
+    storage::migration::put_storage_value,
+    traits::{GetPalletVersion, Hooks},
+    Hashable,
+};
+use sp_core::U256;
+use sp_runtime::DispatchError;
+
+use super::{AssetId, Error, MultiAssetsHandler, PairMetadata, PairStatus, MINIMUM_LIQUIDITY};
+use crate::calculate_twap_amount;
+use crate::mock::*;
 
 // Native Currency
 const DEV_ASSET_ID: AssetId = AssetId { chain_id: 0, asset_type: NATIVE, asset_index: 0 };
@@ -20,6 +30,7 @@ const PAIR_DOT_BTC: u128 = 64962681870856338328114322245433978733;
 
 const ALICE: u128 = 1;
 const BOB: u128 = 2;
+const CHARLIE: u128 = 3;
 const DOT_UNIT: u128 = 1000_000_000_000_000;
 const BTC_UNIT: u128 = 1000_000_00;
 const ETH_UNIT: u128 = 1000_000_000_000;
@@ -80,7 +91,7 @@ fn add_liquidity_should_work() {
             total_supply_btc,
             0,
             0,
-            100
+            Deadline::BlockNumber(100)
         ));
 
         let total_supply_dot = 50 * DOT_UNIT;
@@ -94,7 +105,7 @@ fn add_liquidity_should_work() {
             total_supply_dot,
             0,
             0,
-            100
+            Deadline::BlockNumber(100)
         ));
 
         let balance_dot =
@@ -230,7 +241,7 @@ fn remove_liquidity_should_work() {
             0u128,
             0u128,
             BOB,
-            100
+            Deadline::BlockNumber(100)
         ));
 
         let balance_dot = <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &BOB);
@@ -517,3 +528,1285 @@ fn inner_swap_assets_for_exact_assets_in_pairs_should_work() {
         assert_eq!(dot_balance, amount_out);
     })
 }
+
+#[test]
+fn price_cumulative_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+
+        assert_ok!(DexPallet::inner_add_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            10000 * DOT_UNIT,
+            10000 * BTC_UNIT,
+            0,
+            0
+        ));
+        assert_eq!(
+            DexPallet::lp_price_cumulative((DOT_ASSET_ID, BTC_ASSET_ID)),
+            (U256::zero(), U256::zero(), 1)
+        );
+
+        System::set_block_number(11);
+        let dot_price_11 = DexPallet::get_price_cumulative(DOT_ASSET_ID, BTC_ASSET_ID).unwrap();
+        let btc_price_11 = DexPallet::get_price_cumulative(BTC_ASSET_ID, DOT_ASSET_ID).unwrap();
+        assert_eq!(calculate_twap_amount(U256::zero(), dot_price_11, 10, DOT_UNIT), 99999999);
+        assert_eq!(calculate_twap_amount(U256::zero(), btc_price_11, 10, BTC_UNIT), DOT_UNIT);
+
+        let path = vec![DOT_ASSET_ID, BTC_ASSET_ID];
+        assert_ok!(DexPallet::inner_swap_exact_assets_for_assets(&ALICE, DOT_UNIT, 0, &path, &BOB));
+        assert_eq!(
+            DexPallet::lp_price_cumulative((DOT_ASSET_ID, BTC_ASSET_ID)),
+            (dot_price_11, btc_price_11, 11)
+        );
+
+        System::set_block_number(21);
+        let dot_price_21 = DexPallet::get_price_cumulative(DOT_ASSET_ID, BTC_ASSET_ID).unwrap();
+        let btc_price_21 = DexPallet::get_price_cumulative(BTC_ASSET_ID, DOT_ASSET_ID).unwrap();
+        assert_eq!(calculate_twap_amount(dot_price_11, dot_price_21, 10, DOT_UNIT), 99980032);
+        assert_eq!(
+            calculate_twap_amount(btc_price_11, btc_price_21, 10, BTC_UNIT),
+            1000199709969098
+        );
+        assert_eq!(calculate_twap_amount(U256::zero(), dot_price_21, 20, DOT_UNIT), 99990016);
+
+        assert_eq!(DexPallet::get_price_cumulative(DOT_ASSET_ID, ETH_ASSET_ID), None);
+    });
+}
+
+#[test]
+fn set_exchange_fee_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+
+        assert_ok!(DexPallet::inner_add_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            10000 * DOT_UNIT,
+            10000 * BTC_UNIT,
+            0,
+            0
+        ));
+        let path = vec![DOT_ASSET_ID, BTC_ASSET_ID];
+        assert_eq!(DexPallet::get_exchange_fee(BTC_ASSET_ID, DOT_ASSET_ID), (3, 1000));
+        assert_eq!(
+            DexPallet::get_amount_out_by_path(DOT_UNIT, &path).unwrap(),
+            vec![DOT_UNIT, 99690060]
+        );
+
+        assert_ok!(DexPallet::set_exchange_fee(
+            Origin::root(),
+            BTC_ASSET_ID,
+            DOT_ASSET_ID,
+            Some((5, 1000))
+        ));
+        assert_eq!(DexPallet::get_exchange_fee(DOT_ASSET_ID, BTC_ASSET_ID), (5, 1000));
+        assert_eq!(
+            DexPallet::get_amount_out_by_path(DOT_UNIT, &path).unwrap(),
+            vec![DOT_UNIT, 99490100]
+        );
+        assert_eq!(DexPallet::supply_out_amount(DOT_UNIT, path.clone()), 99490100);
+
+        assert_ok!(DexPallet::set_exchange_fee(Origin::root(), DOT_ASSET_ID, BTC_ASSET_ID, None));
+        assert_eq!(DexPallet::get_exchange_fee(DOT_ASSET_ID, BTC_ASSET_ID), (3, 1000));
+    });
+}
+
+#[test]
+fn set_exchange_fee_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, 0));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, 0));
+
+        assert_noop!(
+            DexPallet::set_exchange_fee(
+                Origin::root(),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                Some((5, 1000))
+            ),
+            Error::<Test>::PairNotExists
+        );
+
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+
+        assert_noop!(
+            DexPallet::set_exchange_fee(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                Some((5, 1000))
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            DexPallet::set_exchange_fee(Origin::root(), DOT_ASSET_ID, BTC_ASSET_ID, Some((5, 5))),
+            Error::<Test>::InvalidExchangeFee
+        );
+    });
+}
+
+#[test]
+fn protocol_fee_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+
+        assert_noop!(
+            DexPallet::set_fee_receiver(Origin::signed(ALICE), Some(CHARLIE)),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            DexPallet::set_protocol_fee_rate(Origin::root(), 6, 6),
+            Error::<Test>::InvalidProtocolFee
+        );
+        assert_ok!(DexPallet::set_fee_receiver(Origin::root(), Some(CHARLIE)));
+        assert_ok!(DexPallet::set_protocol_fee_rate(Origin::root(), 1, 6));
+        assert_eq!(DexPallet::fee_meta(), (Some(CHARLIE), (1, 6)));
+
+        assert_ok!(DexPallet::inner_add_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            10000 * DOT_UNIT,
+            10000 * BTC_UNIT,
+            0,
+            0
+        ));
+        assert_eq!(
+            DexPallet::k_last((DOT_ASSET_ID, BTC_ASSET_ID)),
+            U256::from(10000 * DOT_UNIT) * U256::from(10000 * BTC_UNIT)
+        );
+        assert_eq!(DexPallet::lp_ledger(((DOT_ASSET_ID, BTC_ASSET_ID), CHARLIE)), 0);
+
+        let path = vec![DOT_ASSET_ID, BTC_ASSET_ID];
+        assert_ok!(DexPallet::inner_swap_exact_assets_for_assets(
+            &ALICE,
+            1000 * DOT_UNIT,
+            0,
+            &path,
+            &BOB
+        ));
+
+        assert_ok!(DexPallet::inner_remove_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            BTC_UNIT,
+            0,
+            0,
+            &BOB
+        ));
+        assert_eq!(DexPallet::lp_ledger(((DOT_ASSET_ID, BTC_ASSET_ID), CHARLIE)), 71876481386);
+        assert_eq!(
+            DexPallet::lp_metadata((DOT_ASSET_ID, BTC_ASSET_ID)).unwrap().total_supply,
+            3162277660168379 + 71876481386 - BTC_UNIT
+        );
+
+        assert_ok!(DexPallet::set_fee_receiver(Origin::root(), None));
+        assert_ok!(DexPallet::inner_remove_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            BTC_UNIT,
+            0,
+            0,
+            &BOB
+        ));
+        assert_eq!(DexPallet::k_last((DOT_ASSET_ID, BTC_ASSET_ID)), U256::zero());
+        assert_eq!(DexPallet::lp_ledger(((DOT_ASSET_ID, BTC_ASSET_ID), CHARLIE)), 71876481386);
+    });
+}
+
+#[test]
+fn first_add_liquidity_should_lock_minimum_liquidity() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+
+        assert_noop!(
+            DexPallet::inner_add_liquidity(&ALICE, DOT_ASSET_ID, BTC_ASSET_ID, 1000, 1000, 0, 0),
+            Error::<Test>::InsufficientLiquidity
+        );
+
+        assert_ok!(DexPallet::inner_add_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            10000 * DOT_UNIT,
+            10000 * BTC_UNIT,
+            0,
+            0
+        ));
+
+        let lock_account = DexPallet::lock_account_id();
+        assert_eq!(
+            DexPallet::lp_ledger(((DOT_ASSET_ID, BTC_ASSET_ID), lock_account)),
+            MINIMUM_LIQUIDITY
+        );
+        assert_eq!(
+            DexPallet::lp_ledger(((DOT_ASSET_ID, BTC_ASSET_ID), ALICE)),
+            3162277660168379 - MINIMUM_LIQUIDITY
+        );
+        assert_eq!(
+            DexPallet::lp_metadata((DOT_ASSET_ID, BTC_ASSET_ID)).unwrap().total_supply,
+            3162277660168379
+        );
+    });
+}
+
+#[test]
+fn donation_attack_should_not_work() {
+    new_test_ext().execute_with(|| {
+        let donation = 1000 * DOT_UNIT;
+        let deposit = 100 * DOT_UNIT;
+
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, donation + 2000));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, donation + 2000));
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &BOB, deposit));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &BOB, deposit));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+
+        // The attacker can't hold the whole supply of a tiny pair.
+        assert_noop!(
+            DexPallet::inner_add_liquidity(&ALICE, DOT_ASSET_ID, BTC_ASSET_ID, 1, 1, 0, 0),
+            Error::<Test>::InsufficientLiquidity
+        );
+        assert_ok!(DexPallet::inner_add_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            2000,
+            2000,
+            0,
+            0
+        ));
+        assert_eq!(DexPallet::lp_ledger(((DOT_ASSET_ID, BTC_ASSET_ID), ALICE)), 1000);
+
+        // Inflate the price of one liquidity unit by donating to the pair account.
+        assert_ok!(<Test as Config>::MultiAssetsHandler::transfer(
+            DOT_ASSET_ID,
+            &ALICE,
+            &PAIR_DOT_BTC,
+            donation
+        ));
+        assert_ok!(<Test as Config>::MultiAssetsHandler::transfer(
+            BTC_ASSET_ID,
+            &ALICE,
+            &PAIR_DOT_BTC,
+            donation
+        ));
+
+        // The donation only counts once the pair is synced.
+        assert_ok!(DexPallet::sync(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+
+        // The victim still gets liquidity for a deposit much smaller than the donation.
+        assert_ok!(DexPallet::inner_add_liquidity(
+            &BOB,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            deposit,
+            deposit,
+            0,
+            0
+        ));
+        assert_eq!(DexPallet::lp_ledger(((DOT_ASSET_ID, BTC_ASSET_ID), BOB)), 199);
+
+        // The attacker loses about half of the donation to the locked liquidity.
+        assert_ok!(DexPallet::inner_remove_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            1000,
+            0,
+            0,
+            &ALICE
+        ));
+        let dot_balance = <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &ALICE);
+        let btc_balance = <Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &ALICE);
+        assert_eq!(dot_balance, 500227376080037289);
+        assert_eq!(btc_balance, 500227376080037289);
+        assert!(dot_balance < (donation + 2000) * 51 / 100);
+    });
+}
+
+#[test]
+fn skim_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+        assert_ok!(DexPallet::add_liquidity(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            DOT_UNIT,
+            BTC_UNIT,
+            0,
+            0,
+            Deadline::BlockNumber(100)
+        ));
+
+        let path = vec![DOT_ASSET_ID, BTC_ASSET_ID];
+        let amount_out = DexPallet::supply_out_amount(DOT_UNIT / 100, path.clone());
+
+        // A direct transfer to the pair account doesn't change the price.
+        assert_ok!(<Test as Config>::MultiAssetsHandler::transfer(
+            DOT_ASSET_ID,
+            &ALICE,
+            &PAIR_DOT_BTC,
+            10 * DOT_UNIT
+        ));
+        assert_eq!(DexPallet::get_reserves(DOT_ASSET_ID, BTC_ASSET_ID), (DOT_UNIT, BTC_UNIT));
+        assert_eq!(DexPallet::supply_out_amount(DOT_UNIT / 100, path.clone()), amount_out);
+
+        assert_ok!(DexPallet::skim(Origin::signed(BOB), BTC_ASSET_ID, DOT_ASSET_ID, CHARLIE));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &CHARLIE),
+            10 * DOT_UNIT
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &PAIR_DOT_BTC),
+            DOT_UNIT
+        );
+        assert_eq!(<Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &CHARLIE), 0);
+
+        assert_noop!(
+            DexPallet::skim(Origin::signed(BOB), DOT_ASSET_ID, ETH_ASSET_ID, CHARLIE),
+            Error::<Test>::PairNotExists
+        );
+    });
+}
+
+#[test]
+fn sync_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+        assert_ok!(DexPallet::add_liquidity(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            DOT_UNIT,
+            BTC_UNIT,
+            0,
+            0,
+            Deadline::BlockNumber(100)
+        ));
+
+        let path = vec![DOT_ASSET_ID, BTC_ASSET_ID];
+        let amount_out = DexPallet::supply_out_amount(DOT_UNIT / 100, path.clone());
+
+        assert_ok!(<Test as Config>::MultiAssetsHandler::transfer(
+            BTC_ASSET_ID,
+            &ALICE,
+            &PAIR_DOT_BTC,
+            BTC_UNIT
+        ));
+        assert_ok!(DexPallet::sync(Origin::signed(BOB), BTC_ASSET_ID, DOT_ASSET_ID));
+
+        assert_eq!(DexPallet::get_reserves(DOT_ASSET_ID, BTC_ASSET_ID), (DOT_UNIT, 2 * BTC_UNIT));
+        assert_eq!(DexPallet::get_reserves(BTC_ASSET_ID, DOT_ASSET_ID), (2 * BTC_UNIT, DOT_UNIT));
+        assert!(DexPallet::supply_out_amount(DOT_UNIT / 100, path) > amount_out);
+
+        assert_noop!(
+            DexPallet::sync(Origin::signed(BOB), DOT_ASSET_ID, ETH_ASSET_ID),
+            Error::<Test>::PairNotExists
+        );
+    });
+}
+
+#[test]
+fn flash_swap_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+        assert_ok!(DexPallet::add_liquidity(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            100 * DOT_UNIT,
+            100 * BTC_UNIT,
+            0,
+            0,
+            Deadline::BlockNumber(100)
+        ));
+
+        // Pay back 10 BTC plus the exchange fee, ceil(10 BTC * 1000 / 997).
+        assert_ok!(DexPallet::flash_swap(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            0,
+            10 * BTC_UNIT,
+            BOB,
+            (0u128, 1003009028u128, false).encode()
+        ));
+
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &BOB),
+            10 * BTC_UNIT
+        );
+        assert_eq!(
+            DexPallet::get_reserves(DOT_ASSET_ID, BTC_ASSET_ID),
+            (100 * DOT_UNIT, 10003009028)
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &PAIR_DOT_BTC),
+            10003009028
+        );
+        assert!(!DexPallet::flash_swap_locked((DOT_ASSET_ID, BTC_ASSET_ID)));
+    });
+}
+
+#[test]
+fn flash_swap_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+        assert_ok!(DexPallet::add_liquidity(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            100 * DOT_UNIT,
+            100 * BTC_UNIT,
+            0,
+            0,
+            Deadline::BlockNumber(100)
+        ));
+
+        assert_noop!(
+            DexPallet::flash_swap(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                ETH_ASSET_ID,
+                0,
+                ETH_UNIT,
+                BOB,
+                (0u128, 0u128, false).encode()
+            ),
+            Error::<Test>::PairNotExists
+        );
+
+        assert_noop!(
+            DexPallet::flash_swap(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                0,
+                100 * BTC_UNIT,
+                BOB,
+                (0u128, 0u128, false).encode()
+            ),
+            Error::<Test>::InsufficientPairReserve
+        );
+
+        assert_noop!(
+            DexPallet::flash_swap(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                0,
+                10 * BTC_UNIT,
+                BOB,
+                (0u128, 0u128, false).encode()
+            ),
+            Error::<Test>::InsufficientInputAmount
+        );
+
+        // One unit short of the exchange fee.
+        assert_noop!(
+            DexPallet::flash_swap(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                0,
+                10 * BTC_UNIT,
+                BOB,
+                (0u128, 1003009027u128, false).encode()
+            ),
+            Error::<Test>::InvalidInvariant
+        );
+
+        // The borrowed pair can't be traded before it is paid back.
+        assert_noop!(
+            DexPallet::flash_swap(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                0,
+                10 * BTC_UNIT,
+                BOB,
+                (DOT_UNIT, 1003009028u128, true).encode()
+            ),
+            Error::<Test>::PairLocked
+        );
+    });
+}
+
+#[test]
+fn add_liquidity_single_asset_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &BOB, 100 * BTC_UNIT));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+
+        assert_noop!(
+            DexPallet::add_liquidity_single_asset(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                100 * DOT_UNIT,
+                0,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientPairReserve
+        );
+        assert_ok!(DexPallet::inner_add_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            50000 * DOT_UNIT,
+            100000 * BTC_UNIT,
+            0,
+            0
+        ));
+
+        let pair = (DOT_ASSET_ID, BTC_ASSET_ID);
+        let liquidity = DexPallet::lp_ledger((pair, ALICE));
+        let dot_balance = <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &ALICE);
+        let btc_balance = <Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &ALICE);
+
+        assert_eq!(
+            DexPallet::get_estimate_lptoken_single_asset(
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                100 * DOT_UNIT
+            ),
+            22315935998441
+        );
+        assert_noop!(
+            DexPallet::add_liquidity_single_asset(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                100 * DOT_UNIT,
+                22315935998442,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientTargetAmount
+        );
+        assert_ok!(DexPallet::add_liquidity_single_asset(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            100 * DOT_UNIT,
+            22315935998441,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(DexPallet::lp_ledger((pair, ALICE)), liquidity + 22315935998441);
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &ALICE),
+            dot_balance - 100 * DOT_UNIT
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &ALICE),
+            btc_balance
+        );
+        assert_eq!(
+            DexPallet::get_reserves(DOT_ASSET_ID, BTC_ASSET_ID),
+            (50100 * DOT_UNIT, 100000 * BTC_UNIT)
+        );
+
+        // The dust is left to the caller.
+        assert_eq!(
+            DexPallet::get_estimate_lptoken_single_asset(
+                BTC_ASSET_ID,
+                DOT_ASSET_ID,
+                100 * BTC_UNIT
+            ),
+            11171893119709
+        );
+        assert_ok!(DexPallet::add_liquidity_single_asset(
+            Origin::signed(BOB),
+            BTC_ASSET_ID,
+            DOT_ASSET_ID,
+            100 * BTC_UNIT,
+            11171893119709,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(DexPallet::lp_ledger((pair, BOB)), 11171893119709);
+        assert_eq!(<Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &BOB), 1);
+        assert_eq!(<Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &BOB), 0);
+
+        assert_noop!(
+            DexPallet::add_liquidity_single_asset(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                ETH_ASSET_ID,
+                100 * DOT_UNIT,
+                0,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::PairNotExists
+        );
+    });
+}
+
+#[test]
+fn remove_liquidity_single_asset_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+        assert_ok!(DexPallet::inner_add_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            50000 * DOT_UNIT,
+            100000 * BTC_UNIT,
+            0,
+            0
+        ));
+
+        let pair = (DOT_ASSET_ID, BTC_ASSET_ID);
+        let total_liquidity = DexPallet::lp_metadata(pair).unwrap().total_supply;
+        let liquidity = DexPallet::lp_ledger((pair, ALICE));
+        let remove_liquidity = total_liquidity / 100;
+
+        assert_eq!(
+            DexPallet::get_estimate_remove_liquidity_single_asset(
+                BTC_ASSET_ID,
+                DOT_ASSET_ID,
+                remove_liquidity
+            ),
+            198705961177
+        );
+        assert_noop!(
+            DexPallet::remove_liquidity_single_asset(
+                Origin::signed(ALICE),
+                BTC_ASSET_ID,
+                DOT_ASSET_ID,
+                remove_liquidity,
+                198705961178,
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientTargetAmount
+        );
+        assert_ok!(DexPallet::remove_liquidity_single_asset(
+            Origin::signed(ALICE),
+            BTC_ASSET_ID,
+            DOT_ASSET_ID,
+            remove_liquidity,
+            198705961177,
+            BOB,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(DexPallet::lp_ledger((pair, ALICE)), liquidity - remove_liquidity);
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &BOB),
+            198705961177
+        );
+        assert_eq!(<Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &BOB), 0);
+
+        assert_eq!(
+            DexPallet::get_estimate_remove_liquidity_single_asset(
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                remove_liquidity
+            ),
+            1003514747870024972
+        );
+        assert_ok!(DexPallet::remove_liquidity_single_asset(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            remove_liquidity,
+            1003514747870024972,
+            CHARLIE,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &CHARLIE),
+            1003514747870024972
+        );
+        assert_eq!(<Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &CHARLIE), 0);
+
+        assert_noop!(
+            DexPallet::remove_liquidity_single_asset(
+                Origin::signed(BOB),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                1,
+                0,
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientLiquidity
+        );
+    });
+}
+
+#[test]
+fn remove_dust_liquidity_single_asset_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+        assert_ok!(DexPallet::inner_add_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            50000 * DOT_UNIT,
+            100000 * BTC_UNIT,
+            0,
+            0
+        ));
+
+        // One liquidity is 2236 DOT and no BTC, the DOT swaps for no BTC.
+        assert_eq!(
+            DexPallet::get_estimate_remove_liquidity_single_asset(BTC_ASSET_ID, DOT_ASSET_ID, 1),
+            0
+        );
+        assert_noop!(
+            DexPallet::remove_liquidity_single_asset(
+                Origin::signed(ALICE),
+                BTC_ASSET_ID,
+                DOT_ASSET_ID,
+                1,
+                0,
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientTargetAmount
+        );
+        assert_eq!(<Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &BOB), 0);
+        assert_eq!(<Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &BOB), 0);
+    });
+}
+
+#[test]
+fn set_pair_status_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_noop!(
+            DexPallet::set_pair_status(
+                Origin::root(),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                PairStatus::Disabled
+            ),
+            Error::<Test>::PairNotExists
+        );
+
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+        assert_eq!(
+            DexPallet::lp_metadata((DOT_ASSET_ID, BTC_ASSET_ID)).unwrap().status,
+            PairStatus::Enabled
+        );
+        assert_ok!(DexPallet::inner_add_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            1000 * DOT_UNIT,
+            1000 * BTC_UNIT,
+            0,
+            0
+        ));
+
+        assert_noop!(
+            DexPallet::set_pair_status(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                PairStatus::Disabled
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            DexPallet::set_pair_status(
+                Origin::root(),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                PairStatus::Bootstrap
+            ),
+            Error::<Test>::InvalidPairStatus
+        );
+
+        assert_ok!(DexPallet::set_pair_status(
+            Origin::root(),
+            BTC_ASSET_ID,
+            DOT_ASSET_ID,
+            PairStatus::Disabled
+        ));
+        assert!(!DexPallet::is_pair_enabled(DOT_ASSET_ID, BTC_ASSET_ID));
+        assert_noop!(
+            DexPallet::inner_add_liquidity(
+                &ALICE,
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                DOT_UNIT,
+                BTC_UNIT,
+                0,
+                0
+            ),
+            Error::<Test>::PairNotEnabled
+        );
+        assert_noop!(
+            DexPallet::inner_swap_exact_tokens_for_tokens(
+                &ALICE,
+                DOT_UNIT,
+                0,
+                &[DOT_ASSET_ID, BTC_ASSET_ID],
+                &ALICE
+            ),
+            Error::<Test>::PairNotEnabled
+        );
+        assert_noop!(
+            DexPallet::inner_swap_tokens_for_exact_tokens(
+                &ALICE,
+                BTC_UNIT,
+                u128::MAX,
+                &[DOT_ASSET_ID, BTC_ASSET_ID],
+                &ALICE
+            ),
+            Error::<Test>::PairNotEnabled
+        );
+
+        assert_ok!(DexPallet::set_pair_status(
+            Origin::root(),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            PairStatus::Delisted
+        ));
+        assert_noop!(
+            DexPallet::set_pair_status(
+                Origin::root(),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                PairStatus::Enabled
+            ),
+            Error::<Test>::InvalidPairStatus
+        );
+
+        let liquidity = DexPallet::lp_ledger(((DOT_ASSET_ID, BTC_ASSET_ID), ALICE));
+        assert_ok!(DexPallet::remove_liquidity(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            liquidity,
+            0,
+            0,
+            BOB,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(DexPallet::lp_ledger(((DOT_ASSET_ID, BTC_ASSET_ID), ALICE)), 0);
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &BOB),
+            999999999996837722
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &BOB),
+            99999999999
+        );
+    });
+}
+
+#[test]
+fn create_pair_with_allowlist_and_deposit_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        PairCreationDeposit::set(100);
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, DOT_UNIT));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, BTC_UNIT));
+        assert_ok!(DexPallet::foreign_mint(ETH_ASSET_ID, &ALICE, ETH_UNIT));
+
+        assert_noop!(
+            DexPallet::set_asset_allowlist(
+                Origin::signed(ALICE),
+                Some(vec![DOT_ASSET_ID, BTC_ASSET_ID])
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(DexPallet::set_asset_allowlist(
+            Origin::root(),
+            Some(vec![DOT_ASSET_ID, BTC_ASSET_ID])
+        ));
+        assert!(System::events().iter().any(|record| record.event
+            == Event::Zenlink(crate::Event::AssetAllowlistChanged(Some(vec![
+                DOT_ASSET_ID,
+                BTC_ASSET_ID
+            ])))));
+
+        assert_noop!(
+            DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, ETH_ASSET_ID),
+            Error::<Test>::AssetNotAllowed
+        );
+        assert_noop!(
+            DexPallet::create_pair(Origin::signed(BOB), DOT_ASSET_ID, BTC_ASSET_ID),
+            pallet_balances::Error::<Test>::InsufficientBalance
+        );
+        assert!(DexPallet::lp_metadata((DOT_ASSET_ID, BTC_ASSET_ID)).is_none());
+
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+        assert_eq!(Balances::reserved_balance(ALICE), 100);
+        assert_eq!(DexPallet::pair_deposit((DOT_ASSET_ID, BTC_ASSET_ID)), Some((ALICE, 100)));
+
+        assert_ok!(DexPallet::set_pair_status(
+            Origin::root(),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            PairStatus::Disabled
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 100);
+
+        assert_ok!(DexPallet::set_pair_status(
+            Origin::root(),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            PairStatus::Delisted
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(DexPallet::pair_deposit((DOT_ASSET_ID, BTC_ASSET_ID)), None);
+
+        assert_ok!(DexPallet::set_asset_allowlist(Origin::root(), None));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, ETH_ASSET_ID));
+        assert_eq!(Balances::reserved_balance(ALICE), 100);
+    });
+}
+
+#[test]
+fn max_price_impact_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+        assert_ok!(DexPallet::inner_add_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            1000 * DOT_UNIT,
+            1000 * BTC_UNIT,
+            0,
+            0
+        ));
+
+        let path = vec![DOT_ASSET_ID, BTC_ASSET_ID];
+        assert_eq!(
+            DexPallet::get_price_impact_by_path(10 * DOT_UNIT, &path),
+            Ok(vec![19674832019801980])
+        );
+
+        assert_noop!(
+            DexPallet::set_global_max_price_impact(Origin::signed(ALICE), Some((1, 100))),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            DexPallet::set_global_max_price_impact(Origin::root(), Some((0, 100))),
+            Error::<Test>::InvalidPriceImpact
+        );
+        assert_noop!(
+            DexPallet::set_global_max_price_impact(Origin::root(), Some((100, 100))),
+            Error::<Test>::InvalidPriceImpact
+        );
+        assert_ok!(DexPallet::set_global_max_price_impact(Origin::root(), Some((1, 100))));
+        assert!(System::events().iter().any(|record| record.event
+            == Event::Zenlink(crate::Event::GlobalMaxPriceImpactChanged(Some((1, 100))))));
+
+        assert_noop!(
+            DexPallet::inner_swap_exact_tokens_for_tokens(&ALICE, 10 * DOT_UNIT, 0, &path, &BOB),
+            Error::<Test>::ExcessivePriceImpact
+        );
+        assert_noop!(
+            DexPallet::inner_swap_tokens_for_exact_tokens(
+                &ALICE,
+                10 * BTC_UNIT,
+                u128::MAX,
+                &path,
+                &BOB
+            ),
+            Error::<Test>::ExcessivePriceImpact
+        );
+        assert_ok!(DexPallet::inner_swap_exact_tokens_for_tokens(&ALICE, DOT_UNIT, 0, &path, &BOB));
+        assert_eq!(<Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &BOB), 99600698);
+
+        assert_ok!(DexPallet::set_pair_max_price_impact(
+            Origin::root(),
+            BTC_ASSET_ID,
+            DOT_ASSET_ID,
+            Some((3, 100))
+        ));
+        assert!(System::events().iter().any(|record| record.event
+            == Event::Zenlink(crate::Event::PairMaxPriceImpactChanged(
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                Some((3, 100))
+            ))));
+        assert_eq!(DexPallet::get_max_price_impact(DOT_ASSET_ID, BTC_ASSET_ID), Some((3, 100)));
+        assert_ok!(DexPallet::inner_swap_exact_tokens_for_tokens(
+            &ALICE,
+            10 * DOT_UNIT,
+            0,
+            &path,
+            &BOB
+        ));
+
+        assert_ok!(DexPallet::set_pair_max_price_impact(
+            Origin::root(),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            None
+        ));
+        assert_eq!(DexPallet::get_max_price_impact(DOT_ASSET_ID, BTC_ASSET_ID), Some((1, 100)));
+        assert_ok!(DexPallet::set_global_max_price_impact(Origin::root(), None));
+        assert_eq!(DexPallet::get_max_price_impact(DOT_ASSET_ID, BTC_ASSET_ID), None);
+    });
+}
+
+#[test]
+fn deadline_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+        System::set_block_number(10);
+        Timestamp::set_timestamp(12_000);
+
+        assert_noop!(
+            DexPallet::add_liquidity(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                DOT_UNIT,
+                BTC_UNIT,
+                0,
+                0,
+                Deadline::BlockNumber(10)
+            ),
+            Error::<Test>::Deadline
+        );
+        assert_ok!(DexPallet::add_liquidity(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            DOT_UNIT,
+            BTC_UNIT,
+            0,
+            0,
+            Deadline::BlockNumber(11)
+        ));
+
+        assert_noop!(
+            DexPallet::add_liquidity(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                DOT_UNIT,
+                BTC_UNIT,
+                0,
+                0,
+                Deadline::Timestamp(12_000)
+            ),
+            Error::<Test>::Deadline
+        );
+        assert_ok!(DexPallet::add_liquidity(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            DOT_UNIT,
+            BTC_UNIT,
+            0,
+            0,
+            Deadline::Timestamp(12_001)
+        ));
+
+        let liquidity = DexPallet::lp_ledger(((DOT_ASSET_ID, BTC_ASSET_ID), ALICE));
+        assert_noop!(
+            DexPallet::remove_liquidity(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                liquidity,
+                0,
+                0,
+                ALICE,
+                Deadline::Timestamp(11_999)
+            ),
+            Error::<Test>::Deadline
+        );
+        assert_ok!(DexPallet::remove_liquidity(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            liquidity,
+            0,
+            0,
+            ALICE,
+            Deadline::Timestamp(12_001)
+        ));
+        assert_eq!(DexPallet::lp_ledger(((DOT_ASSET_ID, BTC_ASSET_ID), ALICE)), 0);
+    });
+}
+
+#[test]
+fn migrate_to_pair_metadata_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(DexPallet::storage_version(), None);
+
+        // The pair stored as (PairAccountId, TotalSupply) before the migration.
+        let pair = (DOT_ASSET_ID, BTC_ASSET_ID);
+        let pair_account = DexPallet::pair_account_id(DOT_ASSET_ID, BTC_ASSET_ID);
+        put_storage_value(
+            b"Zenlink",
+            b"LiquidityMeta",
+            &pair.encode().twox_64_concat(),
+            (pair_account, 1000 * DOT_UNIT),
+        );
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &pair_account, 1000 * DOT_UNIT));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &pair_account, 1000 * BTC_UNIT));
+        assert_eq!(DexPallet::lp_metadata(pair), None);
+
+        DexPallet::on_runtime_upgrade();
+
+        assert_eq!(
+            DexPallet::lp_metadata(pair),
+            Some(PairMetadata {
+                pair_account,
+                total_supply: 1000 * DOT_UNIT,
+                reserve_0: 1000 * DOT_UNIT,
+                reserve_1: 1000 * BTC_UNIT,
+                status: PairStatus::Enabled,
+            })
+        );
+        assert_eq!(
+            DexPallet::get_reserves(DOT_ASSET_ID, BTC_ASSET_ID),
+            (1000 * DOT_UNIT, 1000 * BTC_UNIT)
+        );
+    });
+}
+
+#[test]
+fn migrated_pair_should_be_enabled() {
+    new_test_ext().execute_with(|| {
+        let pair = (DOT_ASSET_ID, BTC_ASSET_ID);
+        let pair_account = DexPallet::pair_account_id(DOT_ASSET_ID, BTC_ASSET_ID);
+        put_storage_value(
+            b"Zenlink",
+            b"LiquidityMeta",
+            &pair.encode().twox_64_concat(),
+            (pair_account, 1000 * DOT_UNIT),
+        );
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &pair_account, 1000 * DOT_UNIT));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &pair_account, 1000 * BTC_UNIT));
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, 10 * DOT_UNIT));
+
+        DexPallet::on_runtime_upgrade();
+
+        assert!(DexPallet::is_pair_enabled(DOT_ASSET_ID, BTC_ASSET_ID));
+        assert_ok!(DexPallet::inner_swap_exact_tokens_for_tokens(
+            &ALICE,
+            DOT_UNIT,
+            0,
+            &[DOT_ASSET_ID, BTC_ASSET_ID],
+            &ALICE
+        ));
+
+        assert_ok!(DexPallet::set_pair_status(
+            Origin::root(),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            PairStatus::Disabled
+        ));
+        assert_noop!(
+            DexPallet::inner_swap_exact_tokens_for_tokens(
+                &ALICE,
+                DOT_UNIT,
+                0,
+                &[DOT_ASSET_ID, BTC_ASSET_ID],
+                &ALICE
+            ),
+            Error::<Test>::PairNotEnabled
+        );
+    });
+}
+
+#[test]
+fn max_price_impact_of_single_asset_liquidity_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+        assert_ok!(DexPallet::inner_add_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            1000 * DOT_UNIT,
+            1000 * BTC_UNIT,
+            0,
+            0
+        ));
+        assert_ok!(DexPallet::set_global_max_price_impact(Origin::root(), Some((1, 100))));
+
+        // The half of 100 DOT swapped for BTC moves the price by about 9%.
+        assert_noop!(
+            DexPallet::add_liquidity_single_asset(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                100 * DOT_UNIT,
+                0,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::ExcessivePriceImpact
+        );
+        assert_ok!(DexPallet::add_liquidity_single_asset(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            DOT_UNIT,
+            0,
+            Deadline::BlockNumber(100)
+        ));
+
+        // The BTC of half of the liquidity swapped for DOT moves the price by about 75%.
+        let liquidity = DexPallet::lp_ledger(((DOT_ASSET_ID, BTC_ASSET_ID), ALICE));
+        assert_noop!(
+            DexPallet::remove_liquidity_single_asset(
+                Origin::signed(ALICE),
+                DOT_ASSET_ID,
+                BTC_ASSET_ID,
+                liquidity / 2,
+                0,
+                ALICE,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::ExcessivePriceImpact
+        );
+        assert_ok!(DexPallet::remove_liquidity_single_asset(
+            Origin::signed(ALICE),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            liquidity / 1000,
+            0,
+            ALICE,
+            Deadline::BlockNumber(100)
+        ));
+    });
+}
@@ -126,3 +126,66 @@ impl<AccountId> OtherAssetHandler<AccountId> for () {
         unimplemented!()
     }
 }
+
+/// The borrower of a flash swap.
+pub trait FlashSwapHandler<AccountId> {
+    /// Called after `amount_0` of `asset_0` and `amount_1` of `asset_1` are sent to `recipient`.
+    ///
+    /// The borrowed assets plus the exchange fee must be paid back to the pair account
+    /// before returning, in either asset of the pair.
+    fn on_flash_swap(
+        who: &AccountId,
+        recipient: &AccountId,
+        asset_0: AssetId,
+        asset_1: AssetId,
+        amount_0: AssetBalance,
+        amount_1: AssetBalance,
+        data: &[u8],
+    ) -> DispatchResult;
+}
+
+impl<AccountId> FlashSwapHandler<AccountId> for () {
+    fn on_flash_swap(
+        _who: &AccountId,
+        _recipient: &AccountId,
+        _asset_0: AssetId,
+        _asset_1: AssetId,
+        _amount_0: AssetBalance,
+        _amount_1: AssetBalance,
+        _data: &[u8],
+    ) -> DispatchResult {
+        Ok(())
+    }
+}
+
+/// The verifier of the signatures of the swap orders signed off-chain.
+pub trait OrderSignatureVerifier<AccountId> {
+    /// The signature of an order.
+    type Signature: Parameter;
+
+    /// Whether `signature` is of `message` signed by `signer`.
+    fn verify(signature: &Self::Signature, message: &[u8], signer: &AccountId) -> bool;
+}
+
+impl<AccountId> OrderSignatureVerifier<AccountId> for () {
+    type Signature = ();
+
+    fn verify(_signature: &Self::Signature, _message: &[u8], _signer: &AccountId) -> bool {
+        false
+    }
+}
+
+/// The verifier of the signatures of a `Verify` type, such as `MultiSignature`.
+pub struct VerifySignature<Signature>(PhantomData<Signature>);
+
+impl<AccountId, Signature> OrderSignatureVerifier<AccountId> for VerifySignature<Signature>
+where
+    Signature: Verify + Parameter,
+    Signature::Signer: IdentifyAccount<AccountId = AccountId>,
+{
+    type Signature = Signature;
+
+    fn verify(signature: &Self::Signature, message: &[u8], signer: &AccountId) -> bool {
+        signature.verify(message, signer)
+    }
+}
@@ -0,0 +1,729 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+//! # Weighted Pool Module
+//!
+//! ## Overview
+//!
+//! Built-in pools of multiple assets with configurable weights in Zenlink
+//! Protocol, the swap mechanism refers to the design of Balancer.
+
+use super::*;
+
+/// The fixed point one of the weighted math.
+pub const BONE: u128 = 1_000_000_000_000_000_000;
+/// The liquidity minted on the first deposit of a weighted pool.
+pub const INIT_POOL_SUPPLY: AssetBalance = 100 * BONE;
+/// The min normalized weight of an asset in a weighted pool.
+pub const MIN_WEIGHT: u128 = BONE / 50;
+/// The max number of assets in a weighted pool.
+pub const MAX_WEIGHTED_ASSETS: usize = 8;
+/// The amount in of a swap or a single asset deposit must be no more than this fraction of
+/// the reserve.
+pub const MAX_IN_RATIO: u128 = BONE / 2;
+/// The amount out of a swap or a single asset withdrawal must be no more than this fraction
+/// of the reserve.
+pub const MAX_OUT_RATIO: u128 = BONE / 3 + 1;
+/// The liquidity asset of weighted pool `n` is the `LIQUIDITY` asset of index
+/// `WEIGHTED_LP_INDEX_OFFSET + n`.
+pub const WEIGHTED_LP_INDEX_OFFSET: u32 = 1 << 31;
+
+const BPOW_PRECISION: u128 = BONE / 10_000_000_000;
+const MAX_BPOW_ITERATIONS: u32 = 255;
+
+#[cfg(test)]
+mod tests;
+
+impl<T: Config> Pallet<T> {
+    /// The account ID of a weighted pool account
+    pub fn weighted_pool_account_id(pool_id: u32) -> T::AccountId {
+        T::PalletId::get().into_sub_account((b"weighted", pool_id))
+    }
+
+    /// The liquidity asset ID of a weighted pool
+    pub fn weighted_lp_asset_id(pool_id: u32) -> AssetId {
+        AssetId {
+            chain_id: T::SelfParaId::get(),
+            asset_type: LIQUIDITY,
+            asset_index: WEIGHTED_LP_INDEX_OFFSET + pool_id,
+        }
+    }
+
+    /// The weighted pool of the liquidity asset index, if it is in the range of weighted pools.
+    pub(crate) fn weighted_pool_id_of_lp(asset_index: u32) -> Option<u32> {
+        asset_index.checked_sub(WEIGHTED_LP_INDEX_OFFSET)
+    }
+
+    pub fn inner_create_weighted_pool(
+        assets: Vec<AssetId>,
+        weights: Vec<u128>,
+        fee_numerator: u32,
+        fee_denominator: u32,
+    ) -> DispatchResult {
+        ensure!(
+            assets.len() >= 2 && assets.len() <= MAX_WEIGHTED_ASSETS,
+            Error::<T>::InvalidPoolAssets
+        );
+        ensure!(assets.iter().all(|id| id.is_support()), Error::<T>::UnsupportedAssetType);
+        ensure!(
+            assets.iter().all(|id| T::MultiAssetsHandler::is_exists(*id)),
+            Error::<T>::AssetNotExists
+        );
+        let mut sorted_assets = assets.clone();
+        sorted_assets.sort();
+        sorted_assets.dedup();
+        ensure!(sorted_assets.len() == assets.len(), Error::<T>::InvalidPoolAssets);
+        ensure!(fee_numerator < fee_denominator, Error::<T>::InvalidExchangeFee);
+
+        ensure!(weights.len() == assets.len(), Error::<T>::InvalidWeights);
+        let total_weight = weights
+            .iter()
+            .try_fold(0u128, |total, weight| total.checked_add(*weight))
+            .ok_or(Error::<T>::InvalidWeights)?;
+        let weights = weights
+            .iter()
+            .map(|weight| {
+                U256::from(*weight)
+                    .saturating_mul(U256::from(BONE))
+                    .checked_div(U256::from(total_weight))
+                    .map(|weight| weight.low_u128())
+                    .filter(|weight| *weight >= MIN_WEIGHT)
+            })
+            .collect::<Option<Vec<u128>>>()
+            .ok_or(Error::<T>::InvalidWeights)?;
+
+        let pool_id = Self::next_weighted_pool_id();
+        ensure!(pool_id < WEIGHTED_LP_INDEX_OFFSET, Error::<T>::Overflow);
+
+        WeightedPools::<T>::insert(
+            pool_id,
+            WeightedPool {
+                pool_account: Self::weighted_pool_account_id(pool_id),
+                assets: assets.clone(),
+                weights: weights.clone(),
+                reserves: vec![Zero::zero(); assets.len()],
+                total_supply: Zero::zero(),
+                fee: (fee_numerator, fee_denominator),
+            },
+        );
+        NextWeightedPoolId::<T>::put(pool_id + 1);
+
+        Self::note_weighted_pool_of_assets(pool_id, &assets);
+
+        Self::deposit_event(Event::WeightedPoolCreated(pool_id, assets, weights));
+
+        Ok(())
+    }
+
+    /// Whether the weighted pool has liquidity to swap through.
+    pub(crate) fn is_weighted_pool_liquid(pool_id: u32) -> bool {
+        Self::weighted_pool(pool_id).map_or(false, |pool| !pool.total_supply.is_zero())
+    }
+
+    /// Record the weighted pool for the swaps between its assets, unless the pool recorded for
+    /// two of them has liquidity.
+    fn note_weighted_pool_of_assets(pool_id: u32, assets: &[AssetId]) {
+        for (i, asset_0) in assets.iter().enumerate() {
+            for asset_1 in assets.iter().skip(i + 1) {
+                let pair = Self::sort_asset_id(*asset_0, *asset_1);
+                WeightedPoolOfAssets::<T>::mutate(pair, |recorded| {
+                    if !recorded.map_or(false, Self::is_weighted_pool_liquid) {
+                        *recorded = Some(pool_id);
+                    }
+                });
+            }
+        }
+    }
+
+    /// Deposit all the assets of the pool in proportion to the reserves.
+    pub fn inner_add_weighted_liquidity(
+        who: &T::AccountId,
+        pool_id: u32,
+        max_amounts: Vec<AssetBalance>,
+        min_mint_amount: AssetBalance,
+    ) -> DispatchResult {
+        WeightedPools::<T>::try_mutate(pool_id, |pool| {
+            let pool = pool.as_mut().ok_or(Error::<T>::PoolNotExists)?;
+            ensure!(max_amounts.len() == pool.assets.len(), Error::<T>::InvalidPoolAssets);
+
+            let (amounts, mint_liquidity) = if pool.total_supply.is_zero() {
+                ensure!(
+                    max_amounts.iter().all(|amount| !amount.is_zero()),
+                    Error::<T>::InsufficientLiquidity
+                );
+
+                (max_amounts, INIT_POOL_SUPPLY)
+            } else {
+                let mint_liquidity = max_amounts
+                    .iter()
+                    .zip(pool.reserves.iter())
+                    .map(|(amount, reserve)| {
+                        Self::calculate_share_amount(*amount, *reserve, pool.total_supply)
+                    })
+                    .min()
+                    .unwrap_or_else(Zero::zero);
+
+                // Round up the amounts paid for the liquidity.
+                let amounts = pool
+                    .reserves
+                    .iter()
+                    .map(|reserve| {
+                        U256::from(*reserve)
+                            .saturating_mul(U256::from(mint_liquidity))
+                            .saturating_add(U256::from(pool.total_supply - 1))
+                            .checked_div(U256::from(pool.total_supply))
+                            .and_then(|n| TryInto::<AssetBalance>::try_into(n).ok())
+                            .unwrap_or_else(Zero::zero)
+                    })
+                    .collect::<Vec<_>>();
+
+                (amounts, mint_liquidity)
+            };
+
+            ensure!(mint_liquidity > Zero::zero(), Error::<T>::InsufficientLiquidity);
+            ensure!(mint_liquidity >= min_mint_amount, Error::<T>::InsufficientTargetAmount);
+
+            // The first liquidity of the pool takes over the swaps from the recorded pools
+            // without liquidity.
+            if pool.total_supply.is_zero() {
+                Self::note_weighted_pool_of_assets(pool_id, &pool.assets);
+            }
+
+            for (i, amount) in amounts.iter().enumerate() {
+                T::MultiAssetsHandler::transfer(pool.assets[i], who, &pool.pool_account, *amount)?;
+                pool.reserves[i] =
+                    pool.reserves[i].checked_add(*amount).ok_or(Error::<T>::Overflow)?;
+            }
+
+            Self::mint_weighted_liquidity(pool, pool_id, who, mint_liquidity)?;
+
+            Self::deposit_event(Event::WeightedLiquidityAdded(
+                who.clone(),
+                pool_id,
+                amounts,
+                mint_liquidity,
+            ));
+
+            Ok(())
+        })
+    }
+
+    /// Withdraw all the assets of the pool in proportion to the reserves.
+    pub fn inner_remove_weighted_liquidity(
+        who: &T::AccountId,
+        pool_id: u32,
+        remove_liquidity: AssetBalance,
+        min_amounts: Vec<AssetBalance>,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        WeightedPools::<T>::try_mutate(pool_id, |pool| {
+            let pool = pool.as_mut().ok_or(Error::<T>::PoolNotExists)?;
+            ensure!(min_amounts.len() == pool.assets.len(), Error::<T>::InvalidPoolAssets);
+
+            let mut amounts = Vec::with_capacity(pool.assets.len());
+            for (i, reserve) in pool.reserves.iter().enumerate() {
+                let amount =
+                    Self::calculate_share_amount(remove_liquidity, pool.total_supply, *reserve);
+                ensure!(amount >= min_amounts[i], Error::<T>::InsufficientTargetAmount);
+                amounts.push(amount);
+            }
+
+            Self::burn_weighted_liquidity(pool, pool_id, who, remove_liquidity)?;
+
+            for (i, amount) in amounts.iter().enumerate() {
+                if !amount.is_zero() {
+                    T::MultiAssetsHandler::transfer(
+                        pool.assets[i],
+                        &pool.pool_account,
+                        recipient,
+                        *amount,
+                    )?;
+                }
+                pool.reserves[i] = pool.reserves[i].saturating_sub(*amount);
+            }
+
+            Self::deposit_event(Event::WeightedLiquidityRemoved(
+                who.clone(),
+                recipient.clone(),
+                pool_id,
+                amounts,
+                remove_liquidity,
+            ));
+
+            Ok(())
+        })
+    }
+
+    /// Deposit a single asset of the pool, which is charged the exchange fee for the part
+    /// swapped into the other assets.
+    pub fn inner_add_weighted_liquidity_single_asset(
+        who: &T::AccountId,
+        pool_id: u32,
+        asset_id: AssetId,
+        amount: AssetBalance,
+        min_mint_amount: AssetBalance,
+    ) -> DispatchResult {
+        WeightedPools::<T>::try_mutate(pool_id, |pool| {
+            let pool = pool.as_mut().ok_or(Error::<T>::PoolNotExists)?;
+            let i = pool
+                .assets
+                .iter()
+                .position(|id| *id == asset_id)
+                .ok_or(Error::<T>::InvalidPoolAssets)?;
+            ensure!(!pool.total_supply.is_zero(), Error::<T>::InsufficientLiquidity);
+            ensure!(
+                U256::from(amount)
+                    <= Self::bmul(U256::from(pool.reserves[i]), U256::from(MAX_IN_RATIO)),
+                Error::<T>::ExcessiveRatio
+            );
+
+            let mint_liquidity = Self::calculate_pool_out_given_single_in(
+                U256::from(pool.reserves[i]),
+                U256::from(pool.weights[i]),
+                U256::from(pool.total_supply),
+                U256::from(amount),
+                Self::weighted_fee(pool.fee),
+            )
+            .and_then(|n| TryInto::<AssetBalance>::try_into(n).ok())
+            .ok_or(Error::<T>::Overflow)?;
+
+            ensure!(mint_liquidity > Zero::zero(), Error::<T>::InsufficientLiquidity);
+            ensure!(mint_liquidity >= min_mint_amount, Error::<T>::InsufficientTargetAmount);
+
+            T::MultiAssetsHandler::transfer(asset_id, who, &pool.pool_account, amount)?;
+            pool.reserves[i] = pool.reserves[i].checked_add(amount).ok_or(Error::<T>::Overflow)?;
+
+            Self::mint_weighted_liquidity(pool, pool_id, who, mint_liquidity)?;
+
+            let mut amounts = vec![Zero::zero(); pool.assets.len()];
+            amounts[i] = amount;
+            Self::deposit_event(Event::WeightedLiquidityAdded(
+                who.clone(),
+                pool_id,
+                amounts,
+                mint_liquidity,
+            ));
+
+            Ok(())
+        })
+    }
+
+    /// Withdraw a single asset of the pool, which is charged the exchange fee for the part
+    /// swapped from the other assets.
+    pub fn inner_remove_weighted_liquidity_single_asset(
+        who: &T::AccountId,
+        pool_id: u32,
+        asset_id: AssetId,
+        remove_liquidity: AssetBalance,
+        min_amount: AssetBalance,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        WeightedPools::<T>::try_mutate(pool_id, |pool| {
+            let pool = pool.as_mut().ok_or(Error::<T>::PoolNotExists)?;
+            let i = pool
+                .assets
+                .iter()
+                .position(|id| *id == asset_id)
+                .ok_or(Error::<T>::InvalidPoolAssets)?;
+            ensure!(remove_liquidity < pool.total_supply, Error::<T>::InsufficientLiquidity);
+
+            let amount = Self::calculate_single_out_given_pool_in(
+                U256::from(pool.reserves[i]),
+                U256::from(pool.weights[i]),
+                U256::from(pool.total_supply),
+                U256::from(remove_liquidity),
+                Self::weighted_fee(pool.fee),
+            )
+            .and_then(|n| TryInto::<AssetBalance>::try_into(n).ok())
+            .ok_or(Error::<T>::Overflow)?;
+
+            ensure!(
+                U256::from(amount)
+                    <= Self::bmul(U256::from(pool.reserves[i]), U256::from(MAX_OUT_RATIO)),
+                Error::<T>::ExcessiveRatio
+            );
+            ensure!(amount >= min_amount, Error::<T>::InsufficientTargetAmount);
+
+            Self::burn_weighted_liquidity(pool, pool_id, who, remove_liquidity)?;
+
+            T::MultiAssetsHandler::transfer(asset_id, &pool.pool_account, recipient, amount)?;
+            pool.reserves[i] = pool.reserves[i].saturating_sub(amount);
+
+            let mut amounts = vec![Zero::zero(); pool.assets.len()];
+            amounts[i] = amount;
+            Self::deposit_event(Event::WeightedLiquidityRemoved(
+                who.clone(),
+                recipient.clone(),
+                pool_id,
+                amounts,
+                remove_liquidity,
+            ));
+
+            Ok(())
+        })
+    }
+
+    fn mint_weighted_liquidity(
+        pool: &mut WeightedPool<T::AccountId>,
+        pool_id: u32,
+        who: &T::AccountId,
+        amount: AssetBalance,
+    ) -> DispatchResult {
+        pool.total_supply = pool.total_supply.checked_add(amount).ok_or(Error::<T>::Overflow)?;
+        WeightedLedger::<T>::try_mutate((pool_id, who), |liquidity| -> DispatchResult {
+            *liquidity = liquidity.checked_add(amount).ok_or(Error::<T>::Overflow)?;
+            Ok(())
+        })
+    }
+
+    fn burn_weighted_liquidity(
+        pool: &mut WeightedPool<T::AccountId>,
+        pool_id: u32,
+        who: &T::AccountId,
+        amount: AssetBalance,
+    ) -> DispatchResult {
+        pool.total_supply =
+            pool.total_supply.checked_sub(amount).ok_or(Error::<T>::InsufficientLiquidity)?;
+        WeightedLedger::<T>::try_mutate((pool_id, who), |liquidity| -> DispatchResult {
+            *liquidity = liquidity.checked_sub(amount).ok_or(Error::<T>::InsufficientLiquidity)?;
+            Ok(())
+        })
+    }
+
+    /// The amount of `asset_out` got by swapping `amount_in` of `asset_in` in the pool.
+    pub fn weighted_amount_out(
+        pool_id: u32,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: AssetBalance,
+    ) -> AssetBalance {
+        Self::weighted_pool(pool_id)
+            .and_then(|pool| {
+                let i = pool.assets.iter().position(|id| *id == asset_in)?;
+                let j = pool.assets.iter().position(|id| *id == asset_out)?;
+                let reserve_in = U256::from(pool.reserves[i]);
+                if U256::from(amount_in) > Self::bmul(reserve_in, U256::from(MAX_IN_RATIO)) {
+                    return None;
+                }
+
+                Self::calculate_out_given_in(
+                    reserve_in,
+                    U256::from(pool.weights[i]),
+                    U256::from(pool.reserves[j]),
+                    U256::from(pool.weights[j]),
+                    U256::from(amount_in),
+                    Self::weighted_fee(pool.fee),
+                )
+                .and_then(|n| TryInto::<AssetBalance>::try_into(n).ok())
+            })
+            .unwrap_or_else(Zero::zero)
+    }
+
+    /// The amount of `asset_in` needed to get `amount_out` of `asset_out` from the pool.
+    pub fn weighted_amount_in(
+        pool_id: u32,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_out: AssetBalance,
+    ) -> AssetBalance {
+        Self::weighted_pool(pool_id)
+            .and_then(|pool| {
+                let i = pool.assets.iter().position(|id| *id == asset_in)?;
+                let j = pool.assets.iter().position(|id| *id == asset_out)?;
+                let reserve_out = U256::from(pool.reserves[j]);
+                if U256::from(amount_out) > Self::bmul(reserve_out, U256::from(MAX_OUT_RATIO)) {
+                    return None;
+                }
+
+                Self::calculate_in_given_out(
+                    U256::from(pool.reserves[i]),
+                    U256::from(pool.weights[i]),
+                    reserve_out,
+                    U256::from(pool.weights[j]),
+                    U256::from(amount_out),
+                    Self::weighted_fee(pool.fee),
+                )
+                .and_then(|n| n.checked_add(U256::one()))
+                .and_then(|n| TryInto::<AssetBalance>::try_into(n).ok())
+            })
+            .unwrap_or_else(Zero::zero)
+    }
+
+    /// Send `amount_out` of `asset_out` to the recipient, `amount_in` of `asset_in` must have
+    /// been transferred to the pool account.
+    pub(crate) fn weighted_swap(
+        pool_id: u32,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: AssetBalance,
+        amount_out: AssetBalance,
+        recipient: &T::AccountId,
+    ) -> DispatchResult {
+        WeightedPools::<T>::try_mutate(pool_id, |pool| {
+            let pool = pool.as_mut().ok_or(Error::<T>::PoolNotExists)?;
+            let i =
+                pool.assets.iter().position(|id| *id == asset_in).ok_or(Error::<T>::InvalidPath)?;
+            let j = pool
+                .assets
+                .iter()
+                .position(|id| *id == asset_out)
+                .ok_or(Error::<T>::InvalidPath)?;
+
+            pool.reserves[i] =
+                pool.reserves[i].checked_add(amount_in).ok_or(Error::<T>::Overflow)?;
+            pool.reserves[j] = pool.reserves[j]
+                .checked_sub(amount_out)
+                .ok_or(Error::<T>::InsufficientPairReserve)?;
+
+            T::MultiAssetsHandler::transfer(asset_out, &pool.pool_account, recipient, amount_out)
+        })
+    }
+
+    /// The spot price of `asset_in` quoted in `asset_out` without the exchange fee, in `BONE`.
+    pub fn weighted_spot_price(pool_id: u32, asset_in: AssetId, asset_out: AssetId) -> U256 {
+        Self::weighted_pool(pool_id)
+            .and_then(|pool| {
+                let i = pool.assets.iter().position(|id| *id == asset_in)?;
+                let j = pool.assets.iter().position(|id| *id == asset_out)?;
+
+                let numerator =
+                    Self::bdiv(U256::from(pool.reserves[j]), U256::from(pool.weights[j]))?;
+                let denominator =
+                    Self::bdiv(U256::from(pool.reserves[i]), U256::from(pool.weights[i]))?;
+
+                Self::bdiv(numerator, denominator)
+            })
+            .unwrap_or_else(U256::zero)
+    }
+
+    pub(crate) fn weighted_fee((fee_numerator, fee_denominator): (u32, u32)) -> U256 {
+        U256::from(BONE) * U256::from(fee_numerator) / U256::from(fee_denominator)
+    }
+
+    // Weighted math in fixed point of `BONE`
+
+    pub(crate) fn calculate_out_given_in(
+        reserve_in: U256,
+        weight_in: U256,
+        reserve_out: U256,
+        weight_out: U256,
+        amount_in: U256,
+        fee: U256,
+    ) -> Option<U256> {
+        let bone = U256::from(BONE);
+        let weight_ratio = Self::bdiv(weight_in, weight_out)?;
+        let adjusted_in = Self::bmul(amount_in, bone.checked_sub(fee)?);
+        let y = Self::bdiv(reserve_in, reserve_in.checked_add(adjusted_in)?)?;
+        let foo = Self::bpow(y, weight_ratio)?;
+        let bar = bone.checked_sub(foo)?;
+
+        Some(Self::bmul(reserve_out, bar))
+    }
+
+    pub(crate) fn calculate_in_given_out(
+        reserve_in: U256,
+        weight_in: U256,
+        reserve_out: U256,
+        weight_out: U256,
+        amount_out: U256,
+        fee: U256,
+    ) -> Option<U256> {
+        let bone = U256::from(BONE);
+        let weight_ratio = Self::bdiv(weight_out, weight_in)?;
+        let y = Self::bdiv(reserve_out, reserve_out.checked_sub(amount_out)?)?;
+        let foo = Self::bpow(y, weight_ratio)?.checked_sub(bone)?;
+        let amount_in = Self::bmul(reserve_in, foo);
+
+        Self::bdiv(amount_in, bone.checked_sub(fee)?)
+    }
+
+    pub(crate) fn calculate_pool_out_given_single_in(
+        reserve_in: U256,
+        weight_in: U256,
+        total_supply: U256,
+        amount_in: U256,
+        fee: U256,
+    ) -> Option<U256> {
+        let bone = U256::from(BONE);
+        // The part of the deposit swapped into the other assets is charged the fee.
+        let zaz = Self::bmul(bone.checked_sub(weight_in)?, fee);
+        let amount_in_after_fee = Self::bmul(amount_in, bone.checked_sub(zaz)?);
+        let new_reserve_in = reserve_in.checked_add(amount_in_after_fee)?;
+        let reserve_in_ratio = Self::bdiv(new_reserve_in, reserve_in)?;
+
+        let pool_ratio = Self::bpow(reserve_in_ratio, weight_in)?;
+        let new_total_supply = Self::bmul(pool_ratio, total_supply);
+
+        new_total_supply.checked_sub(total_supply)
+    }
+
+    pub(crate) fn calculate_single_out_given_pool_in(
+        reserve_out: U256,
+        weight_out: U256,
+        total_supply: U256,
+        pool_amount_in: U256,
+        fee: U256,
+    ) -> Option<U256> {
+        let bone = U256::from(BONE);
+        let new_total_supply = total_supply.checked_sub(pool_amount_in)?;
+        let pool_ratio = Self::bdiv(new_total_supply, total_supply)?;
+
+        let reserve_out_ratio = Self::bpow(pool_ratio, Self::bdiv(bone, weight_out)?)?;
+        let new_reserve_out = Self::bmul(reserve_out_ratio, reserve_out);
+        let amount_out_before_fee = reserve_out.checked_sub(new_reserve_out)?;
+
+        // The part of the withdrawal swapped from the other assets is charged the fee.
+        let zaz = Self::bmul(bone.checked_sub(weight_out)?, fee);
+
+        Some(Self::bmul(amount_out_before_fee, bone.checked_sub(zaz)?))
+    }
+
+    pub(crate) fn bmul(a: U256, b: U256) -> U256 {
+        a.saturating_mul(b).saturating_add(U256::from(BONE / 2)) / U256::from(BONE)
+    }
+
+    pub(crate) fn bdiv(a: U256, b: U256) -> Option<U256> {
+        if b.is_zero() {
+            return None;
+        }
+
+        Some(a.saturating_mul(U256::from(BONE)).saturating_add(b / 2) / b)
+    }
+
+    /// `base` to the power of the integer `exponent`, both in `BONE`.
+    fn bpowi(base: U256, exponent: U256) -> U256 {
+        let mut base = base;
+        let mut exponent = exponent;
+        let mut result = if exponent % 2 != U256::zero() { base } else { U256::from(BONE) };
+
+        exponent /= 2;
+        while !exponent.is_zero() {
+            base = Self::bmul(base, base);
+            if exponent % 2 != U256::zero() {
+                result = Self::bmul(result, base);
+            }
+            exponent /= 2;
+        }
+
+        result
+    }
+
+    /// `base` to the power of `exponent`, both in `BONE`, by the integer part of the exponent
+    /// and the binomial series of the fractional part.
+    pub(crate) fn bpow(base: U256, exponent: U256) -> Option<U256> {
+        let bone = U256::from(BONE);
+        if base.is_zero() || base >= bone * 2 {
+            return None;
+        }
+
+        let whole = exponent / bone;
+        let remain = exponent % bone;
+        let whole_pow = Self::bpowi(base, whole);
+        if remain.is_zero() {
+            return Some(whole_pow);
+        }
+
+        let partial = Self::bpow_approx(base, remain)?;
+
+        Some(Self::bmul(whole_pow, partial))
+    }
+
+    fn bpow_approx(base: U256, exponent: U256) -> Option<U256> {
+        let bone = U256::from(BONE);
+        let (x, x_negative) = if base >= bone { (base - bone, false) } else { (bone - base, true) };
+
+        let mut term = bone;
+        let mut sum = term;
+        let mut negative = false;
+
+        for i in 1..=MAX_BPOW_ITERATIONS {
+            let big_k = U256::from(i) * bone;
+            let (c, c_negative) = if exponent >= big_k - bone {
+                (exponent - (big_k - bone), false)
+            } else {
+                ((big_k - bone) - exponent, true)
+            };
+            term = Self::bmul(term, Self::bmul(c, x));
+            term = Self::bdiv(term, big_k)?;
+            if term.is_zero() {
+                break;
+            }
+
+            if x_negative {
+                negative = !negative;
+            }
+            if c_negative {
+                negative = !negative;
+            }
+            if negative {
+                sum = sum.checked_sub(term)?;
+            } else {
+                sum = sum.checked_add(term)?;
+            }
+
+            if term < U256::from(BPOW_PRECISION) {
+                break;
+            }
+        }
+
+        Some(sum)
+    }
+
+    // Liquidity asset of the weighted pools
+
+    pub(crate) fn weighted_lp_transfer(
+        id: AssetId,
+        pool_id: u32,
+        owner: &T::AccountId,
+        target: &T::AccountId,
+        amount: AssetBalance,
+    ) -> DispatchResult {
+        ensure!(WeightedPools::<T>::contains_key(pool_id), Error::<T>::AssetNotExists);
+
+        let owner_balance = Self::weighted_ledger((pool_id, owner));
+        ensure!(owner_balance >= amount, Error::<T>::InsufficientAssetBalance);
+
+        WeightedLedger::<T>::insert((pool_id, owner), owner_balance.saturating_sub(amount));
+        WeightedLedger::<T>::mutate((pool_id, target), |balance| {
+            *balance = balance.saturating_add(amount)
+        });
+
+        Self::deposit_event(Event::Transferred(id, owner.clone(), target.clone(), amount));
+
+        Ok(())
+    }
+
+    pub(crate) fn weighted_lp_mint(
+        id: AssetId,
+        pool_id: u32,
+        owner: &T::AccountId,
+        amount: AssetBalance,
+    ) -> DispatchResult {
+        WeightedPools::<T>::try_mutate(pool_id, |pool| -> DispatchResult {
+            let pool = pool.as_mut().ok_or(Error::<T>::AssetNotExists)?;
+
+            Self::mint_weighted_liquidity(pool, pool_id, owner, amount)
+        })?;
+
+        Self::deposit_event(Event::Minted(id, owner.clone(), amount));
+
+        Ok(())
+    }
+
+    pub(crate) fn weighted_lp_burn(
+        id: AssetId,
+        pool_id: u32,
+        owner: &T::AccountId,
+        amount: AssetBalance,
+    ) -> DispatchResult {
+        WeightedPools::<T>::try_mutate(pool_id, |pool| -> DispatchResult {
+            let pool = pool.as_mut().ok_or(Error::<T>::AssetNotExists)?;
+
+            Self::burn_weighted_liquidity(pool, pool_id, owner, amount)
+        })?;
+
+        Self::deposit_event(Event::Burned(id, owner.clone(), amount));
+
+        Ok(())
+    }
+}
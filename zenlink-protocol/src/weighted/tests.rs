@@ -0,0 +1,391 @@
+// Copyright 2020-2021 Zenlink
+// Licensed under GPL-3.0.
+
+use frame_support::{assert_noop, assert_ok};
+use sp_core::U256;
+use sp_runtime::DispatchError;
+
+use super::{AssetId, Error, Hop, MultiAssetsHandler, BONE};
+use crate::mock::*;
+
+const DOT_ASSET_ID: AssetId = AssetId { chain_id: 200, asset_type: LOCAL, asset_index: 2 };
+
+const BTC_ASSET_ID: AssetId = AssetId { chain_id: 300, asset_type: RESERVED, asset_index: 3 };
+
+const ALICE: u128 = 1;
+const BOB: u128 = 2;
+
+const DOT_UNIT: u128 = 1000_000_000_000_000;
+const BTC_UNIT: u128 = 1000_000_00;
+
+fn create_dot_btc_pool() {
+    assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX / 2));
+    assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX / 2));
+    assert_ok!(DexPallet::create_weighted_pool(
+        Origin::root(),
+        vec![DOT_ASSET_ID, BTC_ASSET_ID],
+        vec![80, 20],
+        3,
+        1000
+    ));
+    assert_ok!(DexPallet::add_weighted_liquidity(
+        Origin::signed(ALICE),
+        0,
+        vec![8000 * DOT_UNIT, 100 * BTC_UNIT],
+        0,
+        Deadline::BlockNumber(100)
+    ));
+}
+
+#[test]
+fn create_weighted_pool_should_work() {
+    new_test_ext().execute_with(|| {
+        create_dot_btc_pool();
+
+        let pool = DexPallet::weighted_pool(0).unwrap();
+        assert_eq!(pool.assets, vec![DOT_ASSET_ID, BTC_ASSET_ID]);
+        assert_eq!(pool.weights, vec![BONE * 4 / 5, BONE / 5]);
+        assert_eq!(pool.reserves, vec![8000 * DOT_UNIT, 100 * BTC_UNIT]);
+        assert_eq!(pool.total_supply, 100 * BONE);
+        assert_eq!(DexPallet::next_weighted_pool_id(), 1);
+        assert_eq!(DexPallet::weighted_pool_of_assets((DOT_ASSET_ID, BTC_ASSET_ID)), Some(0));
+
+        // (100 BTC / 0.2) / (8000 DOT / 0.8)
+        assert_eq!(
+            DexPallet::weighted_spot_price(0, DOT_ASSET_ID, BTC_ASSET_ID),
+            U256::from(5_000_000_000u128)
+        );
+
+        let lp_asset_id = DexPallet::weighted_lp_asset_id(0);
+        assert!(<Test as Config>::MultiAssetsHandler::is_exists(lp_asset_id));
+        assert_eq!(<Test as Config>::MultiAssetsHandler::total_supply(lp_asset_id), 100 * BONE);
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(lp_asset_id, &ALICE),
+            100 * BONE
+        );
+
+        assert_ok!(<Test as Config>::MultiAssetsHandler::transfer(lp_asset_id, &ALICE, &BOB, BONE));
+        assert_eq!(DexPallet::weighted_ledger((0, BOB)), BONE);
+    });
+}
+
+#[test]
+fn create_weighted_pool_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, DOT_UNIT));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, BTC_UNIT));
+
+        assert_noop!(
+            DexPallet::create_weighted_pool(
+                Origin::signed(ALICE),
+                vec![DOT_ASSET_ID, BTC_ASSET_ID],
+                vec![80, 20],
+                3,
+                1000
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            DexPallet::create_weighted_pool(Origin::root(), vec![DOT_ASSET_ID], vec![1], 3, 1000),
+            Error::<Test>::InvalidPoolAssets
+        );
+        assert_noop!(
+            DexPallet::create_weighted_pool(
+                Origin::root(),
+                vec![DOT_ASSET_ID, BTC_ASSET_ID],
+                vec![80],
+                3,
+                1000
+            ),
+            Error::<Test>::InvalidWeights
+        );
+        assert_noop!(
+            DexPallet::create_weighted_pool(
+                Origin::root(),
+                vec![DOT_ASSET_ID, BTC_ASSET_ID],
+                vec![99, 1],
+                3,
+                1000
+            ),
+            Error::<Test>::InvalidWeights
+        );
+        assert_noop!(
+            DexPallet::create_weighted_pool(
+                Origin::root(),
+                vec![DOT_ASSET_ID, BTC_ASSET_ID],
+                vec![80, 20],
+                1000,
+                1000
+            ),
+            Error::<Test>::InvalidExchangeFee
+        );
+    });
+}
+
+#[test]
+fn weighted_liquidity_should_work() {
+    new_test_ext().execute_with(|| {
+        create_dot_btc_pool();
+
+        assert_noop!(
+            DexPallet::add_weighted_liquidity(
+                Origin::signed(ALICE),
+                0,
+                vec![800 * DOT_UNIT, 100 * BTC_UNIT],
+                10 * BONE + 1,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientTargetAmount
+        );
+
+        // Only the amounts in proportion to the reserves are taken.
+        let dot_balance = <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &ALICE);
+        let btc_balance = <Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &ALICE);
+        assert_ok!(DexPallet::add_weighted_liquidity(
+            Origin::signed(ALICE),
+            0,
+            vec![800 * DOT_UNIT, 100 * BTC_UNIT],
+            10 * BONE,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(DexPallet::weighted_ledger((0, ALICE)), 110 * BONE);
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &ALICE),
+            dot_balance - 800 * DOT_UNIT
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &ALICE),
+            btc_balance - 10 * BTC_UNIT
+        );
+
+        assert_ok!(DexPallet::remove_weighted_liquidity(
+            Origin::signed(ALICE),
+            0,
+            10 * BONE,
+            vec![800 * DOT_UNIT, 10 * BTC_UNIT],
+            BOB,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(DOT_ASSET_ID, &BOB),
+            800 * DOT_UNIT
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &BOB),
+            10 * BTC_UNIT
+        );
+
+        let pool = DexPallet::weighted_pool(0).unwrap();
+        assert_eq!(pool.reserves, vec![8000 * DOT_UNIT, 100 * BTC_UNIT]);
+        assert_eq!(pool.total_supply, 100 * BONE);
+
+        assert_noop!(
+            DexPallet::remove_weighted_liquidity(
+                Origin::signed(BOB),
+                0,
+                1,
+                vec![0, 0],
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientLiquidity
+        );
+    });
+}
+
+#[test]
+fn weighted_single_asset_liquidity_should_work() {
+    new_test_ext().execute_with(|| {
+        create_dot_btc_pool();
+
+        assert_noop!(
+            DexPallet::add_weighted_liquidity_single_asset(
+                Origin::signed(ALICE),
+                0,
+                DOT_ASSET_ID,
+                4001 * DOT_UNIT,
+                0,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::ExcessiveRatio
+        );
+
+        assert_ok!(DexPallet::add_weighted_liquidity_single_asset(
+            Origin::signed(ALICE),
+            0,
+            DOT_ASSET_ID,
+            800 * DOT_UNIT,
+            0,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(DexPallet::weighted_ledger((0, ALICE)), 100 * BONE + 7918325135087688500);
+        let pool = DexPallet::weighted_pool(0).unwrap();
+        assert_eq!(pool.reserves, vec![8800 * DOT_UNIT, 100 * BTC_UNIT]);
+        assert_eq!(pool.total_supply, 100 * BONE + 7918325135087688500);
+
+        assert_noop!(
+            DexPallet::remove_weighted_liquidity_single_asset(
+                Origin::signed(ALICE),
+                0,
+                BTC_ASSET_ID,
+                BONE,
+                453714635,
+                BOB,
+                Deadline::BlockNumber(100)
+            ),
+            Error::<Test>::InsufficientTargetAmount
+        );
+        assert_ok!(DexPallet::remove_weighted_liquidity_single_asset(
+            Origin::signed(ALICE),
+            0,
+            BTC_ASSET_ID,
+            BONE,
+            453714634,
+            BOB,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(<Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &BOB), 453714634);
+        let pool = DexPallet::weighted_pool(0).unwrap();
+        assert_eq!(pool.reserves, vec![8800 * DOT_UNIT, 100 * BTC_UNIT - 453714634]);
+        assert_eq!(pool.total_supply, 99 * BONE + 7918325135087688500);
+    });
+}
+
+#[test]
+fn swap_through_weighted_pool_should_work() {
+    new_test_ext().execute_with(|| {
+        create_dot_btc_pool();
+
+        let path = vec![DOT_ASSET_ID, BTC_ASSET_ID];
+        assert_eq!(DexPallet::supply_out_amount(10 * DOT_UNIT, path.clone()), 49695072);
+        assert_eq!(DexPallet::desired_in_amount(BTC_UNIT, path.clone()), 20186504696783704);
+        assert_eq!(
+            DexPallet::supply_out_amount(BTC_UNIT, vec![BTC_ASSET_ID, DOT_ASSET_ID]),
+            19816670499658032
+        );
+        // More than half of the reserve in.
+        assert_eq!(DexPallet::supply_out_amount(4001 * DOT_UNIT, path.clone()), 0);
+
+        assert_ok!(DexPallet::inner_swap_exact_assets_for_assets(
+            &ALICE,
+            10 * DOT_UNIT,
+            0,
+            &path,
+            &BOB
+        ));
+        assert_eq!(<Test as Config>::MultiAssetsHandler::balance_of(BTC_ASSET_ID, &BOB), 49695072);
+        assert_eq!(
+            DexPallet::weighted_pool(0).unwrap().reserves,
+            vec![8010 * DOT_UNIT, 100 * BTC_UNIT - 49695072]
+        );
+        assert_eq!(
+            <Test as Config>::MultiAssetsHandler::balance_of(
+                BTC_ASSET_ID,
+                &DexPallet::weighted_pool_account_id(0)
+            ),
+            100 * BTC_UNIT - 49695072
+        );
+    });
+}
+
+#[test]
+fn liquid_weighted_pool_should_take_precedence_over_empty_stable_pool() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, DOT_UNIT));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, BTC_UNIT));
+        assert_ok!(DexPallet::create_stable_pool(
+            Origin::root(),
+            vec![DOT_ASSET_ID, BTC_ASSET_ID],
+            vec![1, 10_000_000],
+            100,
+            4,
+            10000
+        ));
+        assert_eq!(DexPallet::get_hop(DOT_ASSET_ID, BTC_ASSET_ID), Some(Hop::Stable(0)));
+
+        create_dot_btc_pool();
+        assert_eq!(DexPallet::get_hop(DOT_ASSET_ID, BTC_ASSET_ID), Some(Hop::Weighted(0)));
+    });
+}
+
+#[test]
+fn weighted_pool_should_not_reroute_pair_path() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DexPallet::foreign_mint(DOT_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::foreign_mint(BTC_ASSET_ID, &ALICE, u128::MAX / 2));
+        assert_ok!(DexPallet::create_pair(Origin::signed(ALICE), DOT_ASSET_ID, BTC_ASSET_ID));
+        assert_ok!(DexPallet::inner_add_liquidity(
+            &ALICE,
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            1000 * DOT_UNIT,
+            1000 * BTC_UNIT,
+            0,
+            0
+        ));
+
+        for _ in 0..2 {
+            assert_ok!(DexPallet::create_weighted_pool(
+                Origin::root(),
+                vec![DOT_ASSET_ID, BTC_ASSET_ID],
+                vec![80, 20],
+                3,
+                1000
+            ));
+        }
+        assert_eq!(DexPallet::get_hop(DOT_ASSET_ID, BTC_ASSET_ID), Some(Hop::Pair));
+
+        // The first pool with liquidity is recorded for the assets.
+        assert_ok!(DexPallet::add_weighted_liquidity(
+            Origin::signed(ALICE),
+            1,
+            vec![8000 * DOT_UNIT, 100 * BTC_UNIT],
+            0,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(DexPallet::weighted_pool_of_assets((DOT_ASSET_ID, BTC_ASSET_ID)), Some(1));
+
+        // The liquid pool doesn't take over the path of the trading pair.
+        assert_eq!(DexPallet::get_hop(BTC_ASSET_ID, DOT_ASSET_ID), Some(Hop::Pair));
+        assert_ok!(DexPallet::inner_swap_exact_assets_for_assets(
+            &ALICE,
+            DOT_UNIT,
+            0,
+            &[DOT_ASSET_ID, BTC_ASSET_ID],
+            &BOB
+        ));
+        assert_eq!(DexPallet::get_reserves(DOT_ASSET_ID, BTC_ASSET_ID).0, 1001 * DOT_UNIT);
+
+        // The path keeps the maximum price impact of the pair.
+        assert_ok!(DexPallet::set_pair_max_price_impact(
+            Origin::root(),
+            DOT_ASSET_ID,
+            BTC_ASSET_ID,
+            Some((1, 100))
+        ));
+        assert_noop!(
+            DexPallet::inner_swap_exact_assets_for_assets(
+                &ALICE,
+                100 * DOT_UNIT,
+                0,
+                &[DOT_ASSET_ID, BTC_ASSET_ID],
+                &BOB
+            ),
+            Error::<Test>::ExcessivePriceImpact
+        );
+
+        // The pool is reached by naming the hop.
+        let pool_reserve = DexPallet::weighted_pool(1).unwrap().reserves[0];
+        assert_ok!(DexPallet::swap_exact_assets_for_assets_through_hops(
+            Origin::signed(ALICE),
+            100 * DOT_UNIT,
+            0,
+            vec![DOT_ASSET_ID, BTC_ASSET_ID],
+            vec![Hop::Weighted(1)],
+            BOB,
+            Deadline::BlockNumber(100)
+        ));
+        assert_eq!(DexPallet::get_reserves(DOT_ASSET_ID, BTC_ASSET_ID).0, 1001 * DOT_UNIT);
+        assert_eq!(DexPallet::weighted_pool(1).unwrap().reserves[0], pool_reserve + 100 * DOT_UNIT);
+    });
+}